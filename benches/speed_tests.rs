@@ -5,7 +5,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use prio::benchmarked::*;
 use prio::client::Client;
 use prio::encrypt::PublicKey;
-use prio::field::{rand, Field126 as F, FieldElement};
+use prio::field::{rand, Field126 as F, Field128, Field64, FieldElement};
 use prio::pcp::gadgets::Mul;
 use prio::pcp::types::{MeanVarUnsignedVector, PolyCheckedVector};
 use prio::pcp::{prove, query, Value};
@@ -26,12 +26,39 @@ pub fn fft(c: &mut Criterion) {
 
         c.bench_function(&format!("recursive FFT, size={}", *size), |b| {
             b.iter(|| {
-                benchmarked_recursive_fft(&mut outp, &inp);
+                benchmarked_recursive_fft(&mut outp, &inp).unwrap();
             })
         });
     }
 }
 
+/// This benchmark compares the performance of the iterative FFT with radix-4 butterflies against
+/// the same FFT with only radix-2 butterflies.
+pub fn fft_radix4(c: &mut Criterion) {
+    fn bench<F: FieldElement>(c: &mut Criterion, field: &str) {
+        let test_sizes = [256, 4096, 65536];
+        for size in test_sizes.iter() {
+            let inp: Vec<F> = rand(*size).unwrap();
+            let mut outp = vec![F::zero(); *size];
+
+            c.bench_function(&format!("{} radix-4 FFT, size={}", field, *size), |b| {
+                b.iter(|| {
+                    benchmarked_iterative_fft(&mut outp, &inp);
+                })
+            });
+
+            c.bench_function(&format!("{} radix-2 FFT, size={}", field, *size), |b| {
+                b.iter(|| {
+                    benchmarked_iterative_radix2_fft(&mut outp, &inp);
+                })
+            });
+        }
+    }
+
+    bench::<Field64>(c, "Field64");
+    bench::<Field128>(c, "Field128");
+}
+
 /// Speed test for generating a seed and deriving a pseudorandom sequence of field elements.
 pub fn prng(c: &mut Criterion) {
     let test_sizes = [16, 256, 1024, 4096];
@@ -99,7 +126,7 @@ pub fn bool_vec(c: &mut Criterion) {
         );
 
         let data_and_proof = benchmarked_v2_prove(&data, &mut client);
-        let mut validator: ValidationMemory<F> = ValidationMemory::new(data.len());
+        let mut validator: ValidationMemory<F> = ValidationMemory::new(data.len()).unwrap();
         let eval_at = rand(1).unwrap()[0];
 
         c.bench_function(&format!("bool vec v2 query, size={}", *size), |b| {
@@ -175,5 +202,13 @@ pub fn mean_var_int_vec(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bool_vec, mean_var_int_vec, poly_mul, prng, fft);
+criterion_group!(
+    benches,
+    bool_vec,
+    mean_var_int_vec,
+    poly_mul,
+    prng,
+    fft,
+    fft_radix4
+);
 criterion_main!(benches);
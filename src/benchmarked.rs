@@ -4,8 +4,8 @@
 //! benchmark, but which we don't want to expose in the public API.
 
 use crate::client::Client;
-use crate::fft::discrete_fourier_transform;
-use crate::field::FieldElement;
+use crate::fft::{discrete_fourier_transform, discrete_fourier_transform_radix2};
+use crate::field::{FieldElement, FieldElementWithInteger, FieldError};
 use crate::pcp::gadgets::Mul;
 use crate::pcp::PcpError;
 use crate::polynomial::{poly_fft, PolyAuxMemory};
@@ -15,9 +15,18 @@ pub fn benchmarked_iterative_fft<F: FieldElement>(outp: &mut [F], inp: &[F]) {
     discrete_fourier_transform(outp, inp, inp.len()).unwrap();
 }
 
+/// Sets `outp` to the Discrete Fourier Transform (DFT) using an iterative FFT algorithm with only
+/// radix-2 butterflies.
+pub fn benchmarked_iterative_radix2_fft<F: FieldElement>(outp: &mut [F], inp: &[F]) {
+    discrete_fourier_transform_radix2(outp, inp, inp.len()).unwrap();
+}
+
 /// Sets `outp` to the Discrete Fourier Transform (DFT) using a recursive FFT algorithm.
-pub fn benchmarked_recursive_fft<F: FieldElement>(outp: &mut [F], inp: &[F]) {
-    let mut mem = PolyAuxMemory::new(inp.len() / 2);
+pub fn benchmarked_recursive_fft<F: FieldElementWithInteger>(
+    outp: &mut [F],
+    inp: &[F],
+) -> Result<(), FieldError> {
+    let mut mem = PolyAuxMemory::new(inp.len() / 2)?;
     poly_fft(
         outp,
         inp,
@@ -25,7 +34,8 @@ pub fn benchmarked_recursive_fft<F: FieldElement>(outp: &mut [F], inp: &[F]) {
         inp.len(),
         false,
         &mut mem.fft_memory,
-    )
+    );
+    Ok(())
 }
 
 /// Sets `outp` to `inp[0] * inp[1]`, where `inp[0]` and `inp[1]` are polynomials. This function
@@ -49,7 +59,10 @@ pub fn benchmarked_gadget_mul_call_poly_direct<F: FieldElement>(
 }
 
 /// Returns a Prio v2 proof that `data` is a valid boolean vector.
-pub fn benchmarked_v2_prove<F: FieldElement>(data: &[F], client: &mut Client<F>) -> Vec<F> {
+pub fn benchmarked_v2_prove<F: FieldElementWithInteger>(
+    data: &[F],
+    client: &mut Client<F>,
+) -> Vec<F> {
     let copy_data = |share_data: &mut [F]| {
         share_data[..].clone_from_slice(data);
     };
@@ -5,14 +5,15 @@
 
 use crate::{
     encrypt::{encrypt_share, EncryptError, PublicKey},
-    field::FieldElement,
+    field::{split_compressed_in_place, FieldElement, FieldElementWithInteger},
     polynomial::{poly_fft, PolyAuxMemory},
-    prng::Prng,
-    util::{proof_length, serialize, unpack_proof_mut},
+    prng::{Prng, SEED_LENGTH},
+    util::{
+        serialize_with_header, write_header, DimensionError, ProofDimensions, ProofLayout,
+        HEADER_LEN,
+    },
 };
 
-use std::convert::TryFrom;
-
 /// The main object that can be used to create Prio shares
 ///
 /// Client is used to create Prio shares.
@@ -25,8 +26,7 @@ pub struct Client<F: FieldElement> {
     evals_f: Vec<F>,
     evals_g: Vec<F>,
     poly_mem: PolyAuxMemory<F>,
-    public_key1: PublicKey,
-    public_key2: PublicKey,
+    public_keys: Vec<PublicKey>,
 }
 
 /// Errors that might be emitted by the client.
@@ -40,6 +40,14 @@ pub enum ClientError {
     /// ssytem's addressible memory.
     #[error("input size exceeds field capacity")]
     InputSizeExceedsMemoryCapacity,
+    /// This error is output by `Client<F>::with_public_keys()` if fewer than two public keys are
+    /// given.
+    #[error("at least two servers are required")]
+    TooFewServers,
+    /// This error is output by `Client<F>::encode_simple()` and `Client<F>::encode_with()` if the
+    /// client was constructed for more than two servers. Use `Client<F>::encode_shares()` instead.
+    #[error("client is not configured for two servers")]
+    NotTwoServers,
     /// Encryption/decryption error
     #[error("encryption/decryption error")]
     Encrypt(#[from] EncryptError),
@@ -48,23 +56,39 @@ pub enum ClientError {
     GetRandom(#[from] getrandom::Error),
 }
 
-impl<F: FieldElement> Client<F> {
-    /// Construct a new Prio client
+impl<F: FieldElementWithInteger> Client<F> {
+    /// Construct a new Prio client for two servers
     pub fn new(
         dimension: usize,
         public_key1: PublicKey,
         public_key2: PublicKey,
     ) -> Result<Self, ClientError> {
-        let n = (dimension + 1).next_power_of_two();
-
-        if let Ok(size) = F::Integer::try_from(2 * n) {
-            if size > F::generator_order() {
-                return Err(ClientError::InputSizeExceedsFieldCapacity);
-            }
-        } else {
-            return Err(ClientError::InputSizeExceedsMemoryCapacity);
+        Self::with_public_keys(dimension, vec![public_key1, public_key2])
+    }
+
+    /// Construct a new Prio client that splits each input among `public_keys.len()` servers, where
+    /// share `i` is encrypted with `public_keys[i]`. At least two servers are required. The input
+    /// stays private as long as at least one of the servers does not collude with the others.
+    pub fn with_public_keys(
+        dimension: usize,
+        public_keys: Vec<PublicKey>,
+    ) -> Result<Self, ClientError> {
+        if public_keys.len() < 2 {
+            return Err(ClientError::TooFewServers);
         }
 
+        let n = ProofDimensions::<F>::new(dimension)
+            .map_err(|e| match e {
+                DimensionError::TooLarge(_) => ClientError::InputSizeExceedsMemoryCapacity,
+                DimensionError::ExceedsFieldCapacity { .. } => {
+                    ClientError::InputSizeExceedsFieldCapacity
+                }
+            })?
+            .num_points();
+
+        let poly_mem =
+            PolyAuxMemory::new(n).map_err(|_| ClientError::InputSizeExceedsFieldCapacity)?;
+
         Ok(Client {
             prng: Prng::new()?,
             dimension,
@@ -72,47 +96,94 @@ impl<F: FieldElement> Client<F> {
             points_g: vec![F::zero(); n],
             evals_f: vec![F::zero(); 2 * n],
             evals_g: vec![F::zero(); 2 * n],
-            poly_mem: PolyAuxMemory::new(n),
-            public_key1,
-            public_key2,
+            poly_mem,
+            public_keys,
         })
     }
 
+    /// Returns the number of servers that inputs are split among.
+    pub fn num_servers(&self) -> usize {
+        self.public_keys.len()
+    }
+
     /// Construct a pair of encrypted shares based on the input data.
+    ///
+    /// Returns `ClientError::NotTwoServers` if the client was constructed for more than two
+    /// servers; use [`Client::encode_shares`] in that case.
     pub fn encode_simple(&mut self, data: &[F]) -> Result<(Vec<u8>, Vec<u8>), ClientError> {
         let copy_data = |share_data: &mut [F]| {
             share_data[..].clone_from_slice(data);
         };
-        Ok(self.encode_with(copy_data)?)
+        self.encode_with(copy_data)
     }
 
     /// Construct a pair of encrypted shares using a initilization function.
     ///
     /// This might be slightly more efficient on large vectors, because one can
     /// avoid copying the input data.
-    pub fn encode_with<G>(&mut self, init_function: G) -> Result<(Vec<u8>, Vec<u8>), EncryptError>
+    ///
+    /// Returns `ClientError::NotTwoServers` if the client was constructed for more than two
+    /// servers; use [`Client::encode_shares_with`] in that case.
+    pub fn encode_with<G>(&mut self, init_function: G) -> Result<(Vec<u8>, Vec<u8>), ClientError>
+    where
+        G: FnOnce(&mut [F]),
+    {
+        if self.num_servers() != 2 {
+            return Err(ClientError::NotTwoServers);
+        }
+        let mut shares = self.encode_shares_with(init_function)?;
+        let share2 = shares.pop().unwrap();
+        let share1 = shares.pop().unwrap();
+        Ok((share1, share2))
+    }
+
+    /// Construct one encrypted share of the input data per server.
+    pub fn encode_shares(&mut self, data: &[F]) -> Result<Vec<Vec<u8>>, ClientError> {
+        let copy_data = |share_data: &mut [F]| {
+            share_data[..].clone_from_slice(data);
+        };
+        Ok(self.encode_shares_with(copy_data)?)
+    }
+
+    /// Construct one encrypted share per server using a initilization function.
+    ///
+    /// The first share holds the share of the proof in full and is meant for the server
+    /// constructed with `is_first_server` set. Each of the other shares only holds a PRNG seed,
+    /// from which the server expands its share of the proof.
+    pub fn encode_shares_with<G>(&mut self, init_function: G) -> Result<Vec<Vec<u8>>, EncryptError>
     where
         G: FnOnce(&mut [F]),
     {
         let mut proof = self.prove_with(init_function);
 
-        // use prng to share the proof: share2 is the PRNG seed, and proof is mutated
+        // use prng to share the proof: the other shares are PRNG seeds, and proof is mutated
         // in-place
-        let share2 = crate::prng::secret_share(&mut proof)?;
-        let share1 = serialize(&proof);
+        let seeds = split_compressed_in_place(&mut proof, self.num_servers() - 1)?;
+        let mut shares = Vec::with_capacity(self.num_servers());
+        shares.push(serialize_with_header(self.dimension, &proof));
+        for seed in seeds.iter() {
+            let mut share = Vec::with_capacity(HEADER_LEN + SEED_LENGTH);
+            write_header::<F>(&mut share, self.dimension);
+            seed.append_to(&mut share);
+            shares.push(share);
+        }
+
         // encrypt shares with respective keys
-        let encrypted_share1 = encrypt_share(&share1, &self.public_key1)?;
-        let encrypted_share2 = encrypt_share(&share2, &self.public_key2)?;
-        Ok((encrypted_share1, encrypted_share2))
+        shares
+            .iter()
+            .zip(self.public_keys.iter())
+            .map(|(share, key)| encrypt_share(share, key))
+            .collect()
     }
 
     pub(crate) fn prove_with<G>(&mut self, init_function: G) -> Vec<F>
     where
         G: FnOnce(&mut [F]),
     {
-        let mut proof = vec![F::zero(); proof_length(self.dimension)];
+        let layout = ProofLayout::new::<F>(self.dimension);
+        let mut proof = vec![F::zero(); layout.proof_length()];
         // unpack one long vector to different subparts
-        let mut unpacked = unpack_proof_mut(&mut proof, self.dimension).unwrap();
+        let mut unpacked = layout.unpack_mut(&mut proof).unwrap();
         // initialize the data part
         init_function(&mut unpacked.data);
         // fill in the rest
@@ -132,7 +203,7 @@ impl<F: FieldElement> Client<F> {
 
 /// Convenience function if one does not want to reuse
 /// [`Client`](struct.Client.html).
-pub fn encode_simple<F: FieldElement>(
+pub fn encode_simple<F: FieldElementWithInteger>(
     data: &[F],
     public_key1: PublicKey,
     public_key2: PublicKey,
@@ -181,7 +252,7 @@ fn construct_proof<F: FieldElement>(
     points_h_packed: &mut [F],
     mem: &mut Client<F>,
 ) {
-    let n = (dimension + 1).next_power_of_two();
+    let n = mem.points_f.len();
 
     // set zero terms to random
     *f0 = mem.prng.next().unwrap();
@@ -230,8 +301,57 @@ fn test_encode() {
     let data_u32 = [0u32, 1, 0, 1, 1, 0, 0, 0, 1];
     let data = data_u32
         .iter()
-        .map(|x| Field32::from(*x))
+        .map(|x| Field32::from_reduced(*x))
         .collect::<Vec<Field32>>();
     let encoded_shares = encode_simple(&data, pub_key1, pub_key2);
     assert_eq!(encoded_shares.is_ok(), true);
 }
+
+#[test]
+fn test_encode_shares() {
+    use crate::field::Field32;
+
+    let pub_key = PublicKey::from_base64(
+        "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9RqZx3XMkdEHBHfNuCSMpOwofVQ=",
+    )
+    .unwrap();
+
+    let mut client = Client::<Field32>::with_public_keys(4, vec![pub_key.clone(); 3]).unwrap();
+    assert_eq!(client.num_servers(), 3);
+    let shares = client
+        .encode_shares(&[Field32::from_reduced(1); 4])
+        .unwrap();
+    assert_eq!(shares.len(), 3);
+    // The helper shares are seeds, which are shorter than the leader's share.
+    assert!(shares[1].len() < shares[0].len());
+    assert_eq!(shares[1].len(), shares[2].len());
+    assert!(matches!(
+        client.encode_simple(&[Field32::from_reduced(1); 4]),
+        Err(ClientError::NotTwoServers)
+    ));
+
+    assert!(matches!(
+        Client::<Field32>::with_public_keys(4, vec![pub_key]),
+        Err(ClientError::TooFewServers)
+    ));
+}
+
+#[test]
+fn test_dimension_too_large() {
+    use crate::field::Field32;
+
+    let pub_key = PublicKey::from_base64(
+        "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9RqZx3XMkdEHBHfNuCSMpOwofVQ=",
+    )
+    .unwrap();
+
+    // Field32 has roots of unity of order 2^20 * 3^2.
+    assert!(matches!(
+        Client::<Field32>::new(9 << 19, pub_key.clone(), pub_key.clone()),
+        Err(ClientError::InputSizeExceedsFieldCapacity)
+    ));
+    assert!(matches!(
+        Client::<Field32>::new(usize::MAX, pub_key.clone(), pub_key),
+        Err(ClientError::InputSizeExceedsMemoryCapacity)
+    ));
+}
@@ -0,0 +1,560 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Support for encoding and decoding messages to or from the TLS wire encoding, as specified in
+//! [RFC 8446, Section 3][1].
+//!
+//! The [`Encode`], [`Decode`], [`ParameterizedEncode`] and [`ParameterizedDecode`] traits can be
+//! implemented on values that need to be encoded or decoded. Utility functions are provided to
+//! encode or decode sequences of values, either with a fixed number of items or prefixed with the
+//! length of the encoding in bytes.
+//!
+//! [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3
+
+use std::{
+    error::Error,
+    io::{Cursor, Read},
+    mem::size_of,
+};
+
+/// An error that occurred during decoding.
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    /// An I/O error.
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// Extra data remained in the input after decoding a value.
+    #[error("{0} bytes left in buffer after decoding value")]
+    BytesLeftOver(usize),
+
+    /// A length prefix would overflow the number of bytes in the input.
+    #[error("length prefix of encoded vector overflows buffer: {0}")]
+    LengthPrefixTooBig(usize),
+
+    /// The encoding of a vector is too long to be described by its length prefix.
+    #[error("vector is too long for its length prefix")]
+    LengthPrefixOverflow,
+
+    /// Custom errors from [`Decode`] implementations.
+    #[error("other error: {0}")]
+    Other(#[source] Box<dyn Error + 'static + Send + Sync>),
+
+    /// An invalid value was decoded.
+    #[error("unexpected value")]
+    UnexpectedValue,
+}
+
+/// Describes how to decode an object from a byte sequence.
+pub trait Decode: Sized {
+    /// Read and decode an encoded object from `bytes`. On success, the decoded value is returned
+    /// and `bytes` is advanced by the encoded size of the value. On failure, an error is returned
+    /// and no further attempt to read from `bytes` should be made.
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError>;
+
+    /// Convenience method to get a decoded value. Returns an error if [`Self::decode`] fails, or
+    /// if there are any bytes left in `bytes` after decoding a value.
+    fn get_decoded(bytes: &[u8]) -> Result<Self, CodecError> {
+        Self::get_decoded_with_param(&(), bytes)
+    }
+}
+
+/// Describes how to decode an object from a byte sequence and a decoding parameter that provides
+/// additional context, e.g., the number of elements in a vector whose length is not encoded.
+pub trait ParameterizedDecode<P>: Sized {
+    /// Read and decode an encoded object from `bytes`. `decoding_parameter` provides details of
+    /// the wire encoding such as lengths of different portions of the message. On success, the
+    /// decoded value is returned and `bytes` is advanced by the encoded size of the value. On
+    /// failure, an error is returned and no further attempt to read from `bytes` should be made.
+    fn decode_with_param(
+        decoding_parameter: &P,
+        bytes: &mut Cursor<&[u8]>,
+    ) -> Result<Self, CodecError>;
+
+    /// Convenience method to get a decoded value. Returns an error if [`Self::decode_with_param`]
+    /// fails, or if there are any bytes left in `bytes` after decoding a value.
+    fn get_decoded_with_param(decoding_parameter: &P, bytes: &[u8]) -> Result<Self, CodecError> {
+        let mut cursor = Cursor::new(bytes);
+        let decoded = Self::decode_with_param(decoding_parameter, &mut cursor)?;
+        if cursor.position() as usize != bytes.len() {
+            return Err(CodecError::BytesLeftOver(
+                bytes.len() - cursor.position() as usize,
+            ));
+        }
+
+        Ok(decoded)
+    }
+}
+
+// Provide a blanket implementation so that any Decode can be used as a ParameterizedDecode<T> for
+// any T.
+impl<D: Decode, T> ParameterizedDecode<T> for D {
+    fn decode_with_param(
+        _decoding_parameter: &T,
+        bytes: &mut Cursor<&[u8]>,
+    ) -> Result<Self, CodecError> {
+        Self::decode(bytes)
+    }
+}
+
+/// Describes how to encode objects into a byte sequence.
+pub trait Encode {
+    /// Append the encoded form of this object to the end of `bytes`, growing the vector as needed.
+    fn encode(&self, bytes: &mut Vec<u8>);
+
+    /// Convenience method to get the encoded form of this object as a vector of bytes.
+    fn get_encoded(&self) -> Vec<u8> {
+        self.get_encoded_with_param(&())
+    }
+
+    /// Returns an optional hint indicating how many bytes will be required to encode this value,
+    /// or `None` by default.
+    fn encoded_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Describes how to encode objects into a byte sequence given an encoding parameter.
+pub trait ParameterizedEncode<P> {
+    /// Append the encoded form of this object to the end of `bytes`, growing the vector as needed.
+    /// `encoding_parameter` provides details of the wire encoding, used to control how the value
+    /// is encoded.
+    fn encode_with_param(&self, encoding_parameter: &P, bytes: &mut Vec<u8>);
+
+    /// Convenience method to get the encoded form of this object as a vector of bytes.
+    fn get_encoded_with_param(&self, encoding_parameter: &P) -> Vec<u8> {
+        let mut ret =
+            Vec::with_capacity(self.encoded_len_with_param(encoding_parameter).unwrap_or(0));
+        self.encode_with_param(encoding_parameter, &mut ret);
+        ret
+    }
+
+    /// Returns an optional hint indicating how many bytes will be required to encode this value,
+    /// or `None` by default.
+    fn encoded_len_with_param(&self, _encoding_parameter: &P) -> Option<usize> {
+        None
+    }
+}
+
+// Provide a blanket implementation so that any Encode can be used as a ParameterizedEncode<T> for
+// any T.
+impl<E: Encode + ?Sized, T> ParameterizedEncode<T> for E {
+    fn encode_with_param(&self, _encoding_parameter: &T, bytes: &mut Vec<u8>) {
+        self.encode(bytes)
+    }
+
+    fn encoded_len_with_param(&self, _encoding_parameter: &T) -> Option<usize> {
+        <Self as Encode>::encoded_len(self)
+    }
+}
+
+impl Decode for () {
+    fn decode(_bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        Ok(())
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _bytes: &mut Vec<u8>) {}
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+impl Decode for u8 {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        let mut value = [0u8; size_of::<u8>()];
+        bytes.read_exact(&mut value)?;
+        Ok(value[0])
+    }
+}
+
+impl Encode for u8 {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(*self);
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+impl Decode for u16 {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        let mut value = [0u8; size_of::<u16>()];
+        bytes.read_exact(&mut value)?;
+        Ok(u16::from_be_bytes(value))
+    }
+}
+
+impl Encode for u16 {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+/// 24 bit integer, per
+/// [RFC 8446, Section 3.3](https://datatracker.ietf.org/doc/html/rfc8446#section-3.3)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct U24(pub u32);
+
+impl Decode for U24 {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        let mut buffer = [0u8; 4];
+        bytes.read_exact(&mut buffer[1..])?;
+        Ok(U24(u32::from_be_bytes(buffer)))
+    }
+}
+
+impl Encode for U24 {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        // Encode lower three bytes of the u32 as u24
+        bytes.extend_from_slice(&self.0.to_be_bytes()[1..]);
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(3)
+    }
+}
+
+impl Decode for u32 {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        let mut value = [0u8; size_of::<u32>()];
+        bytes.read_exact(&mut value)?;
+        Ok(u32::from_be_bytes(value))
+    }
+}
+
+impl Encode for u32 {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(4)
+    }
+}
+
+impl Decode for u64 {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        let mut value = [0u8; size_of::<u64>()];
+        bytes.read_exact(&mut value)?;
+        Ok(u64::from_be_bytes(value))
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(8)
+    }
+}
+
+/// Encode `items` into `bytes` as a sequence of exactly `items.len()` values, without a length
+/// prefix. The decoder must learn the number of items from context, e.g., via
+/// [`decode_fixed_items`].
+pub fn encode_fixed_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+) {
+    for item in items {
+        item.encode_with_param(encoding_parameter, bytes);
+    }
+}
+
+/// Decode exactly `count` items from `bytes`, as encoded by [`encode_fixed_items`].
+pub fn decode_fixed_items<P, D: ParameterizedDecode<P>>(
+    count: usize,
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    (0..count)
+        .map(|_| D::decode_with_param(decoding_parameter, bytes))
+        .collect()
+}
+
+/// Encode `items` into `bytes` as a [variable-length vector][1] with a maximum length of `0xff`.
+///
+/// # Errors
+///
+/// Returns [`CodecError::LengthPrefixOverflow`] if the encoding of `items` is longer than `0xff`
+/// bytes. `bytes` is left unchanged in that case.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn encode_u8_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+) -> Result<(), CodecError> {
+    encode_items(bytes, encoding_parameter, items, 1, 0xff)
+}
+
+/// Decode `bytes` into a vector of `D` values, treating `bytes` as a [variable-length vector][1]
+/// of maximum length `0xff`.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn decode_u8_items<P, D: ParameterizedDecode<P>>(
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    let length = usize::from(u8::decode(bytes)?);
+    decode_items(length, decoding_parameter, bytes)
+}
+
+/// Encode `items` into `bytes` as a [variable-length vector][1] with a maximum length of `0xffff`.
+///
+/// # Errors
+///
+/// Returns [`CodecError::LengthPrefixOverflow`] if the encoding of `items` is longer than
+/// `0xffff` bytes. `bytes` is left unchanged in that case.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn encode_u16_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+) -> Result<(), CodecError> {
+    encode_items(bytes, encoding_parameter, items, 2, 0xffff)
+}
+
+/// Decode `bytes` into a vector of `D` values, treating `bytes` as a [variable-length vector][1]
+/// of maximum length `0xffff`.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn decode_u16_items<P, D: ParameterizedDecode<P>>(
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    let length = usize::from(u16::decode(bytes)?);
+    decode_items(length, decoding_parameter, bytes)
+}
+
+/// Encode `items` into `bytes` as a [variable-length vector][1] with a maximum length of
+/// `0xffffff`.
+///
+/// # Errors
+///
+/// Returns [`CodecError::LengthPrefixOverflow`] if the encoding of `items` is longer than
+/// `0xffffff` bytes. `bytes` is left unchanged in that case.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn encode_u24_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+) -> Result<(), CodecError> {
+    encode_items(bytes, encoding_parameter, items, 3, 0xffffff)
+}
+
+/// Decode `bytes` into a vector of `D` values, treating `bytes` as a [variable-length vector][1]
+/// of maximum length `0xffffff`.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn decode_u24_items<P, D: ParameterizedDecode<P>>(
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    let length = U24::decode(bytes)?.0 as usize;
+    decode_items(length, decoding_parameter, bytes)
+}
+
+/// Encode `items` into `bytes` as a [variable-length vector][1] with a maximum length of
+/// `0xffffffff`.
+///
+/// # Errors
+///
+/// Returns [`CodecError::LengthPrefixOverflow`] if the encoding of `items` is longer than
+/// `0xffffffff` bytes. `bytes` is left unchanged in that case.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn encode_u32_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+) -> Result<(), CodecError> {
+    encode_items(bytes, encoding_parameter, items, 4, 0xffffffff)
+}
+
+/// Decode `bytes` into a vector of `D` values, treating `bytes` as a [variable-length vector][1]
+/// of maximum length `0xffffffff`.
+///
+/// [1]: https://datatracker.ietf.org/doc/html/rfc8446#section-3.4
+pub fn decode_u32_items<P, D: ParameterizedDecode<P>>(
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    let length = u32::decode(bytes)? as usize;
+    decode_items(length, decoding_parameter, bytes)
+}
+
+// Writes a `prefix_len`-byte big-endian placeholder for the length, encodes the items and then
+// fills in the length of their encoding.
+fn encode_items<P, E: ParameterizedEncode<P>>(
+    bytes: &mut Vec<u8>,
+    encoding_parameter: &P,
+    items: &[E],
+    prefix_len: usize,
+    max_len: usize,
+) -> Result<(), CodecError> {
+    let len_offset = bytes.len();
+    bytes.resize(len_offset + prefix_len, 0);
+    encode_fixed_items(bytes, encoding_parameter, items);
+
+    let len = bytes.len() - len_offset - prefix_len;
+    if len > max_len {
+        bytes.truncate(len_offset);
+        return Err(CodecError::LengthPrefixOverflow);
+    }
+    let len_bytes = (len as u64).to_be_bytes();
+    bytes[len_offset..len_offset + prefix_len].copy_from_slice(&len_bytes[8 - prefix_len..]);
+    Ok(())
+}
+
+// Decodes items from the next `length` bytes of `bytes`, failing if they do not consume exactly
+// that many bytes.
+fn decode_items<P, D: ParameterizedDecode<P>>(
+    length: usize,
+    decoding_parameter: &P,
+    bytes: &mut Cursor<&[u8]>,
+) -> Result<Vec<D>, CodecError> {
+    let mut decoded = Vec::new();
+    let initial_position = bytes.position() as usize;
+
+    // Create cursor over specified portion of provided cursor to ensure we can't read past length.
+    let inner = bytes.get_ref();
+
+    // Make sure encoded length doesn't overflow usize or go past the end of provided byte buffer.
+    let (items_end, overflowed) = initial_position.overflowing_add(length);
+    if overflowed || items_end > inner.len() {
+        return Err(CodecError::LengthPrefixTooBig(length));
+    }
+
+    let mut sub = Cursor::new(&inner[initial_position..items_end]);
+
+    while sub.position() < length as u64 {
+        decoded.push(D::decode_with_param(decoding_parameter, &mut sub)?);
+    }
+
+    // Advance outer cursor by the amount read in the inner cursor
+    bytes.set_position(items_end as u64);
+
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn encode_nothing() {
+        let mut bytes = vec![];
+        ().encode(&mut bytes);
+        assert!(bytes.is_empty());
+        <()>::get_decoded(&bytes).unwrap();
+    }
+
+    #[test]
+    fn roundtrip_integers() {
+        let value = 100u8;
+        assert_eq!(value.get_encoded(), [100]);
+        assert_eq!(u8::get_decoded(&[100]).unwrap(), value);
+
+        let value = 0x0102u16;
+        assert_eq!(value.get_encoded(), [1, 2]);
+        assert_eq!(u16::get_decoded(&[1, 2]).unwrap(), value);
+
+        let value = U24(0x010203);
+        assert_eq!(value.get_encoded(), [1, 2, 3]);
+        assert_eq!(U24::get_decoded(&[1, 2, 3]).unwrap(), value);
+
+        let value = 0x01020304u32;
+        assert_eq!(value.get_encoded(), [1, 2, 3, 4]);
+        assert_eq!(u32::get_decoded(&[1, 2, 3, 4]).unwrap(), value);
+
+        let value = 0x0102030405060708u64;
+        assert_eq!(value.get_encoded(), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(u64::get_decoded(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap(), value);
+    }
+
+    #[test]
+    fn get_decoded_rejects_trailing_bytes() {
+        assert_matches!(
+            u16::get_decoded(&[1, 2, 3]),
+            Err(CodecError::BytesLeftOver(1))
+        );
+        assert_matches!(u32::get_decoded(&[1, 2, 3]), Err(CodecError::Io(_)));
+    }
+
+    #[test]
+    fn roundtrip_variable_length_items() {
+        let values = vec![0x0102u16, 0x0304, 0x0506];
+
+        let mut bytes = vec![];
+        encode_u8_items(&mut bytes, &(), &values).unwrap();
+        assert_eq!(bytes, [6, 1, 2, 3, 4, 5, 6]);
+        let decoded: Vec<u16> = decode_u8_items(&(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+
+        let mut bytes = vec![];
+        encode_u16_items(&mut bytes, &(), &values).unwrap();
+        assert_eq!(bytes[..2], [0, 6]);
+        let decoded: Vec<u16> = decode_u16_items(&(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+
+        let mut bytes = vec![];
+        encode_u24_items(&mut bytes, &(), &values).unwrap();
+        assert_eq!(bytes[..3], [0, 0, 6]);
+        let decoded: Vec<u16> = decode_u24_items(&(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+
+        let mut bytes = vec![];
+        encode_u32_items(&mut bytes, &(), &values).unwrap();
+        assert_eq!(bytes[..4], [0, 0, 0, 6]);
+        let decoded: Vec<u16> = decode_u32_items(&(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+
+        let mut bytes = vec![];
+        encode_fixed_items(&mut bytes, &(), &values);
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6]);
+        let decoded: Vec<u16> = decode_fixed_items(3, &(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn variable_length_items_errors() {
+        // The encoding of the items does not fit in the length prefix.
+        let mut bytes = vec![9];
+        assert_matches!(
+            encode_u8_items(&mut bytes, &(), &[0u8; 0x100]),
+            Err(CodecError::LengthPrefixOverflow)
+        );
+        assert_eq!(bytes, [9]);
+
+        // The length prefix exceeds the remaining input.
+        assert_matches!(
+            decode_u8_items::<(), u8>(&(), &mut Cursor::new(&[3, 1, 2])),
+            Err(CodecError::LengthPrefixTooBig(3))
+        );
+
+        // The last item is cut off by the length prefix.
+        assert_matches!(
+            decode_u8_items::<(), u16>(&(), &mut Cursor::new(&[3, 1, 2, 3, 4])),
+            Err(CodecError::Io(_))
+        );
+
+        // Decoding stops at the end of the vector.
+        let mut cursor = Cursor::new(&[1u8, 7, 8][..]);
+        let decoded: Vec<u8> = decode_u8_items(&(), &mut cursor).unwrap();
+        assert_eq!(decoded, [7]);
+        assert_eq!(cursor.position(), 2);
+    }
+}
@@ -7,6 +7,10 @@ use aes_gcm::aead::generic_array::typenum::U16;
 use aes_gcm::aead::generic_array::GenericArray;
 use aes_gcm::{AeadInPlace, NewAead};
 use ring::agreement;
+
+use crate::secret::SecretBytes;
+#[cfg(feature = "base64-encoding")]
+use crate::util::{decode_base64_url, encode_base64_url};
 type Aes128 = aes_gcm::AesGcm<aes_gcm::aes::Aes128, U16>;
 
 /// Length of the EC public key (X9.62 format)
@@ -45,9 +49,10 @@ pub struct PublicKey(Vec<u8>);
 
 /// NIST P-256, private key
 ///
-/// X9.62 uncompressed public key concatenated with the secret scalar.
+/// X9.62 uncompressed public key concatenated with the secret scalar. The key is not printed by
+/// `{:?}`.
 #[derive(Debug, Clone)]
-pub struct PrivateKey(Vec<u8>);
+pub struct PrivateKey(SecretBytes);
 
 impl PublicKey {
     /// Load public key from a base64 encoded X9.62 uncompressed representation.
@@ -55,6 +60,19 @@ impl PublicKey {
         let keydata = base64::decode(key)?;
         Ok(PublicKey(keydata))
     }
+
+    /// Load public key from a URL-safe base64 encoded X9.62 uncompressed representation, with or
+    /// without padding.
+    #[cfg(feature = "base64-encoding")]
+    pub fn from_base64_url(key: &str) -> Result<Self, EncryptError> {
+        Ok(PublicKey(decode_base64_url(key)?))
+    }
+
+    /// Encode public key as URL-safe base64 without padding.
+    #[cfg(feature = "base64-encoding")]
+    pub fn to_base64_url(&self) -> String {
+        encode_base64_url(&self.0)
+    }
 }
 
 /// Copy public key from a private key
@@ -68,8 +86,32 @@ impl PrivateKey {
     /// Load private key from a base64 encoded string.
     pub fn from_base64(key: &str) -> Result<Self, EncryptError> {
         let keydata = base64::decode(key)?;
-        Ok(PrivateKey(keydata))
+        Ok(PrivateKey(keydata.into()))
+    }
+
+    /// Load private key from a URL-safe base64 encoded string, with or without padding.
+    #[cfg(feature = "base64-encoding")]
+    pub fn from_base64_url(key: &str) -> Result<Self, EncryptError> {
+        Ok(PrivateKey(decode_base64_url(key)?.into()))
     }
+
+    /// Encode private key as URL-safe base64 without padding.
+    #[cfg(feature = "base64-encoding")]
+    pub fn to_base64_url(&self) -> String {
+        encode_base64_url(&self.0)
+    }
+}
+
+/// Encode an encrypted share as URL-safe base64 without padding.
+#[cfg(feature = "base64-encoding")]
+pub fn share_to_base64_url(share: &[u8]) -> String {
+    encode_base64_url(share)
+}
+
+/// Decode an encrypted share from URL-safe base64, with or without padding.
+#[cfg(feature = "base64-encoding")]
+pub fn share_from_base64_url(share: &str) -> Result<Vec<u8>, EncryptError> {
+    Ok(decode_base64_url(share)?)
 }
 
 /// Encrypt a bytestring using the public key
@@ -109,8 +151,9 @@ pub fn encrypt_share(share: &[u8], key: &PublicKey) -> Result<Vec<u8>, EncryptEr
 /// Decrypt a bytestring using the private key
 ///
 /// This uses ECIES with X9.63 key derivation function and AES-GCM for the
-/// symmetic encryption and MAC.
-pub fn decrypt_share(share: &[u8], key: &PrivateKey) -> Result<Vec<u8>, EncryptError> {
+/// symmetic encryption and MAC. The plaintext is returned as [`SecretBytes`], since it is a share
+/// of a client's input.
+pub fn decrypt_share(share: &[u8], key: &PrivateKey) -> Result<SecretBytes, EncryptError> {
     if share.len() < PUBLICKEY_LENGTH + TAG_LENGTH {
         return Err(EncryptError::DecryptionLength);
     }
@@ -141,6 +184,7 @@ pub fn decrypt_share(share: &[u8], key: &PrivateKey) -> Result<Vec<u8>, EncryptE
         &symmetric_key_bytes[KEY_LENGTH..],
         in_out,
     )
+    .map(SecretBytes::from)
 }
 
 fn x963_kdf(z: &[u8], shared_info: &[u8]) -> [u8; 32] {
@@ -188,7 +232,33 @@ mod tests {
         let encrypted = encrypt_share(&data, &pub_key)?;
 
         let decrypted = decrypt_share(&encrypted, &priv_key)?;
-        assert_eq!(decrypted, data);
+        assert_eq!(decrypted.as_bytes(), data);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "base64-encoding")]
+    fn test_base64_url() -> Result<(), EncryptError> {
+        let priv_key = PrivateKey::from_base64(
+            "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgN\
+             t9HUC2E0w+9RqZx3XMkdEHBHfNuCSMpOwofVSq3TfyKwn0NrftKisKKVSaTOt5seJ67P5QL4hxgPWvxw==",
+        )?;
+        let encoded = priv_key.to_base64_url();
+        assert!(!encoded.contains(|c| c == '+' || c == '/' || c == '='));
+        let priv_key = PrivateKey::from_base64_url(&encoded)?;
+
+        let pub_key = PublicKey::from(&priv_key);
+        let pub_key = PublicKey::from_base64_url(&pub_key.to_base64_url())?;
+
+        let data = b"share".to_vec();
+        let encrypted =
+            share_from_base64_url(&share_to_base64_url(&encrypt_share(&data, &pub_key)?))?;
+        assert_eq!(decrypt_share(&encrypted, &priv_key)?.as_bytes(), data);
+
+        assert!(matches!(
+            PublicKey::from_base64_url("!"),
+            Err(EncryptError::DecodeBase64(_))
+        ));
         Ok(())
     }
 
@@ -221,7 +291,7 @@ mod tests {
         let decrypted1 = decrypt_share(&encrypted_share1, &priv_key1).unwrap();
         let decrypted2 = decrypt_share(&encrypted_share2, &priv_key2).unwrap();
 
-        assert_eq!(decrypted1, share1);
-        assert_eq!(decrypted2, share2);
+        assert_eq!(decrypted1.as_bytes(), share1);
+        assert_eq!(decrypted2.as_bytes(), share2);
     }
 }
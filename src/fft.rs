@@ -3,10 +3,15 @@
 //! This module implements an iterative FFT algorithm for computing the (inverse) Discrete Fourier
 //! Transform (DFT) over a slice of field elements.
 
-use crate::field::FieldElement;
-use crate::fp::{log2, MAX_ROOTS};
+use crate::field::{from_usize, integer_from_usize, FieldElement, FieldElementWithInteger};
+use crate::fp::log2;
+use crate::polynomial::{poly_deg, poly_fft, poly_mul_direct_into, PolyFFTTempMemory};
 
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::iter;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// An error returned by an FFT operation.
 #[derive(Debug, PartialEq, thiserror::Error)]
@@ -17,11 +22,28 @@ pub enum FftError {
     /// The specified size is too large.
     #[error("size is larger than than maximum permitted")]
     SizeTooLarge,
-    /// The specified size is not a power of 2.
+    /// The specified size is not a power of 2, or, for mixed-radix transforms, not of the form
+    /// `2^a * 3^b`.
     #[error("size is not a power of 2")]
     SizeInvalid,
+    /// The coset offset is zero.
+    #[error("coset offset is zero")]
+    CosetOffsetZero,
+    /// The number of requested outputs is larger than the size of the transform.
+    #[error("number of outputs is larger than specified size")]
+    PrefixTooLong,
 }
 
+/// Transforms of at least this size are parallelized if the `multithreaded` feature is enabled.
+/// Below this, the overhead of distributing the work outweighs the speedup. The threshold can be
+/// changed per call with the `_with_threshold` variants of the transforms, or per planner with
+/// [`FftPlanner::set_parallel_threshold`].
+pub const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+/// The number of butterflies computed by each task of the rayon thread pool.
+#[cfg(feature = "multithreaded")]
+const PARALLEL_CHUNK_LEN: usize = 1 << 12;
+
 /// Sets `outp` to the DFT of `inp`.
 ///
 /// Interpreting the input as the coefficients of a polynomial, the output is equal to the input
@@ -32,13 +54,192 @@ pub fn discrete_fourier_transform<F: FieldElement>(
     inp: &[F],
     size: usize,
 ) -> Result<(), FftError> {
+    discrete_fourier_transform_with_threshold(outp, inp, size, PARALLEL_THRESHOLD)
+}
+
+/// Like [`discrete_fourier_transform`], except that the transform is parallelized if `size` is at
+/// least `parallel_threshold` rather than [`PARALLEL_THRESHOLD`]. This has no effect unless the
+/// `multithreaded` feature is enabled.
+#[cfg_attr(not(feature = "multithreaded"), allow(unused_variables))]
+pub fn discrete_fourier_transform_with_threshold<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    parallel_threshold: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(outp.len(), size)?;
+
+    #[cfg(feature = "multithreaded")]
+    {
+        if size >= parallel_threshold {
+            shared_plan::<F>(d).transform(&mut outp[..size], inp, parallel_threshold);
+            return Ok(());
+        }
+    }
+
+    bitrev_copy(&mut outp[..size], inp, d);
+    butterflies(&mut outp[..size], d);
+    Ok(())
+}
+
+// Same as `discrete_fourier_transform()`, but with radix-2 butterflies only. This is exposed for
+// comparison in benchmarks.
+pub(crate) fn discrete_fourier_transform_radix2<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(outp.len(), size)?;
+    bitrev_copy(&mut outp[..size], inp, d);
+    butterflies_radix2(&mut outp[..size], d);
+    Ok(())
+}
+
+// Sets `outp[i]` to `inp[bitrev(d, i)]`, or to zero if `inp` is too short.
+fn bitrev_copy<F: FieldElement>(outp: &mut [F], inp: &[F], d: usize) {
+    for (i, out) in outp.iter_mut().enumerate() {
+        let j = bitrev(d, i);
+        *out = if j < inp.len() { inp[j] } else { F::zero() };
+    }
+}
+
+/// Replaces the first `size` elements of `data` with their DFT. This is equivalent to
+/// [`discrete_fourier_transform`], except that the input is overwritten rather than copied into a
+/// separate output buffer.
+pub fn discrete_fourier_transform_in_place<F: FieldElement>(
+    data: &mut [F],
+    size: usize,
+) -> Result<(), FftError> {
+    discrete_fourier_transform_in_place_with_threshold(data, size, PARALLEL_THRESHOLD)
+}
+
+/// Like [`discrete_fourier_transform_in_place`], except that the transform is parallelized if
+/// `size` is at least `parallel_threshold` rather than [`PARALLEL_THRESHOLD`]. This has no effect
+/// unless the `multithreaded` feature is enabled.
+#[cfg_attr(not(feature = "multithreaded"), allow(unused_variables))]
+pub fn discrete_fourier_transform_in_place_with_threshold<F: FieldElement>(
+    data: &mut [F],
+    size: usize,
+    parallel_threshold: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(data.len(), size)?;
+
+    #[cfg(feature = "multithreaded")]
+    {
+        if size >= parallel_threshold {
+            shared_plan::<F>(d).transform_in_place(&mut data[..size], parallel_threshold);
+            return Ok(());
+        }
+    }
+
+    bit_reverse_permute_unchecked(&mut data[..size], d);
+
+    butterflies(&mut data[..size], d);
+    Ok(())
+}
+
+/// Replaces the first `size` elements of `data` with their DFT, processing the data in blocks so
+/// that the transform can run with a small working set. The output is the same as that of
+/// [`discrete_fourier_transform_in_place`].
+///
+/// This uses the "four-step" algorithm: the input is viewed as a matrix with about `sqrt(size)`
+/// rows and columns, the columns are transformed in batches that are copied into a scratch buffer
+/// of `block_len` elements, and then the rows are transformed in place. The scratch buffer is
+/// enlarged to hold at least one column, i.e., to at least `sqrt(2 * size)` elements. Apart from
+/// this buffer, no memory proportional to `size` is allocated.
+pub fn discrete_fourier_transform_streaming<F: FieldElement>(
+    data: &mut [F],
+    size: usize,
+    block_len: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(data.len(), size)?;
+    let data = &mut data[..size];
+
+    // View the data as an `n1 x n2` matrix in row-major order, i.e., `data[n2 * j1 + j2]` is the
+    // element in row `j1` and column `j2`. The DFT of size `n = n1 * n2` is then
+    //
+    //   X[k1 + n1 * k2] = sum_{j2} w^(n1 * j2 * k2) * w^(j2 * k1) * Y[j2][k1],
+    //   Y[j2][k1] = sum_{j1} x[n2 * j1 + j2] * w^(n2 * j1 * k1)
+    //
+    // for `w = root(d)`: `Y[j2]` is a DFT of size `n1` over a column, and the outer sum is a DFT of
+    // size `n2` over a row.
+    let (a, b) = (d - d / 2, d / 2);
+    let (n1, n2) = (1 << a, 1 << b);
+    let cols = std::cmp::max(block_len / n1, 1);
+    let mut scratch = vec![F::zero(); cols * n1];
+    let w = F::root(d).unwrap();
+
+    // Steps 1 and 2: transform the columns and multiply by the twiddle factors `w^(j2 * k1)`.
+    let mut w_j2 = F::one();
+    for first_col in (0..n2).step_by(cols) {
+        let cols = std::cmp::min(cols, n2 - first_col);
+
+        // Gather the batch of columns so that each column is contiguous in `scratch`.
+        for j1 in 0..n1 {
+            for c in 0..cols {
+                scratch[c * n1 + j1] = data[n2 * j1 + first_col + c];
+            }
+        }
+
+        for column in scratch.chunks_mut(n1).take(cols) {
+            discrete_fourier_transform_in_place(column, n1)?;
+            let mut t = F::one();
+            for y in column.iter_mut() {
+                *y *= t;
+                t *= w_j2;
+            }
+            w_j2 *= w;
+        }
+
+        for k1 in 0..n1 {
+            for c in 0..cols {
+                data[n2 * k1 + first_col + c] = scratch[c * n1 + k1];
+            }
+        }
+    }
+    drop(scratch);
+
+    // Step 3: transform the rows in place.
+    for row in data.chunks_mut(n2) {
+        discrete_fourier_transform_in_place(row, n2)?;
+    }
+
+    // Step 4: `data[n2 * k1 + k2]` now holds `X[k1 + n1 * k2]`, so transpose the matrix. The
+    // transpose rotates the `d` bits of each index by `b`, so every cycle of the permutation has at
+    // most `d` elements. Each cycle is rotated once, starting from its smallest index.
+    let next = |p: usize| ((p << a) | (p >> b)) & (size - 1);
+    for start in 0..size {
+        let mut p = next(start);
+        while p > start {
+            p = next(p);
+        }
+        if p < start {
+            continue;
+        }
+
+        // Move the value at each index `p` of the cycle to `next(p)`.
+        let mut carry = data[start];
+        let mut p = next(start);
+        while p != start {
+            std::mem::swap(&mut carry, &mut data[p]);
+            p = next(p);
+        }
+        data[start] = carry;
+    }
+
+    Ok(())
+}
+
+// Checks that a DFT of the given size can be computed in a buffer of length `len`. Returns the
+// base-2 logarithm of `size`.
+fn check_size<F: FieldElement>(len: usize, size: usize) -> Result<usize, FftError> {
     let d = usize::try_from(log2(size as u128)).unwrap();
 
-    if size > outp.len() {
+    if size > len {
         return Err(FftError::OutputTooSmall);
     }
 
-    if size > 1 << MAX_ROOTS {
+    if F::root(d).is_none() {
         return Err(FftError::SizeTooLarge);
     }
 
@@ -46,33 +247,80 @@ pub fn discrete_fourier_transform<F: FieldElement>(
         return Err(FftError::SizeInvalid);
     }
 
-    for i in 0..size {
-        let j = bitrev(d, i);
-        if j < inp.len() {
-            outp[i] = inp[j];
-        } else {
-            outp[i] = F::zero();
-        }
+    Ok(d)
+}
+
+// Runs the iterative Cooley-Tukey butterflies over `data`, whose length is `2^d` and whose elements
+// are in bit-reversed order. Layers are merged in pairs into radix-4 butterflies, which halves the
+// number of passes over `data` and the number of twiddle factor updates; if `d` is odd, the last
+// layer is a radix-2 layer.
+fn butterflies<F: FieldElement>(data: &mut [F], d: usize) {
+    let mut l = 1;
+    while l < d {
+        radix4_layers(data, l);
+        l += 2;
     }
+    if l == d {
+        radix2_layer(data, l);
+    }
+}
 
-    let mut w: F;
+// Radix-2 version of `butterflies()`. This is only used for comparison.
+fn butterflies_radix2<F: FieldElement>(data: &mut [F], d: usize) {
     for l in 1..d + 1 {
-        w = F::one();
-        let r = F::root(l).unwrap();
-        let y = 1 << (l - 1);
-        for i in 0..y {
-            for j in 0..(size / y) >> 1 {
-                let x = (1 << l) * j + i;
-                let u = outp[x];
-                let v = w * outp[x + y];
-                outp[x] = u + v;
-                outp[x + y] = u - v;
-            }
-            w *= r;
+        radix2_layer(data, l);
+    }
+}
+
+// Runs layer `l` of the butterflies.
+fn radix2_layer<F: FieldElement>(data: &mut [F], l: usize) {
+    let r = F::root(l).unwrap();
+    let y = 1 << (l - 1);
+    let mut w = F::one();
+    for i in 0..y {
+        for x in (i..data.len()).step_by(1 << l) {
+            let u = data[x];
+            let v = w * data[x + y];
+            data[x] = u + v;
+            data[x + y] = u - v;
         }
+        w *= r;
     }
+}
 
-    Ok(())
+// Runs layers `l` and `l + 1` of the butterflies at once. Each radix-4 butterfly combines the four
+// elements at offsets `0, h, 2h, 3h`, where `h = 2^(l-1)`. With `w1 = root(l)^i`,
+// `w2 = root(l+1)^i` and `j = root(2)`, the two radix-2 layers compute
+//
+//   a0 + w1*a1 + w2*(a2 + w1*a3)       a0 - w1*a1 + j*w2*(a2 - w1*a3)
+//   a0 + w1*a1 - w2*(a2 + w1*a3)       a0 - w1*a1 - j*w2*(a2 - w1*a3)
+//
+// which only needs the products `w1*a1`, `w2*a2`, `w1*w2*a3` and one multiplication by `j`.
+fn radix4_layers<F: FieldElement>(data: &mut [F], l: usize) {
+    let r = F::root(l + 1).unwrap();
+    let j = F::root(2).unwrap();
+    let h = 1 << (l - 1);
+    let mut w2 = F::one();
+    for i in 0..h {
+        let w1 = w2 * w2;
+        let w3 = w1 * w2;
+        for x in (i..data.len()).step_by(4 * h) {
+            let a0 = data[x];
+            let b1 = w1 * data[x + h];
+            let b2 = w2 * data[x + 2 * h];
+            let b3 = w3 * data[x + 3 * h];
+
+            let t0 = a0 + b1;
+            let t1 = a0 - b1;
+            let t2 = b2 + b3;
+            let t3 = j * (b2 - b3);
+            data[x] = t0 + t2;
+            data[x + h] = t1 + t3;
+            data[x + 2 * h] = t0 - t2;
+            data[x + 3 * h] = t1 - t3;
+        }
+        w2 *= r;
+    }
 }
 
 /// Sets `outp` to the inverse of the DFT of `inp`.
@@ -81,9 +329,96 @@ pub fn discrete_fourier_transform_inv<F: FieldElement>(
     inp: &[F],
     size: usize,
 ) -> Result<(), FftError> {
-    let size_inv = F::from(F::Integer::try_from(size).unwrap()).inv();
-    discrete_fourier_transform(outp, inp, size)?;
-    discrete_fourier_transform_inv_finish(outp, size, size_inv);
+    let size_inv = from_usize::<F>(size).inv();
+    discrete_fourier_transform_inv_scaled(outp, inp, size, size_inv)
+}
+
+/// Sets `outp` to the inverse of the DFT of `inp`, where `size_inv` is the inverse of `size`.
+///
+/// This is equivalent to calling [`discrete_fourier_transform`] followed by
+/// `discrete_fourier_transform_inv_finish()`, but the transform is computed with the inverse roots
+/// of unity and the multiplication by `size_inv` is folded into the last butterfly pass, saving a
+/// pass over the output. Passing in `size_inv` allows the caller to amortize the cost of the
+/// modular inverse across multiple inverse DFT operations.
+pub fn discrete_fourier_transform_inv_scaled<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    size_inv: F,
+) -> Result<(), FftError> {
+    discrete_fourier_transform_inv_scaled_with_threshold(
+        outp,
+        inp,
+        size,
+        size_inv,
+        PARALLEL_THRESHOLD,
+    )
+}
+
+/// Like [`discrete_fourier_transform_inv_scaled`], except that the transform is parallelized if
+/// `size` is at least `parallel_threshold` rather than [`PARALLEL_THRESHOLD`]. This has no effect
+/// unless the `multithreaded` feature is enabled.
+#[cfg_attr(not(feature = "multithreaded"), allow(unused_variables))]
+pub fn discrete_fourier_transform_inv_scaled_with_threshold<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    size_inv: F,
+    parallel_threshold: usize,
+) -> Result<(), FftError> {
+    #[cfg(feature = "multithreaded")]
+    {
+        if size >= parallel_threshold {
+            discrete_fourier_transform_with_threshold(outp, inp, size, parallel_threshold)?;
+            discrete_fourier_transform_inv_finish(outp, size, size_inv);
+            return Ok(());
+        }
+    }
+
+    let d = check_size::<F>(outp.len(), size)?;
+
+    bitrev_copy(&mut outp[..size], inp, d);
+
+    if d == 0 {
+        outp[0] *= size_inv;
+        return Ok(());
+    }
+
+    let data = &mut outp[..size];
+    for l in 1..d + 1 {
+        // The inverse of the `2^l`-th root of unity `r` is `r^(2^l - 1)`.
+        let mut r = F::root(l).unwrap();
+        let mut r_inv = F::one();
+        for _ in 0..l {
+            r_inv *= r;
+            r *= r;
+        }
+
+        let y = 1 << (l - 1);
+        if l < d {
+            let mut w = F::one();
+            for i in 0..y {
+                for x in (i..size).step_by(1 << l) {
+                    let u = data[x];
+                    let v = w * data[x + y];
+                    data[x] = u + v;
+                    data[x + y] = u - v;
+                }
+                w *= r_inv;
+            }
+        } else {
+            // The last layer consists of a single block. Scale both inputs of each butterfly.
+            let mut w = size_inv;
+            for x in 0..y {
+                let u = size_inv * data[x];
+                let v = w * data[x + y];
+                data[x] = u + v;
+                data[x + y] = u - v;
+                w *= r_inv;
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -104,20 +439,687 @@ pub(crate) fn discrete_fourier_transform_inv_finish<F: FieldElement>(
     }
 }
 
-// bitrev returns the first d bits of x in reverse order. (Thanks, OEIS! https://oeis.org/A030109)
+/// Sets `outp` to the DFT of `inp` over the coset `offset * H`, where `H` is the subgroup generated
+/// by the `size`-th principal root of unity `p`.
+///
+/// Interpreting the input as the coefficients of a polynomial, the output is equal to the input
+/// evaluated at points `offset * p^0, offset * p^1, ... offset * p^(size-1)`. The offset must be
+/// non-zero.
+pub fn discrete_fourier_transform_coset<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    offset: F,
+) -> Result<(), FftError> {
+    if offset == F::zero() {
+        return Err(FftError::CosetOffsetZero);
+    }
+    check_size::<F>(outp.len(), size)?;
+
+    // Evaluating `f(x)` at `offset * x` is the same as evaluating `f(offset * x)` at `x`, whose
+    // `j`-th coefficient is `offset^j` times that of `f`.
+    let mut scale = F::one();
+    for (j, out) in outp[..size].iter_mut().enumerate() {
+        *out = if j < inp.len() {
+            inp[j] * scale
+        } else {
+            F::zero()
+        };
+        scale *= offset;
+    }
+
+    discrete_fourier_transform_in_place(outp, size)
+}
+
+/// Sets `outp` to the inverse of the DFT of `inp` over the coset `offset * H`. This interpolates
+/// the polynomial whose evaluations at the points of the coset are given by `inp`. See
+/// [`discrete_fourier_transform_coset`].
+pub fn discrete_fourier_transform_coset_inv<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    offset: F,
+) -> Result<(), FftError> {
+    if offset == F::zero() {
+        return Err(FftError::CosetOffsetZero);
+    }
+
+    discrete_fourier_transform_inv(outp, inp, size)?;
+    let offset_inv = offset.inv();
+    let mut scale = F::one();
+    for out in outp[..size].iter_mut() {
+        *out *= scale;
+        scale *= offset_inv;
+    }
+
+    Ok(())
+}
+
+/// Sets `outp[..k]` to the first `k` outputs of the DFT of `inp`, i.e., to the input evaluated at
+/// points `p^0, p^1, ... p^(k-1)`, where `p` is the `size`-th principal root of unity. The other
+/// elements of `outp` are left unchanged.
+///
+/// Butterflies that only contribute to discarded outputs, or that only combine zero inputs, are
+/// skipped. Let `s = size / k'`, where `k'` is the smallest power of 2 that is at least `k`. The
+/// input is split into `s` interleaved subsequences of length `k'`, where the `b`-th subsequence
+/// is `inp[b], inp[s + b], inp[2 * s + b], ...` for `0 <= b < s`. Each subsequence is transformed
+/// separately and then combined into the first `k` outputs. Subsequences whose elements are all
+/// zero, which is the case whenever `b >= inp.len()`, are skipped entirely, and subsequences with a
+/// single non-zero element are not transformed. The cost is therefore roughly
+/// `min(inp.len(), size) * (log2(k') + k / k')` multiplications rather than `size * log2(size)`.
+pub fn discrete_fourier_transform_truncated<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    k: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(size, size)?;
+    if k > outp.len() {
+        return Err(FftError::OutputTooSmall);
+    }
+    if k > size {
+        return Err(FftError::PrefixTooLong);
+    }
+    if k == 0 {
+        return Ok(());
+    }
+
+    let inp = &inp[..std::cmp::min(inp.len(), size)];
+    let k_pow2 = k.next_power_of_two();
+    let s = size / k_pow2;
+    let w = F::root(d).unwrap();
+
+    for out in outp[..k].iter_mut() {
+        *out = F::zero();
+    }
+
+    // Write `j = a * s + b`. Then `X[i] = sum_b w^(i * b) * Y_b[i]`, where `Y_b` is the DFT of size
+    // `k'` of the subsequence `x_b, x_(s + b), x_(2 * s + b), ...` with root `w^s`.
+    let mut col = vec![F::zero(); k_pow2];
+    let mut w_b = F::one();
+    for b in 0..std::cmp::min(s, inp.len()) {
+        let mut t = F::one();
+        if b + s >= inp.len() {
+            // Only `x_b` is non-zero, so `Y_b` is constant.
+            for out in outp[..k].iter_mut() {
+                *out += t * inp[b];
+                t *= w_b;
+            }
+        } else {
+            for (a, c) in col.iter_mut().enumerate() {
+                let j = a * s + b;
+                *c = if j < inp.len() { inp[j] } else { F::zero() };
+            }
+            discrete_fourier_transform_in_place(&mut col, k_pow2)?;
+            for (out, c) in outp[..k].iter_mut().zip(col.iter()) {
+                *out += t * *c;
+                t *= w_b;
+            }
+        }
+        w_b *= w;
+    }
+
+    Ok(())
+}
+
+/// Returns the smallest size of the form `2^a * 3^b` that is at least `n` and for which the field
+/// has a root of unity of that order, or `None` if there is no such size. Transforms of this size
+/// can be computed with [`discrete_fourier_transform_mixed_radix`], so that the input does not need
+/// to be padded up to the next power of 2.
+pub fn mixed_radix_size<F: FieldElementWithInteger>(n: usize) -> Option<usize> {
+    mixed_radix_sizes::<F>().filter(|size| *size >= n).min()
+}
+
+/// Sets `outp` to the DFT of `inp` over a domain of size `2^a * 3^b`.
+///
+/// Interpreting the input as the coefficients of a polynomial, the output is equal to the input
+/// evaluated at points `w^0, w^1, ... w^(size-1)`, where `w` is the root of unity of order `size`
+/// returned by [`root_of_unity`]. If `size` is a power of 2, then the output is the same as that of
+/// [`discrete_fourier_transform`].
+///
+/// Returns [`FftError::SizeInvalid`] if `size` is not of the form `2^a * 3^b` and
+/// [`FftError::SizeTooLarge`] if the field has no root of unity of order `size`.
+pub fn discrete_fourier_transform_mixed_radix<F: FieldElementWithInteger>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+) -> Result<(), FftError> {
+    mixed_radix(outp, inp, size, false)
+}
+
+/// Sets `outp` to the inverse of the DFT of `inp` over a domain of size `2^a * 3^b`. See
+/// [`discrete_fourier_transform_mixed_radix`].
+pub fn discrete_fourier_transform_mixed_radix_inv<F: FieldElementWithInteger>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+) -> Result<(), FftError> {
+    mixed_radix(outp, inp, size, true)
+}
+
+fn mixed_radix<F: FieldElementWithInteger>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+    invert: bool,
+) -> Result<(), FftError> {
+    if size > outp.len() {
+        return Err(FftError::OutputTooSmall);
+    }
+    let mut w = root_of_unity::<F>(size)?;
+    if invert {
+        w = w.inv();
+    }
+    let roots: Vec<F> = iter::successors(Some(F::one()), |x| Some(*x * w))
+        .take(size)
+        .collect();
+    let mut padded = vec![F::zero(); size];
+    for (x, y) in padded.iter_mut().zip(inp.iter()) {
+        *x = *y;
+    }
+    poly_fft(
+        &mut outp[..size],
+        &padded,
+        &roots,
+        size,
+        invert,
+        &mut PolyFFTTempMemory::new(size),
+    );
+    Ok(())
+}
+
+/// Returns the root of unity of order `size` that is used for transforms of that size, where
+/// `size` must be of the form `2^a * 3^b`.
+///
+/// The root is the product of the `2^a`-th principal root of unity and a fixed root of unity of
+/// order `3^b`, so for powers of 2 it is the root used by [`discrete_fourier_transform`].
+pub fn root_of_unity<F: FieldElementWithInteger>(size: usize) -> Result<F, FftError> {
+    if size == 0 {
+        return Err(FftError::SizeInvalid);
+    }
+    let a = size.trailing_zeros() as usize;
+    let mut rest = size >> a;
+    let mut b = 0;
+    while rest.is_multiple_of(3) {
+        rest /= 3;
+        b += 1;
+    }
+    if rest != 1 {
+        return Err(FftError::SizeInvalid);
+    }
+
+    let root2 = F::root(a).ok_or(FftError::SizeTooLarge)?;
+    if b == 0 {
+        return Ok(root2);
+    }
+    let (t, mut root3) = three_adicity::<F>().ok_or(FftError::SizeTooLarge)?;
+    if b > t {
+        return Err(FftError::SizeTooLarge);
+    }
+    // Reduce the order of `root3` from `3^t` to `3^b`.
+    for _ in b..t {
+        root3 = root3 * root3 * root3;
+    }
+    Ok(root2 * root3)
+}
+
+// Returns each size of the form `2^a * 3^b` for which the field has a root of unity of that order.
+pub(crate) fn mixed_radix_sizes<F: FieldElementWithInteger>() -> impl Iterator<Item = usize> {
+    let max_b = three_adicity::<F>().map_or(0, |(t, _)| t);
+    let max_a = (0..usize::BITS as usize)
+        .take_while(|a| F::root(*a).is_some())
+        .last()
+        .unwrap_or(0);
+    (0..=max_b)
+        .map_while(|b| 3usize.checked_pow(b as u32))
+        .flat_map(move |pow3| (0..=max_a).map_while(move |a| pow3.checked_mul(1 << a)))
+}
+
+// Returns the largest `t` such that `3^t` divides `p - 1`, along with a root of unity of order
+// `3^t`, or `None` if `t == 0`.
+fn three_adicity<F: FieldElementWithInteger>() -> Option<(usize, F)> {
+    let one = integer_from_usize::<F>(1).ok()?;
+    let three = integer_from_usize::<F>(3).ok()?;
+
+    // Write `p - 1 = 3^t * c`. `x` is divisible by 3 if and only if `x / 3 != (x - 1) / 3`.
+    let mut t = 0;
+    let mut c = F::MODULUS - one;
+    while c / three != (c - one) / three {
+        c = c / three;
+        t += 1;
+    }
+    if t == 0 {
+        return None;
+    }
+
+    // For any non-zero `x`, `x^c` has order dividing `3^t`, and the order is exactly `3^t` unless
+    // `x` is a cube, which holds for a third of the field. The search is deterministic, so the
+    // root is the same on every call.
+    (2..).map(from_usize::<F>).find_map(|x| {
+        let root = x.pow(c);
+        let mut y = root;
+        for _ in 1..t {
+            y = y * y * y;
+        }
+        if y != F::one() {
+            Some((t, root))
+        } else {
+            None
+        }
+    })
+}
+
+/// Precomputed bit-reversal permutations and twiddle factors for DFTs of a fixed field.
+///
+/// A planner computes the tables for a given transform size the first time a transform of that
+/// size is requested and reuses them for every subsequent transform of the same size. The outputs
+/// are the same as those of [`discrete_fourier_transform`] and [`discrete_fourier_transform_inv`].
+///
+/// The tables are stored in a process-wide cache keyed by field and size, so they are computed at
+/// most once per process (barring a race between threads) and shared by all planners, including
+/// those on different threads. Cached tables are never freed.
+///
+/// If the `multithreaded` feature is enabled, the butterfly stages of transforms whose size is at
+/// least the planner's parallel threshold are run on the rayon thread pool.
+#[derive(Clone, Debug)]
+pub struct FftPlanner<F: FieldElement> {
+    plans: HashMap<usize, Arc<FftPlan<F>>>,
+    parallel_threshold: usize,
+}
+
+#[derive(Clone, Debug)]
+struct FftPlan<F: FieldElement> {
+    // The bit-reversal permutation of `0..size`.
+    bitrev: Vec<usize>,
+    // `root(d)^k` for `k` in `0..size/2`, where `size = 2^d`.
+    twiddles: Vec<F>,
+    // The inverse of `size`.
+    size_inv: F,
+}
+
+impl<F: FieldElement> FftPlanner<F> {
+    /// Returns a planner. Tables are fetched from the process-wide cache as they are needed.
+    pub fn new() -> Self {
+        Self {
+            plans: HashMap::new(),
+            parallel_threshold: PARALLEL_THRESHOLD,
+        }
+    }
+
+    /// Sets the smallest transform size for which the butterfly stages are parallelized. The
+    /// default is [`PARALLEL_THRESHOLD`]. This has no effect unless the `multithreaded` feature is
+    /// enabled.
+    pub fn set_parallel_threshold(&mut self, threshold: usize) {
+        self.parallel_threshold = threshold;
+    }
+
+    /// Sets `outp` to the DFT of `inp`. See [`discrete_fourier_transform`].
+    pub fn transform(&mut self, outp: &mut [F], inp: &[F], size: usize) -> Result<(), FftError> {
+        let parallel_threshold = self.parallel_threshold;
+        let plan = self.plan(outp.len(), size)?;
+        plan.transform(&mut outp[..size], inp, parallel_threshold);
+        Ok(())
+    }
+
+    /// Replaces the first `size` elements of `data` with their DFT. See
+    /// [`discrete_fourier_transform_in_place`].
+    pub fn transform_in_place(&mut self, data: &mut [F], size: usize) -> Result<(), FftError> {
+        let parallel_threshold = self.parallel_threshold;
+        let plan = self.plan(data.len(), size)?;
+        plan.transform_in_place(&mut data[..size], parallel_threshold);
+        Ok(())
+    }
+
+    /// Sets `outp` to the inverse of the DFT of `inp`. See [`discrete_fourier_transform_inv`].
+    pub fn transform_inv(
+        &mut self,
+        outp: &mut [F],
+        inp: &[F],
+        size: usize,
+    ) -> Result<(), FftError> {
+        self.transform(outp, inp, size)?;
+        let size_inv = self.plans[&size].size_inv;
+        discrete_fourier_transform_inv_finish(outp, size, size_inv);
+        Ok(())
+    }
+
+    // Returns the tables for the given size, fetching them from the shared cache if necessary.
+    fn plan(&mut self, len: usize, size: usize) -> Result<&FftPlan<F>, FftError> {
+        let d = check_size::<F>(len, size)?;
+        Ok(self.plans.entry(size).or_insert_with(|| shared_plan(d)))
+    }
+}
+
+// The tables computed so far, keyed by the type of the field and the size of the transform. The
+// values are `Arc<FftPlan<F>>` for the field `F` named by the key.
+type PlanCache = HashMap<(TypeId, usize), Arc<dyn Any + Send + Sync>>;
+
+static SHARED_PLANS: OnceLock<Mutex<PlanCache>> = OnceLock::new();
+
+// Returns the tables for transforms of size `2^d` from the shared cache, computing them if
+// necessary. The tables are computed without holding the lock so that threads working on different
+// sizes do not wait for each other.
+fn shared_plan<F: FieldElement>(d: usize) -> Arc<FftPlan<F>> {
+    let size = 1 << d;
+    let key = (TypeId::of::<F>(), size);
+    let cache = SHARED_PLANS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(plan) = cache.lock().unwrap().get(&key) {
+        return plan.clone().downcast().unwrap();
+    }
+
+    let r = F::root(d).unwrap();
+    let mut twiddles = Vec::with_capacity(size / 2);
+    let mut w = F::one();
+    for _ in 0..size / 2 {
+        twiddles.push(w);
+        w *= r;
+    }
+    let plan = Arc::new(FftPlan {
+        bitrev: (0..size).map(|i| bitrev(d, i)).collect(),
+        twiddles,
+        size_inv: from_usize::<F>(size).inv(),
+    });
+
+    // If another thread computed the same tables in the meantime, keep the first copy.
+    cache
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert(plan)
+        .clone()
+        .downcast()
+        .unwrap()
+}
+
+impl<F: FieldElement> Default for FftPlanner<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FieldElement> FftPlan<F> {
+    // Sets `outp` to the DFT of `inp`, where `outp` has the size of the plan.
+    fn transform(&self, outp: &mut [F], inp: &[F], parallel_threshold: usize) {
+        for (out, j) in outp.iter_mut().zip(self.bitrev.iter()) {
+            *out = if *j < inp.len() { inp[*j] } else { F::zero() };
+        }
+        self.butterflies(outp, parallel_threshold);
+    }
+
+    // Replaces `data`, which has the size of the plan, with its DFT.
+    fn transform_in_place(&self, data: &mut [F], parallel_threshold: usize) {
+        for (i, j) in self.bitrev.iter().enumerate() {
+            if i < *j {
+                data.swap(i, *j);
+            }
+        }
+        self.butterflies(data, parallel_threshold);
+    }
+
+    // Same as `butterflies()`, but with the root powers read from the table. The twiddle factor
+    // for layer `l` and offset `i` is `root(l)^i = root(d)^(i * 2^(d-l))`.
+    #[cfg_attr(not(feature = "multithreaded"), allow(unused_variables))]
+    fn butterflies(&self, data: &mut [F], parallel_threshold: usize) {
+        let size = data.len();
+        let mut y = 1;
+        while y < size {
+            let stride = size / (2 * y);
+
+            #[cfg(feature = "multithreaded")]
+            {
+                if size >= parallel_threshold {
+                    self.par_layer(data, y, stride);
+                    y *= 2;
+                    continue;
+                }
+            }
+
+            for block in data.chunks_mut(2 * y) {
+                let (lo, hi) = block.split_at_mut(y);
+                butterfly(lo, hi, &self.twiddles, 0, stride);
+            }
+            y *= 2;
+        }
+    }
+
+    // Runs one layer of butterflies on the rayon thread pool. In the early layers there are many
+    // small blocks, which are distributed across threads; in the later layers there are only a
+    // few large blocks, each of which is split into chunks.
+    #[cfg(feature = "multithreaded")]
+    fn par_layer(&self, data: &mut [F], y: usize, stride: usize) {
+        use rayon::prelude::*;
+
+        if 2 * y <= PARALLEL_CHUNK_LEN {
+            data.par_chunks_mut(2 * y).for_each(|block| {
+                let (lo, hi) = block.split_at_mut(y);
+                butterfly(lo, hi, &self.twiddles, 0, stride);
+            });
+        } else {
+            for block in data.chunks_mut(2 * y) {
+                let (lo, hi) = block.split_at_mut(y);
+                lo.par_chunks_mut(PARALLEL_CHUNK_LEN)
+                    .zip(hi.par_chunks_mut(PARALLEL_CHUNK_LEN))
+                    .enumerate()
+                    .for_each(|(k, (lo, hi))| {
+                        butterfly(lo, hi, &self.twiddles, k * PARALLEL_CHUNK_LEN, stride);
+                    });
+            }
+        }
+    }
+}
+
+// Applies the butterfly to each pair `(lo[i], hi[i])` with twiddle factor
+// `twiddles[(offset + i) * stride]`.
+fn butterfly<F: FieldElement>(
+    lo: &mut [F],
+    hi: &mut [F],
+    twiddles: &[F],
+    offset: usize,
+    stride: usize,
+) {
+    for (i, (u, v)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+        let w = twiddles[(offset + i) * stride];
+        let t = w * *v;
+        *v = *u - t;
+        *u += t;
+    }
+}
+
+/// Polynomials with at most this many coefficients are multiplied directly by [`poly_mul`]. Above
+/// this, evaluating, multiplying and interpolating with the FFT is faster.
+const POLY_MUL_FFT_THRESHOLD: usize = 32;
+
+/// Multiplies polynomials `p` and `q` and returns the result. Each polynomial is given by its
+/// coefficients, starting with the constant term; an empty slice is the zero polynomial. The output
+/// has no trailing zero coefficients, except that the zero polynomial is returned as a single
+/// coefficient.
+///
+/// If both polynomials are large enough, the product is computed with the FFT; otherwise, or if
+/// the field does not have roots of unity of sufficient order, it is computed directly.
+pub fn poly_mul<F: FieldElement>(p: &[F], q: &[F]) -> Vec<F> {
+    if p.is_empty() || q.is_empty() {
+        return vec![F::zero()];
+    }
+
+    let p = &p[..poly_deg(p) + 1];
+    let q = &q[..poly_deg(q) + 1];
+    let mut out = vec![F::zero(); p.len() + q.len() - 1];
+    poly_mul_into(&mut out, p, q, &mut Vec::new()).unwrap();
+    out.truncate(poly_deg(&out) + 1);
+    out
+}
+
+/// Multiplies polynomials `p` and `q`, which must not be empty, and writes the
+/// `p.len() + q.len() - 1` coefficients of the product to the start of `outp`. The rest of `outp` is
+/// not modified. This is the same as [`poly_mul`], except that trailing zeros are neither removed
+/// from the inputs nor from the output.
+///
+/// `scratch` is used as working memory for the FFT. It is resized as needed, so passing the same
+/// vector to repeated calls avoids allocating on each call.
+pub fn poly_mul_into<F: FieldElement>(
+    outp: &mut [F],
+    p: &[F],
+    q: &[F],
+    scratch: &mut Vec<F>,
+) -> Result<(), FftError> {
+    let len = p.len() + q.len() - 1;
+    if outp.len() < len {
+        return Err(FftError::OutputTooSmall);
+    }
+
+    let size = len.next_power_of_two();
+    let d = usize::try_from(log2(size as u128)).unwrap();
+    if p.len().min(q.len()) <= POLY_MUL_FFT_THRESHOLD || F::root(d).is_none() {
+        poly_mul_direct_into(outp, p, q);
+        return Ok(());
+    }
+
+    scratch.clear();
+    scratch.resize(2 * size, F::zero());
+    let (p_vals, q_vals) = scratch.split_at_mut(size);
+    discrete_fourier_transform(p_vals, p, size)?;
+    discrete_fourier_transform(q_vals, q, size)?;
+    for (x, y) in q_vals.iter_mut().zip(p_vals.iter()) {
+        *x *= *y;
+    }
+    discrete_fourier_transform_inv(p_vals, q_vals, size)?;
+    outp[..len].copy_from_slice(&p_vals[..len]);
+    Ok(())
+}
+
+/// Permutes `data` in place so that the element at index `i` moves to the index whose binary
+/// representation is that of `i` reversed, where indices have `log2(data.len())` bits. This is the
+/// reordering applied to the input of the iterative FFT. The length of `data` must be a power of 2.
+///
+/// For large inputs, the permutation is applied in square tiles so that each swap touches a small
+/// number of cache lines, rather than striding across the whole slice.
+pub fn bit_reverse_permute<T>(data: &mut [T]) -> Result<(), FftError> {
+    if !data.len().is_power_of_two() {
+        return Err(FftError::SizeInvalid);
+    }
+    let d = data.len().trailing_zeros() as usize;
+    bit_reverse_permute_unchecked(data, d);
+    Ok(())
+}
+
+// The number of low-order (and high-order) index bits covered by a tile in
+// `bit_reverse_permute_unchecked()`.
+const BITREV_TILE_BITS: usize = 4;
+
+// Same as `bit_reverse_permute()`, where `data.len()` is `2^d`.
+fn bit_reverse_permute_unchecked<T>(data: &mut [T], d: usize) {
+    let q = BITREV_TILE_BITS;
+    if d < 4 * q {
+        for i in 0..data.len() {
+            let j = bitrev(d, i);
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        return;
+    }
+
+    // Split each index into `hi | mid | lo`, where `hi` and `lo` have `q` bits. Reversing the index
+    // gives `rev(lo) | rev(mid) | rev(hi)`, so the tile of indices with middle bits `mid` is
+    // swapped with the tile with middle bits `rev(mid)`. Each tile spans `2^q` runs of `2^q`
+    // consecutive elements.
+    let m = d - 2 * q;
+    for mid in 0..1 << m {
+        let mid_rev = bitrev(m, mid);
+        if mid > mid_rev {
+            continue;
+        }
+        for hi in 0..1 << q {
+            for lo in 0..1 << q {
+                let i = (hi << (d - q)) | (mid << q) | lo;
+                let j = (bitrev(q, lo) << (d - q)) | (mid_rev << q) | bitrev(q, hi);
+                if mid < mid_rev || i < j {
+                    data.swap(i, j);
+                }
+            }
+        }
+    }
+}
+
+// bitrev returns the first d bits of x in reverse order.
 fn bitrev(d: usize, x: usize) -> usize {
-    let mut y = 0;
-    for i in 0..d {
-        y += ((x >> i) & 1) << (d - i);
+    if d == 0 {
+        return 0;
+    }
+    x.reverse_bits() >> (usize::BITS as usize - d)
+}
+
+/// Sets `outp` to the DFT of `inp` by evaluating the sum `X[i] = sum_j x_j p^(i * j)` directly,
+/// where `p` is the `size`-th principal root of unity. This takes `O(size^2)` operations and is
+/// intended only as a reference for testing the optimized transforms.
+#[cfg(any(test, feature = "test-util"))]
+pub fn discrete_fourier_transform_naive<F: FieldElement>(
+    outp: &mut [F],
+    inp: &[F],
+    size: usize,
+) -> Result<(), FftError> {
+    let d = check_size::<F>(outp.len(), size)?;
+    let w = F::root(d).unwrap();
+    let inp = &inp[..std::cmp::min(inp.len(), size)];
+
+    let mut w_i = F::one();
+    for out in outp[..size].iter_mut() {
+        // Horner's method evaluates the input at `w^i`.
+        *out = F::zero();
+        for x in inp.iter().rev() {
+            *out = *out * w_i + *x;
+        }
+        w_i *= w;
+    }
+
+    Ok(())
+}
+
+/// Checks the transforms of the given size against each other on input `inp`, whose length must be
+/// at most `size`. Returns `Ok(true)` if all of the following hold:
+///
+/// * The `size`-th principal root of unity returned by `F::root` has order exactly `size`.
+/// * [`discrete_fourier_transform`] agrees with [`discrete_fourier_transform_naive`].
+/// * [`discrete_fourier_transform_inv`] recovers `inp` from the output.
+///
+/// This is intended for validating the root tables of new fields.
+#[cfg(any(test, feature = "test-util"))]
+pub fn verify_fft_roundtrip<F: FieldElement>(inp: &[F], size: usize) -> Result<bool, FftError> {
+    let d = check_size::<F>(size, size)?;
+    if inp.len() > size {
+        return Err(FftError::OutputTooSmall);
     }
-    y >> 1
+
+    // A root whose order divides `size` has order exactly `size` if and only if raising it to the
+    // power `size / 2` yields `-1`.
+    let mut w = F::root(d).unwrap();
+    for _ in 1..d {
+        w *= w;
+    }
+    let want_w = if d == 0 { F::one() } else { -F::one() };
+    if w != want_w {
+        return Ok(false);
+    }
+
+    let mut want = vec![F::zero(); size];
+    discrete_fourier_transform_naive(&mut want, inp, size)?;
+    let mut got = vec![F::zero(); size];
+    discrete_fourier_transform(&mut got, inp, size)?;
+    if got != want {
+        return Ok(false);
+    }
+
+    let mut inv = vec![F::zero(); size];
+    discrete_fourier_transform_inv(&mut inv, &got, size)?;
+    Ok(inv[..inp.len()] == inp[..] && inv[inp.len()..].iter().all(|x| *x == F::zero()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::field::{rand, split, Field126, Field32, Field64, Field80};
-    use crate::polynomial::{poly_fft, PolyAuxMemory};
+    use crate::field::{rand, split, Field126, Field128, Field32, Field61, Field64, Field80};
+    use crate::polynomial::{poly_eval, poly_fft, poly_mul as poly_mul_schoolbook, PolyAuxMemory};
+    use crate::secret::SecretVec;
 
     fn discrete_fourier_transform_then_inv_test<F: FieldElement>() -> Result<(), FftError> {
         let test_sizes = [1, 2, 4, 8, 16, 256, 1024, 2048];
@@ -155,10 +1157,488 @@ mod tests {
         discrete_fourier_transform_then_inv_test::<Field126>().expect("unexpected error");
     }
 
+    #[test]
+    fn test_field128() {
+        discrete_fourier_transform_then_inv_test::<Field128>().expect("unexpected error");
+    }
+
+    #[test]
+    fn test_verify_fft_roundtrip() {
+        for size in [1, 2, 4, 8, 64].iter() {
+            assert!(verify_fft_roundtrip(&rand::<Field32>(*size).unwrap(), *size).unwrap());
+            assert!(verify_fft_roundtrip(&rand::<Field64>(*size / 2).unwrap(), *size).unwrap());
+            assert!(verify_fft_roundtrip(&rand::<Field128>(*size).unwrap(), *size).unwrap());
+        }
+        assert!(verify_fft_roundtrip(&rand::<Field61>(2).unwrap(), 2).unwrap());
+
+        // The naive DFT evaluates the input at each root of unity.
+        let inp: Vec<Field64> = rand(5).unwrap();
+        let mut outp = vec![Field64::zero(); 8];
+        discrete_fourier_transform_naive(&mut outp, &inp, 8).unwrap();
+        let w = Field64::root(3).unwrap();
+        let mut x = Field64::one();
+        for out in outp.iter() {
+            assert_eq!(*out, poly_eval(&inp, x));
+            x *= w;
+        }
+
+        assert_eq!(
+            verify_fft_roundtrip(&rand::<Field64>(9).unwrap(), 8),
+            Err(FftError::OutputTooSmall)
+        );
+        assert_eq!(
+            verify_fft_roundtrip::<Field61>(&[], 4),
+            Err(FftError::SizeTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_in_place() {
+        for size in [1, 2, 4, 256, 1024].iter() {
+            let inp: Vec<Field64> = rand(*size).unwrap();
+            let mut want = vec![Field64::zero(); *size];
+            discrete_fourier_transform(&mut want, &inp, *size).unwrap();
+
+            let mut got = inp.clone();
+            discrete_fourier_transform_in_place(&mut got, *size).unwrap();
+            assert_eq!(got, want);
+        }
+
+        // Only the first `size` elements are transformed.
+        let inp: Vec<Field64> = rand(8).unwrap();
+        let mut got = inp.clone();
+        let mut want = vec![Field64::zero(); 4];
+        discrete_fourier_transform(&mut want, &inp[..4], 4).unwrap();
+        discrete_fourier_transform_in_place(&mut got, 4).unwrap();
+        assert_eq!(got[..4], want[..]);
+        assert_eq!(got[4..], inp[4..]);
+
+        let mut data = vec![Field64::zero(); 4];
+        assert_eq!(
+            discrete_fourier_transform_in_place(&mut data, 8),
+            Err(FftError::OutputTooSmall)
+        );
+        assert_eq!(
+            discrete_fourier_transform_in_place(&mut data, 3),
+            Err(FftError::SizeInvalid)
+        );
+    }
+
+    #[test]
+    fn test_planner() {
+        let mut planner = FftPlanner::<Field128>::new();
+        for size in [1, 2, 4, 256, 1024, 4].iter() {
+            let inp: Vec<Field128> = rand(*size).unwrap();
+            let mut want = vec![Field128::zero(); *size];
+            let mut got = vec![Field128::zero(); *size];
+
+            discrete_fourier_transform(&mut want, &inp, *size).unwrap();
+            planner.transform(&mut got, &inp, *size).unwrap();
+            assert_eq!(got, want);
+
+            let mut got_in_place = inp.clone();
+            planner
+                .transform_in_place(&mut got_in_place, *size)
+                .unwrap();
+            assert_eq!(got_in_place, want);
+
+            planner.transform_inv(&mut got, &want, *size).unwrap();
+            assert_eq!(got, inp);
+        }
+
+        let mut outp = vec![Field128::zero(); 4];
+        assert_eq!(
+            planner.transform(&mut outp, &[], 8),
+            Err(FftError::OutputTooSmall)
+        );
+        assert_eq!(
+            planner.transform(&mut outp, &[], 3),
+            Err(FftError::SizeInvalid)
+        );
+    }
+
+    #[test]
+    fn test_planner_shared_cache() {
+        // Planners on different threads share the same tables.
+        let plans: Vec<Arc<FftPlan<Field64>>> = (0..4)
+            .map(|_| std::thread::spawn(|| shared_plan::<Field64>(5)))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        for plan in plans.iter() {
+            assert!(Arc::ptr_eq(plan, &plans[0]));
+        }
+        assert_eq!(plans[0].twiddles.len(), 16);
+
+        let mut planner = FftPlanner::<Field64>::new();
+        planner.plan(32, 32).unwrap();
+        assert!(Arc::ptr_eq(&planner.plans[&32], &plans[0]));
+
+        // Tables are keyed by field as well as by size.
+        let other = shared_plan::<Field32>(5);
+        assert_eq!(other.twiddles[1], Field32::root(5).unwrap());
+        assert_eq!(plans[0].twiddles[1], Field64::root(5).unwrap());
+    }
+
+    #[test]
+    fn test_planner_parallel() {
+        // With the `multithreaded` feature, a threshold of 1 exercises the parallel code paths,
+        // including blocks that are split into several chunks.
+        let size = 1 << 14;
+        let inp: Vec<Field64> = rand(size).unwrap();
+        let mut want = vec![Field64::zero(); size];
+        let mut got = vec![Field64::zero(); size];
+        FftPlanner::new().transform(&mut want, &inp, size).unwrap();
+
+        let mut planner = FftPlanner::new();
+        planner.set_parallel_threshold(1);
+        planner.transform(&mut got, &inp, size).unwrap();
+        assert_eq!(got, want);
+
+        // Large enough to be parallelized by default.
+        let size = PARALLEL_THRESHOLD;
+        let inp: Vec<Field64> = rand(size).unwrap();
+        let mut got = vec![Field64::zero(); size];
+        discrete_fourier_transform(&mut got, &inp, size).unwrap();
+        let mut back = vec![Field64::zero(); size];
+        discrete_fourier_transform_inv(&mut back, &got, size).unwrap();
+        assert_eq!(back, inp);
+    }
+
+    #[test]
+    fn test_parallel_threshold() {
+        // The transforms agree on either side of the configured cutoff. With the `multithreaded`
+        // feature, a transform whose size equals the threshold takes the parallel path and one
+        // below it the serial path.
+        let size = 1 << 10;
+        let inp: Vec<Field64> = rand(size).unwrap();
+
+        let mut serial = vec![Field64::zero(); size];
+        let mut parallel = vec![Field64::zero(); size];
+        discrete_fourier_transform_with_threshold(&mut serial, &inp, size, size + 1).unwrap();
+        discrete_fourier_transform_with_threshold(&mut parallel, &inp, size, size).unwrap();
+        assert_eq!(parallel, serial);
+
+        let mut want = vec![Field64::zero(); size];
+        discrete_fourier_transform(&mut want, &inp, size).unwrap();
+        assert_eq!(serial, want);
+
+        let mut in_place = inp.clone();
+        discrete_fourier_transform_in_place_with_threshold(&mut in_place, size, size).unwrap();
+        assert_eq!(in_place, serial);
+        let mut in_place = inp.clone();
+        discrete_fourier_transform_in_place_with_threshold(&mut in_place, size, size + 1).unwrap();
+        assert_eq!(in_place, serial);
+
+        let size_inv = from_usize::<Field64>(size).inv();
+        for threshold in [size, size + 1].iter() {
+            let mut back = vec![Field64::zero(); size];
+            discrete_fourier_transform_inv_scaled_with_threshold(
+                &mut back, &serial, size, size_inv, *threshold,
+            )
+            .unwrap();
+            assert_eq!(back, inp);
+        }
+    }
+
+    #[test]
+    fn test_inv_scaled() {
+        for size in [1, 2, 4, 8, 256, 1024].iter() {
+            let inp: Vec<Field128> = rand(*size).unwrap();
+            let size_inv = from_usize::<Field128>(*size).inv();
+
+            let mut want = vec![Field128::zero(); *size];
+            discrete_fourier_transform(&mut want, &inp, *size).unwrap();
+            discrete_fourier_transform_inv_finish(&mut want, *size, size_inv);
+
+            let mut got = vec![Field128::zero(); *size];
+            discrete_fourier_transform_inv_scaled(&mut got, &inp, *size, size_inv).unwrap();
+            assert_eq!(got, want);
+        }
+    }
+
+    #[test]
+    fn test_poly_mul() {
+        for (p_len, q_len) in [(1, 1), (3, 5), (33, 40), (100, 257), (1000, 2)].iter() {
+            let p: Vec<Field64> = rand(*p_len).unwrap();
+            let q: Vec<Field64> = rand(*q_len).unwrap();
+            assert_eq!(poly_mul(&p, &q), poly_mul_schoolbook(&p, &q));
+            assert_eq!(poly_mul(&p, &q), poly_mul(&q, &p));
+        }
+
+        // Trailing zeros are ignored.
+        let one = Field64::one();
+        let zero = Field64::zero();
+        assert_eq!(poly_mul(&[one, one, zero], &[one, zero]), vec![one, one]);
+        assert_eq!(poly_mul::<Field64>(&[], &[one]), vec![zero]);
+        assert_eq!(poly_mul(&[zero, zero], &[one, one]), vec![zero]);
+
+        // Field61 does not have the roots of unity needed for the FFT.
+        let p: Vec<Field61> = rand(50).unwrap();
+        let q: Vec<Field61> = rand(50).unwrap();
+        assert_eq!(poly_mul(&p, &q), poly_mul_schoolbook(&p, &q));
+    }
+
+    #[test]
+    fn test_poly_mul_into() {
+        let mut scratch = Vec::new();
+        for (p_len, q_len) in [(1, 1), (3, 5), (100, 257), (40, 40)].iter() {
+            let p: Vec<Field64> = rand(*p_len).unwrap();
+            let q: Vec<Field64> = rand(*q_len).unwrap();
+            let want = poly_mul_schoolbook(&p, &q);
+
+            let len = p_len + q_len - 1;
+            let mut got = vec![Field64::one(); len + 1];
+            poly_mul_into(&mut got, &p, &q, &mut scratch).unwrap();
+            assert_eq!(got[..want.len()], want[..]);
+            assert_eq!(got[len], Field64::one());
+
+            assert_eq!(
+                poly_mul_into(&mut got[..len - 1], &p, &q, &mut scratch),
+                Err(FftError::OutputTooSmall)
+            );
+        }
+    }
+
+    #[test]
+    fn test_coset() {
+        let size = 16;
+        let offset = Field64::generator();
+        let root = Field64::root(4).unwrap();
+        let inp: Vec<Field64> = rand(10).unwrap();
+
+        let mut evals = vec![Field64::zero(); size];
+        discrete_fourier_transform_coset(&mut evals, &inp, size, offset).unwrap();
+        let mut x = offset;
+        for eval in evals.iter() {
+            assert_eq!(*eval, poly_eval(&inp, x));
+            x *= root;
+        }
+
+        // None of the evaluation points are in the base domain.
+        let mut base = vec![Field64::zero(); size];
+        discrete_fourier_transform(&mut base, &inp, size).unwrap();
+        assert_ne!(evals, base);
+
+        let mut got = vec![Field64::zero(); size];
+        discrete_fourier_transform_coset_inv(&mut got, &evals, size, offset).unwrap();
+        assert_eq!(got[..inp.len()], inp[..]);
+        assert!(got[inp.len()..].iter().all(|x| *x == Field64::zero()));
+
+        assert_eq!(
+            discrete_fourier_transform_coset(&mut got, &inp, size, Field64::zero()),
+            Err(FftError::CosetOffsetZero)
+        );
+        assert_eq!(
+            discrete_fourier_transform_coset_inv(&mut got, &evals, size, Field64::zero()),
+            Err(FftError::CosetOffsetZero)
+        );
+    }
+
+    #[test]
+    fn test_truncated() {
+        let size = 64;
+        for inp_len in [1, 3, 8, 17, 64, 70].iter() {
+            let inp: Vec<Field64> = rand(*inp_len).unwrap();
+            let mut want = vec![Field64::zero(); size];
+            discrete_fourier_transform(&mut want, &inp, size).unwrap();
+
+            for k in [0, 1, 3, 4, 5, 16, 33, 64].iter() {
+                let mut got = vec![Field64::one(); size];
+                discrete_fourier_transform_truncated(&mut got, &inp, size, *k).unwrap();
+                assert_eq!(got[..*k], want[..*k], "inp_len={} k={}", inp_len, k);
+                assert!(got[*k..].iter().all(|x| *x == Field64::one()));
+            }
+        }
+
+        let mut got = vec![Field64::zero(); 4];
+        assert_eq!(
+            discrete_fourier_transform_truncated(&mut got, &[], 4, 5),
+            Err(FftError::OutputTooSmall)
+        );
+        let mut got = vec![Field64::zero(); 8];
+        assert_eq!(
+            discrete_fourier_transform_truncated(&mut got, &[], 4, 5),
+            Err(FftError::PrefixTooLong)
+        );
+        assert_eq!(
+            discrete_fourier_transform_truncated(&mut got, &[], 6, 1),
+            Err(FftError::SizeInvalid)
+        );
+    }
+
+    #[test]
+    fn test_radix4() {
+        for d in 0..11 {
+            let size = 1 << d;
+            let inp: Vec<Field128> = rand(size).unwrap();
+            let mut want = vec![Field128::zero(); size];
+            for (i, x) in want.iter_mut().enumerate() {
+                *x = inp[bitrev(d, i)];
+            }
+            let mut got = want.clone();
+
+            butterflies_radix2(&mut want, d);
+            butterflies(&mut got, d);
+            assert_eq!(got, want, "d = {}", d);
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        for d in 0..13 {
+            let size = 1 << d;
+            let inp: Vec<Field64> = rand(size).unwrap();
+            let mut want = vec![Field64::zero(); size];
+            discrete_fourier_transform(&mut want, &inp, size).unwrap();
+
+            for block_len in [0, 64, 1 << 20].iter() {
+                let mut got = inp.clone();
+                discrete_fourier_transform_streaming(&mut got, size, *block_len).unwrap();
+                assert_eq!(got, want, "d = {}, block_len = {}", d, block_len);
+            }
+        }
+
+        let mut data = vec![Field64::zero(); 4];
+        assert_eq!(
+            discrete_fourier_transform_streaming(&mut data, 8, 16),
+            Err(FftError::OutputTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_bit_reverse_permute() {
+        for d in 0..20 {
+            let mut got: Vec<usize> = (0..1 << d).collect();
+            bit_reverse_permute(&mut got).unwrap();
+            for (i, x) in got.iter().enumerate() {
+                let mut want = 0;
+                for b in 0..d {
+                    want |= ((i >> b) & 1) << (d - 1 - b);
+                }
+                assert_eq!(*x, want, "d = {}", d);
+            }
+        }
+
+        assert_eq!(
+            bit_reverse_permute::<u8>(&mut []),
+            Err(FftError::SizeInvalid)
+        );
+        assert_eq!(
+            bit_reverse_permute(&mut [0u8; 6]),
+            Err(FftError::SizeInvalid)
+        );
+    }
+
+    fn mixed_radix_test<F: FieldElementWithInteger>(sizes: &[usize]) {
+        for size in sizes.iter() {
+            let inp: Vec<F> = rand(*size).unwrap();
+            let mut outp = vec![F::zero(); *size];
+            discrete_fourier_transform_mixed_radix(&mut outp, &inp, *size).unwrap();
+
+            // The evaluation points are the distinct powers of a root of unity of order `size`.
+            let w = root_of_unity::<F>(*size).unwrap();
+            let mut x = F::one();
+            for (i, out) in outp.iter().enumerate() {
+                assert_eq!(*out, poly_eval(&inp, x));
+                assert!(i == 0 || x != F::one());
+                x *= w;
+            }
+            assert_eq!(x, F::one());
+
+            let mut got = vec![F::zero(); *size];
+            discrete_fourier_transform_mixed_radix_inv(&mut got, &outp, *size).unwrap();
+            assert_eq!(got, inp);
+        }
+    }
+
+    #[test]
+    fn test_mixed_radix() {
+        mixed_radix_test::<Field32>(&[1, 2, 3, 6, 9, 12, 18, 36, 72, 96, 1152]);
+        mixed_radix_test::<Field64>(&[3, 24, 27, 54, 96, 108, 216, 864]);
+        // Field61 only has roots of unity of order 2, but 9 divides `p - 1`.
+        mixed_radix_test::<Field61>(&[2, 3, 6, 9, 18]);
+
+        // Power-of-2 sizes match the radix-2 DFT.
+        let inp: Vec<Field64> = rand(16).unwrap();
+        let mut want = vec![Field64::zero(); 16];
+        let mut got = vec![Field64::zero(); 16];
+        discrete_fourier_transform(&mut want, &inp, 16).unwrap();
+        discrete_fourier_transform_mixed_radix(&mut got, &inp, 16).unwrap();
+        assert_eq!(got, want);
+
+        // Shorter inputs are padded with zeros.
+        let mut got = vec![Field32::zero(); 12];
+        discrete_fourier_transform_mixed_radix(&mut got, &[Field32::one(); 5], 12).unwrap();
+        let mut x = Field32::one();
+        for out in got.iter() {
+            assert_eq!(*out, poly_eval(&[Field32::one(); 5], x));
+            x *= root_of_unity::<Field32>(12).unwrap();
+        }
+
+        let mut outp = vec![Field64::zero(); 81];
+        assert_eq!(
+            discrete_fourier_transform_mixed_radix(&mut outp, &inp, 81),
+            Err(FftError::SizeTooLarge)
+        );
+        assert_eq!(
+            discrete_fourier_transform_mixed_radix(&mut outp, &inp, 10),
+            Err(FftError::SizeInvalid)
+        );
+        assert_eq!(
+            discrete_fourier_transform_mixed_radix(&mut outp, &inp, 0),
+            Err(FftError::SizeInvalid)
+        );
+        assert_eq!(
+            discrete_fourier_transform_mixed_radix(&mut outp[..8], &inp, 12),
+            Err(FftError::OutputTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_mixed_radix_size() {
+        assert_eq!(mixed_radix_size::<Field32>(1), Some(1));
+        assert_eq!(mixed_radix_size::<Field32>(17), Some(18));
+        assert_eq!(mixed_radix_size::<Field32>(19), Some(24));
+        assert_eq!(mixed_radix_size::<Field32>(1 << 20), Some(1 << 20));
+        assert_eq!(mixed_radix_size::<Field32>((1 << 20) + 1), Some(9 << 17));
+        assert_eq!(mixed_radix_size::<Field32>(9 << 20), Some(9 << 20));
+        assert_eq!(mixed_radix_size::<Field32>((9 << 20) + 1), None);
+        assert_eq!(mixed_radix_size::<Field64>(65), Some(72));
+        assert_eq!(mixed_radix_size::<Field64>(100), Some(108));
+        assert_eq!(mixed_radix_size::<Field61>(5), Some(6));
+        assert_eq!(mixed_radix_size::<Field61>(10), Some(18));
+        assert_eq!(mixed_radix_size::<Field61>(19), None);
+    }
+
+    #[test]
+    fn test_field61() {
+        // Field61 only has roots of unity of order 1 and 2.
+        let inp = rand::<Field61>(4).unwrap();
+        let mut outp = vec![Field61::zero(); 4];
+        discrete_fourier_transform(&mut outp, &inp, 2).expect("unexpected error");
+        assert_eq!(
+            discrete_fourier_transform(&mut outp, &inp, 4),
+            Err(FftError::SizeTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_size_too_large() {
+        // The generator of Field32 has order 2^20.
+        let inp = rand::<Field32>(4).unwrap();
+        let mut outp = vec![Field32::zero(); 1 << 21];
+        assert_eq!(
+            discrete_fourier_transform(&mut outp, &inp, 1 << 21),
+            Err(FftError::SizeTooLarge)
+        );
+    }
+
     #[test]
     fn test_recursive_fft() {
         let size = 128;
-        let mut mem = PolyAuxMemory::new(size / 2);
+        let mut mem = PolyAuxMemory::new(size / 2).unwrap();
 
         let inp = rand(size).unwrap();
         let mut want = vec![Field32::zero(); size];
@@ -186,7 +1666,11 @@ mod tests {
         let len = 16;
         let num_shares = 3;
         let x: Vec<Field64> = rand(len).unwrap();
-        let mut x_shares = split(&x, num_shares).unwrap();
+        let mut x_shares: Vec<Vec<Field64>> = split(&x, num_shares)
+            .unwrap()
+            .into_iter()
+            .map(SecretVec::into_vec)
+            .collect();
 
         // Just for fun, let's do something different with a subset of the inputs. For the first
         // share, every odd element is set to the plaintext value. For all shares but the first,
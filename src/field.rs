@@ -6,15 +6,43 @@
 //! Each field has an associated parameter called the "generator" that generates a multiplicative
 //! subgroup of order `2^n` for some `n`.
 
-use crate::fp::{FP126, FP32, FP64, FP80};
-use crate::prng::Prng;
-use serde::{Deserialize, Serialize};
+use crate::codec::{CodecError, Decode, Encode};
+use crate::fft::{discrete_fourier_transform, FftError};
+use crate::fp::{FP126, FP128, FP255, FP32, FP61, FP64, FP80, LIMBS};
+use crate::prng::{Prng, Seed, SeedStream, SeedStreamShake128, SEED_LENGTH};
+use crate::secret::SecretVec;
+use rand_core::{CryptoRng, RngCore};
+use serde::{
+    de::{Error as _, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use std::{
     cmp::min,
-    convert::TryFrom,
+    convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    io::{self, Cursor, Read, Write},
+    iter::{Product, Sum},
+    marker::PhantomData,
     ops::{Add, AddAssign, BitAnd, Div, DivAssign, Mul, MulAssign, Neg, Shr, Sub, SubAssign},
+    str::FromStr,
+};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+mod binary;
+pub(crate) mod bulk;
+mod erased;
+mod shamir;
+#[cfg(feature = "vss")]
+mod vss;
+
+pub use binary::Field2_128;
+pub use erased::{FieldEnum, FieldId, FieldVec};
+pub use shamir::{
+    shamir_reconstruct, shamir_split, shamir_split_with_rng, ShamirError, ShamirShare,
 };
+#[cfg(feature = "vss")]
+pub use vss::{vss_split, VssCommitments, VssShare};
 
 /// Possible errors from finite field operations.
 #[derive(Debug, PartialEq, thiserror::Error)]
@@ -28,11 +56,57 @@ pub enum FieldError {
     /// Returned by `FieldElement::read_from()` if the input is larger than the modulus.
     #[error("read from byte slice exceeds modulus")]
     FromBytesModulusOverflow,
+    /// Returned when converting an integer into a field element if the integer is larger than or
+    /// equal to the modulus.
+    #[error("integer exceeds modulus")]
+    ModulusOverflow,
+    /// Returned by `encode_into_bitvector()` and `decode_bitvector()` if the number of bits is not
+    /// smaller than the bit length of the modulus.
+    #[error("bit vector length exceeds field capacity")]
+    BitVectorTooLong,
+    /// Returned by `encode_into_bitvector()` if the integer does not fit in the given number of
+    /// bits.
+    #[error("integer exceeds bit vector capacity")]
+    IntegerTooLarge,
+    /// Returned by `decode_bitvector()` if an entry of the input is neither 0 nor 1.
+    #[error("bit vector entry is not 0 or 1")]
+    InvalidBit,
+    /// Returned by `decode_aggregate()` if an entry of the aggregate exceeds the bound.
+    #[error("aggregate exceeds bound")]
+    AggregateOutOfBounds,
+    /// Returned by `integer_from_usize()` and `usize_from_integer()` if the value does not fit in
+    /// the target type.
+    #[error("integer conversion out of range")]
+    IntegerConversion,
+    /// Returned if more roots of unity are needed than the generator of the field provides.
+    #[error("not enough roots of unity")]
+    RootsUnavailable,
+    /// Returned by `to_signed()` if the element does not encode an integer whose absolute value is
+    /// within the bound.
+    #[error("signed integer exceeds bound")]
+    SignedOutOfBounds,
+    /// Returned by `FromStr::from_str()` and `FieldElement::from_hex()` if the input is not a
+    /// well-formed string encoding of a field element.
+    #[error("invalid string encoding")]
+    InvalidString,
+    /// Returned by the methods of `FieldEnum` and `FieldVec` if the operands belong to different
+    /// fields.
+    #[error("operands belong to different fields")]
+    FieldMismatch,
+    /// Returned by `FieldId::try_from()` if the input does not identify a field.
+    #[error("unknown field ID {0}")]
+    UnknownFieldId(u8),
 }
 
 /// Objects with this trait represent an element of `GF(p)` for some prime `p`.
+///
+/// This trait provides the arithmetic and encoding operations used by the proof system. Fields
+/// whose elements can be converted to and from a primitive integer type additionally implement
+/// [`FieldElementWithInteger`].
 pub trait FieldElement:
     Sized
+    + Encode
+    + Decode
     + Debug
     + Copy
     + PartialEq
@@ -43,38 +117,40 @@ pub trait FieldElement:
     + SubAssign
     + Mul<Output = Self>
     + MulAssign
+    + Mul<u64, Output = Self>
+    + MulAssign<u64>
     + Div<Output = Self>
     + DivAssign
     + Neg<Output = Self>
+    + Sum
+    + for<'a> Sum<&'a Self>
+    + Product
+    + for<'a> Product<&'a Self>
     + Display
-    + From<<Self as FieldElement>::Integer>
+    + ConstantTimeEq
+    + ConditionallySelectable
+    + Send
+    + Sync
     + 'static // NOTE This bound is needed for downcasting a `dyn Gadget<F>>` to a concrete type.
 {
     /// Size of each field element in bytes.
     const BYTES: usize;
 
-    /// The error returned if converting `usize` to an `Int` fails.
-    type IntegerTryFromError: Debug;
-
-    /// The integer representation of the field element.
-    type Integer: Copy
-        + Debug
-        + PartialOrd
-        + BitAnd<Output = <Self as FieldElement>::Integer>
-        + Div<Output = <Self as FieldElement>::Integer>
-        + Shr<Output = <Self as FieldElement>::Integer>
-        + Sub<Output = <Self as FieldElement>::Integer>
-        + TryFrom<usize, Error = Self::IntegerTryFromError>;
+    /// The length of the encoding written by `append_to()`, equal to `BYTES`. Being a constant,
+    /// it can be used to declare buffers for encoded elements on the stack, e.g.,
+    /// `[u8; Field64::ENCODED_LEN]`.
+    const ENCODED_LEN: usize = Self::BYTES;
 
-    /// Modular exponentation, i.e., `self^exp (mod p)`.
-    fn pow(&self, exp: Self::Integer) -> Self;
+    /// Identifies the field, e.g., in versioned encodings such as the header written by
+    /// [`util::serialize_with_header`](crate::util::serialize_with_header), which writes
+    /// `Self::ID as u8`.
+    const ID: FieldId;
 
     /// Modular inversion, i.e., `self^-1 (mod p)`. If `self` is 0, then the output is undefined.
+    /// The runtime does not depend on the value of `self`, so this may be used to invert secret
+    /// values.
     fn inv(&self) -> Self;
 
-    /// Returns the prime modulus `p`.
-    fn modulus() -> Self::Integer;
-
     /// Writes the field element to the end of input buffer. Exactly `BYTES` bytes will be written.
     ///
     /// TODO(acmiyaguchi) Replace this with an implementation of the corresponding serde trait
@@ -86,8 +162,39 @@ pub trait FieldElement:
     /// TODO(acmiyaguchi) Replace this with an implementation of the corresponding serde trait
     fn read_from(bytes: &[u8]) -> Result<Self, FieldError>;
 
+    /// Writes the field element to the end of the input buffer in big-endian byte order. For prime
+    /// fields, these are the bytes written by `append_to()` in reverse order, i.e., the big-endian
+    /// encoding of the integer representative, as used by some other implementations. Extension
+    /// fields write their coefficients in the same order as `append_to()`, each in big-endian byte
+    /// order.
+    fn append_to_be(&self, bytes: &mut Vec<u8>) {
+        let start = bytes.len();
+        self.append_to(bytes);
+        bytes[start..].reverse();
+    }
+
+    /// Interprets the next `BYTES` bytes from the input buffer as an element of the field encoded
+    /// by `append_to_be()`. An error is returned if the bytes encode an integer larger than the
+    /// field modulus.
+    fn read_from_be(bytes: &[u8]) -> Result<Self, FieldError> {
+        if bytes.len() < Self::BYTES {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        let mut le = bytes[..Self::BYTES].to_vec();
+        le.reverse();
+        Self::read_from(&le)
+    }
+
+    /// Writes the encoding of the field element, as written by `append_to()`, to `writer`.
+    fn encode_into<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads the next `BYTES` bytes from `reader` and interprets them as an element of the field.
+    /// An error of kind `InvalidData`, wrapping a `FieldError`, is returned if the bytes encode an
+    /// integer larger than the field modulus.
+    fn decode_from<R: Read>(reader: &mut R) -> io::Result<Self>;
+
     /// Interprets the next `BYTES` bytes from the input buffer as an element of the field. The `m`
-    /// most significant bits are cleared, where `m` is equal to the length of `Integer` in bits
+    /// most significant bits are cleared, where `m` is equal to the length of the encoding in bits
     /// minus the length of the modulus in bits. An error is returned if the result encodes an
     /// integer larger than the field modulus.
     ///
@@ -95,14 +202,36 @@ pub trait FieldElement:
     /// *should not* be used to deserialize field elements.
     fn try_from_random(bytes: &[u8]) -> Result<Self, FieldError>;
 
-    /// Returns the size of the multiplicative subgroup generated by `generator()`.
-    fn generator_order() -> Self::Integer;
+    /// Converts `2 * BYTES` random bytes into a field element by interpreting them as a
+    /// little-endian integer and reducing it modulo `p`. Unlike `try_from_random()`, this never
+    /// fails on well-formed input, so the number of random bytes consumed per element is fixed.
+    /// The output is statistically close to uniform: its distance from the uniform distribution is
+    /// at most `p / 2^(16 * BYTES)`. The runtime does not depend on the value of the input.
+    ///
+    /// An error is returned if `bytes` is shorter than `2 * BYTES`. Only the first `2 * BYTES`
+    /// bytes are used.
+    fn from_random_rejection_free(bytes: &[u8]) -> Result<Self, FieldError> {
+        if bytes.len() < 2 * Self::BYTES {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        // Horner's method over the bits, from most to least significant.
+        let mut out = Self::zero();
+        for byte in bytes[..2 * Self::BYTES].iter().rev() {
+            for i in (0..8).rev() {
+                out += out;
+                let bit = Choice::from((byte >> i) & 1);
+                out += Self::conditional_select(&Self::zero(), &Self::one(), bit);
+            }
+        }
+        Ok(out)
+    }
 
-    /// Returns the generator of the multiplicative subgroup of size `generator_order()`.
+    /// Returns the generator of a multiplicative subgroup whose order is a power of 2.
     fn generator() -> Self;
 
-    /// Returns the `2^l`-th principal root of unity for any `l <= 20`. Note that the `2^0`-th
-    /// prinicpal root of unity is 1 by definition.
+    /// Returns the `2^l`-th principal root of unity for any `l` such that `2^l` divides the order
+    /// of the generator, or `None` otherwise. Note that the `2^0`-th prinicpal root of unity is 1
+    /// by definition.
     fn root(l: usize) -> Option<Self>;
 
     /// Returns the additive identity.
@@ -110,26 +239,458 @@ pub trait FieldElement:
 
     /// Returns the multiplicative identity.
     fn one() -> Self;
+
+    /// Modular exponentation, i.e., `self^exp (mod p)`, where the exponent is given by its
+    /// big-endian encoding. Unlike `FieldElementWithInteger::pow()`, the exponent may have any
+    /// length. The runtime depends only on the length of `exp_be`, not on its value.
+    fn pow_bytes(&self, exp_be: &[u8]) -> Self {
+        // Left-to-right square-and-multiply.
+        let mut out = Self::one();
+        for byte in exp_be {
+            for i in (0..8).rev() {
+                out *= out;
+                let bit = Choice::from((byte >> i) & 1);
+                out = Self::conditional_select(&out, &(out * *self), bit);
+            }
+        }
+        out
+    }
+
+    /// Returns the hexadecimal encoding of the bytes written by `append_to()`.
+    fn to_hex(&self) -> String {
+        let mut bytes = Vec::with_capacity(Self::BYTES);
+        self.append_to(&mut bytes);
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Decodes a field element from the output of `to_hex()`. Both lower- and upper-case digits
+    /// are accepted. An error is returned if the input is not the hexadecimal encoding of exactly
+    /// `BYTES` bytes or if these encode an integer larger than the field modulus.
+    fn from_hex(hex: &str) -> Result<Self, FieldError> {
+        if hex.len() != 2 * Self::BYTES || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(FieldError::InvalidString);
+        }
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        Self::read_from(&bytes)
+    }
+
+    /// Encodes a slice of field elements as the concatenation of their encodings, as written by
+    /// `append_to()`.
+    fn slice_into_byte_vec(values: &[Self]) -> Vec<u8> {
+        let mut vec = Vec::with_capacity(values.len() * Self::BYTES);
+        for elem in values.iter() {
+            elem.append_to(&mut vec);
+        }
+        vec
+    }
+
+    /// Decodes a vector of field elements from a byte slice encoded by `slice_into_byte_vec()`.
+    /// An error is returned if the length of the input is not a multiple of `BYTES` or if any of
+    /// the chunks encodes an integer larger than the field modulus.
+    fn byte_slice_into_vec(bytes: &[u8]) -> Result<Vec<Self>, FieldError> {
+        let chunks = bytes.chunks_exact(Self::BYTES);
+        if !chunks.remainder().is_empty() {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        chunks.map(Self::read_from).collect()
+    }
+}
+
+/// The byte order in which field elements are encoded. This crate encodes field elements
+/// little-endian, e.g., in `FieldElement::append_to()` and in the output of
+/// [`util::serialize`](crate::util::serialize); `Big` selects the encoding of
+/// `FieldElement::append_to_be()` instead, for exchanging elements with implementations that use
+/// it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The encoding written by `FieldElement::append_to()`.
+    #[default]
+    Little,
+    /// The encoding written by `FieldElement::append_to_be()`.
+    Big,
+}
+
+impl Endianness {
+    /// Writes `elem` to the end of `bytes` in this byte order.
+    pub fn append_to<F: FieldElement>(self, elem: &F, bytes: &mut Vec<u8>) {
+        match self {
+            Endianness::Little => elem.append_to(bytes),
+            Endianness::Big => elem.append_to_be(bytes),
+        }
+    }
+
+    /// Reads an element from the next `F::BYTES` bytes of `bytes` in this byte order.
+    pub fn read_from<F: FieldElement>(self, bytes: &[u8]) -> Result<F, FieldError> {
+        match self {
+            Endianness::Little => F::read_from(bytes),
+            Endianness::Big => F::read_from_be(bytes),
+        }
+    }
+
+    /// Encodes a slice of field elements as the concatenation of their encodings in this byte
+    /// order.
+    pub fn slice_into_byte_vec<F: FieldElement>(self, values: &[F]) -> Vec<u8> {
+        let mut vec = Vec::with_capacity(values.len() * F::BYTES);
+        for elem in values.iter() {
+            self.append_to(elem, &mut vec);
+        }
+        vec
+    }
+
+    /// Decodes a vector of field elements encoded by `slice_into_byte_vec()` in this byte order.
+    /// An error is returned if the length of the input is not a multiple of `F::BYTES` or if any
+    /// of the chunks encodes an integer larger than the field modulus.
+    pub fn byte_slice_into_vec<F: FieldElement>(self, bytes: &[u8]) -> Result<Vec<F>, FieldError> {
+        let chunks = bytes.chunks_exact(F::BYTES);
+        if !chunks.remainder().is_empty() {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        chunks.map(|chunk| self.read_from(chunk)).collect()
+    }
+}
+
+/// Field elements that can be converted to and from a primitive integer type that is large enough
+/// to represent the modulus.
+pub trait FieldElementWithInteger:
+    FieldElement + TryFrom<<Self as FieldElementWithInteger>::Integer, Error = FieldError>
+{
+    /// The error returned if converting `usize` to an `Int` fails.
+    type IntegerTryFromError: Debug;
+
+    /// The integer representation of the field element.
+    type Integer: Copy
+        + Debug
+        + PartialOrd
+        + BitAnd<Output = <Self as FieldElementWithInteger>::Integer>
+        + Div<Output = <Self as FieldElementWithInteger>::Integer>
+        + Shr<Output = <Self as FieldElementWithInteger>::Integer>
+        + Sub<Output = <Self as FieldElementWithInteger>::Integer>
+        + TryFrom<usize, Error = Self::IntegerTryFromError>
+        + TryInto<usize>
+        + From<Self>;
+
+    /// Converts an integer into a field element, reducing it modulo `p`. Use `TryFrom` instead
+    /// if integers larger than or equal to the modulus should be rejected.
+    fn from_reduced(x: Self::Integer) -> Self;
+
+    /// Modular exponentation, i.e., `self^exp (mod p)`. The runtime does not depend on the values
+    /// of `self` or `exp`: the multiplication is carried out for every bit of the exponent and its
+    /// result is selected in constant time.
+    fn pow(&self, exp: Self::Integer) -> Self;
+
+    /// The prime modulus `p`.
+    const MODULUS: Self::Integer;
+
+    /// Returns the prime modulus `p`.
+    fn modulus() -> Self::Integer {
+        Self::MODULUS
+    }
+
+    /// Returns the size of the multiplicative subgroup generated by `generator()`.
+    fn generator_order() -> Self::Integer;
+
+    /// Returns `true` if `self` is a quadratic residue, i.e., if `self = x^2` for some `x`. Zero
+    /// is considered a quadratic residue.
+    fn is_square(&self) -> bool {
+        // Euler's criterion: self is a non-zero square if and only if self^((p-1)/2) = 1.
+        let int_one = Self::Integer::try_from(1).unwrap();
+        let exp = (Self::modulus() - int_one) >> int_one;
+        *self == Self::zero() || self.pow(exp) == Self::one()
+    }
+
+    /// Returns a square root of `self`, i.e., an `x` such that `x^2 = self`, or `None` if `self` is
+    /// not a quadratic residue. Which of the two square roots is returned is unspecified. The
+    /// runtime of this method depends on `self`.
+    ///
+    /// This uses the Tonelli-Shanks algorithm. It relies on the generator having order `2^s`,
+    /// where `2^s` is the largest power of 2 dividing `p - 1`, which is the case for each of the
+    /// fields in this module.
+    fn sqrt(&self) -> Option<Self> {
+        if *self == Self::zero() {
+            return Some(Self::zero());
+        }
+
+        // Write p - 1 = q * 2^s for odd q. The generator c has order 2^s and thus is equal to z^q
+        // for some non-residue z.
+        let int_one = Self::Integer::try_from(1).unwrap();
+        let q = (Self::modulus() - int_one) / Self::generator_order();
+        let mut m = 0;
+        let mut n = Self::generator_order();
+        while n > int_one {
+            n = n >> int_one;
+            m += 1;
+        }
+        let mut c = Self::generator();
+
+        // Since q is odd, (q + 1) / 2 = (q >> 1) + 1.
+        let x = self.pow(q >> int_one);
+        let mut r = x * *self; // self^((q + 1) / 2)
+        let mut t = x * r; // self^q
+
+        // Invariant: r^2 = self * t, the order of t divides 2^m, and c has order 2^m.
+        while t != Self::one() {
+            // Find the least i such that t^(2^i) = 1.
+            let mut i = 0;
+            let mut t_pow = t;
+            while t_pow != Self::one() {
+                t_pow *= t_pow;
+                i += 1;
+                if i == m {
+                    // The order of t is 2^m, which means self is not a square.
+                    return None;
+                }
+            }
+
+            // b = c^(2^(m - i - 1))
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b *= b;
+            }
+
+            m = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+
+        Some(r)
+    }
+}
+
+/// Converts `n` into the integer type of `F`.
+///
+/// # Errors
+///
+/// Fails if `n` does not fit in the integer type, e.g., if it exceeds `u32::MAX` for `Field32`.
+pub fn integer_from_usize<F: FieldElementWithInteger>(n: usize) -> Result<F::Integer, FieldError> {
+    F::Integer::try_from(n).map_err(|_| FieldError::IntegerConversion)
+}
+
+/// Converts an integer of the integer type of `F` into a `usize`.
+///
+/// # Errors
+///
+/// Fails if `x` does not fit in a `usize`, e.g., if it exceeds `u32::MAX` on a 32-bit target.
+pub fn usize_from_integer<F: FieldElementWithInteger>(x: F::Integer) -> Result<usize, FieldError> {
+    x.try_into().map_err(|_| FieldError::IntegerConversion)
+}
+
+/// Converts `n` into a field element, reducing it modulo `p`. This is used to compute constants
+/// such as the inverse of the size of an FFT, for which only `FieldElement` is required.
+pub(crate) fn from_usize<F: FieldElement>(n: usize) -> F {
+    let mut out = F::zero();
+    for i in (0..usize::BITS - n.leading_zeros()).rev() {
+        out += out;
+        if (n >> i) & 1 == 1 {
+            out += F::one();
+        }
+    }
+    out
+}
+
+// Returns `true` if `bits` is smaller than the bit length of the modulus of `F`, in which case
+// every `bits`-bit integer is smaller than the modulus.
+fn bitvector_length_ok<F: FieldElementWithInteger>(bits: usize) -> bool {
+    let int_zero = F::Integer::try_from(0).unwrap();
+    let int_one = F::Integer::try_from(1).unwrap();
+    let mut modulus_bits = 0;
+    let mut p = F::modulus();
+    while p > int_zero {
+        p = p >> int_one;
+        modulus_bits += 1;
+    }
+    bits < modulus_bits
+}
+
+/// Encodes `value` as a vector of `bits` field elements, each of which is 0 or 1. The `l`-th
+/// element is the `l`-th least significant bit of `value`.
+///
+/// # Errors
+///
+/// Fails if `bits` is not smaller than the bit length of the modulus or if `value` does not fit in
+/// `bits` bits.
+pub fn encode_into_bitvector<F: FieldElementWithInteger>(
+    value: F::Integer,
+    bits: usize,
+) -> Result<Vec<F>, FieldError> {
+    if !bitvector_length_ok::<F>(bits) {
+        return Err(FieldError::BitVectorTooLong);
+    }
+
+    let int_zero = F::Integer::try_from(0).unwrap();
+    let int_one = F::Integer::try_from(1).unwrap();
+    if value >> integer_from_usize::<F>(bits)? != int_zero {
+        return Err(FieldError::IntegerTooLarge);
+    }
+
+    (0..bits)
+        .map(|l| {
+            Ok(F::from_reduced(
+                (value >> integer_from_usize::<F>(l)?) & int_one,
+            ))
+        })
+        .collect()
+}
+
+/// Decodes a vector of field elements output by [`encode_into_bitvector`] into an integer.
+///
+/// # Errors
+///
+/// Fails if the length of `input` is not smaller than the bit length of the modulus or if one of
+/// its entries is neither 0 nor 1.
+pub fn decode_bitvector<F: FieldElementWithInteger>(input: &[F]) -> Result<F::Integer, FieldError> {
+    if !bitvector_length_ok::<F>(input.len()) {
+        return Err(FieldError::BitVectorTooLong);
+    }
+
+    let mut out = F::zero();
+    let mut weight = F::one();
+    for bit in input {
+        if *bit != F::zero() && *bit != F::one() {
+            return Err(FieldError::InvalidBit);
+        }
+        out += weight * *bit;
+        weight += weight;
+    }
+    Ok(F::Integer::from(out))
+}
+
+/// Converts an aggregate, e.g., the vector obtained by reconstructing the `total_shares()` of the
+/// servers, into integers. Each entry is checked against `bound`, the largest value the sum can
+/// take for valid inputs. A larger value means that the sum wrapped around the modulus, or that
+/// the aggregate was not reconstructed correctly.
+///
+/// # Errors
+///
+/// Fails if any entry of `aggregate` is larger than `bound`.
+pub fn decode_aggregate<F: FieldElementWithInteger>(
+    aggregate: &[F],
+    bound: F::Integer,
+) -> Result<Vec<F::Integer>, FieldError> {
+    aggregate
+        .iter()
+        .map(|x| {
+            let int = F::Integer::from(*x);
+            if int > bound {
+                return Err(FieldError::AggregateOutOfBounds);
+            }
+            Ok(int)
+        })
+        .collect()
+}
+
+// Checks that `s` is a non-empty string of decimal digits.
+fn check_decimal(s: &str) -> Result<(), FieldError> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(FieldError::InvalidString);
+    }
+    Ok(())
+}
+
+/// Field elements whose sums can be accumulated in a `u128` without reducing modulo `p` after each
+/// addition. See [`FieldAccumulator`].
+pub trait LazyAdd: FieldElement {
+    /// The number of field elements that can be summed with `to_wide()` without overflowing.
+    const LAZY_ADD_CAPACITY: u128;
+
+    /// Returns the internal representation of the field element as an integer.
+    fn to_wide(&self) -> u128;
+
+    /// Returns the field element corresponding to a sum of outputs of `to_wide()`.
+    fn from_wide(sum: u128) -> Self;
+}
+
+// Field elements are serialized as their canonical encoding, i.e., the `BYTES`-byte little-endian
+// encoding of an integer in `[0, p)` written by `append_to()`. Deserialization accepts either a
+// byte string or a sequence of bytes (for formats without a native byte string type) and rejects
+// encodings of integers larger than or equal to the modulus.
+struct FieldElementVisitor<F>(PhantomData<F>);
+
+impl<'de, F: FieldElement> Visitor<'de> for FieldElementVisitor<F> {
+    type Value = F;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "the {}-byte encoding of a field element", F::BYTES)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<F, E> {
+        if v.len() != F::BYTES {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        F::read_from(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<F, A::Error> {
+        let mut bytes = Vec::with_capacity(F::BYTES);
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if bytes.len() == F::BYTES {
+                return Err(A::Error::invalid_length(F::BYTES + 1, &self));
+            }
+            bytes.push(byte);
+        }
+        if bytes.len() != F::BYTES {
+            return Err(A::Error::invalid_length(bytes.len(), &self));
+        }
+        F::read_from(&bytes).map_err(A::Error::custom)
+    }
+}
+
+// Samples a uniformly distributed field element by rejection sampling.
+#[cfg(feature = "rand")]
+fn sample_uniform<F: FieldElement, R: rand::Rng + ?Sized>(rng: &mut R) -> F {
+    let mut buf = [0; 32];
+    let bytes = &mut buf[..F::BYTES];
+    loop {
+        rng.fill_bytes(bytes);
+        if let Ok(x) = F::try_from_random(bytes) {
+            return x;
+        }
+    }
+}
+
+/// Scalars up to this value are multiplied by `mul_small()` rather than by converting them into
+/// field elements.
+const MUL_SMALL_MAX: u64 = 16;
+
+// Computes `x * c` by double-and-add: one doubling per bit of `c` and one addition per set bit,
+// i.e., at most `2 * b` additions for a `b`-bit `c`. For `c <= MUL_SMALL_MAX`, `b <= 5`, which is
+// faster than a modular multiplication. The runtime depends on `c`.
+fn mul_small<F: FieldElement>(x: F, c: u64) -> F {
+    let mut out = F::zero();
+    for i in (0..64 - c.leading_zeros()).rev() {
+        out += out;
+        if (c >> i) & 1 == 1 {
+            out += x;
+        }
+    }
+    out
 }
 
 macro_rules! make_field {
     (
         $(#[$meta:meta])*
-        $elem:ident, $int:ident, $fp:ident, $bytes:literal
+        $elem:ident, $int:ident, $repr:ident, $fp:ident, $bytes:literal
     ) => {
         $(#[$meta])*
-        #[derive(Clone, Copy, PartialOrd, Ord, Hash, Default, Deserialize, Serialize)]
-        pub struct $elem(u128);
+        //
+        // The representation is transparent so that the bulk routines can pass slices of elements
+        // to vector kernels as slices of `$repr`.
+        #[derive(Clone, Copy, PartialOrd, Ord, Hash, Default)]
+        #[repr(transparent)]
+        pub struct $elem($repr);
 
         impl $elem {
-            fn try_from_bytes(bytes: &[u8], mask: u128) -> Result<Self, FieldError> {
+            fn try_from_bytes(bytes: &[u8], mask: $repr) -> Result<Self, FieldError> {
                 if Self::BYTES > bytes.len() {
                     return Err(FieldError::FromBytesShortRead);
                 }
 
                 let mut int = 0;
                 for i in 0..Self::BYTES {
-                    int |= (bytes[i] as u128) << (i << 3);
+                    int |= (bytes[i] as $repr) << (i << 3);
                 }
 
                 int &= mask;
@@ -139,14 +700,41 @@ macro_rules! make_field {
                 }
                 Ok(Self($fp.elem(int)))
             }
+
+            fn to_bytes(self) -> [u8; $bytes] {
+                let int = $fp.from_elem(self.0);
+                let mut slice = [0; $bytes];
+                for i in 0..$bytes {
+                    slice[i] = ((int >> (i << 3)) & 0xff) as u8;
+                }
+                slice
+            }
         }
 
         impl PartialEq for $elem {
             fn eq(&self, rhs: &Self) -> bool {
-                $fp.from_elem(self.0) == $fp.from_elem(rhs.0)
+                self.ct_eq(rhs).into()
+            }
+        }
+
+        impl ConstantTimeEq for $elem {
+            fn ct_eq(&self, rhs: &Self) -> Choice {
+                // The internal representation of each field element is unique, so there is no need
+                // to map the operands out of the Montgomery domain.
+                self.0.ct_eq(&rhs.0)
+            }
+        }
+
+        impl ConditionallySelectable for $elem {
+            fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+                Self($repr::conditional_select(&a.0, &b.0, choice))
             }
         }
 
+        // The default value is the additive identity, whose internal representation is 0.
+        #[cfg(feature = "zeroize")]
+        impl zeroize::DefaultIsZeroes for $elem {}
+
         impl Eq for $elem {}
 
         impl Add for $elem {
@@ -209,6 +797,22 @@ macro_rules! make_field {
             }
         }
 
+        impl Mul<u64> for $elem {
+            type Output = $elem;
+            fn mul(self, rhs: u64) -> Self {
+                if rhs <= MUL_SMALL_MAX {
+                    return mul_small(self, rhs);
+                }
+                self * Self($fp.elem((rhs as u128 % $fp.p as u128) as $repr))
+            }
+        }
+
+        impl MulAssign<u64> for $elem {
+            fn mul_assign(&mut self, rhs: u64) {
+                *self = *self * rhs;
+            }
+        }
+
         impl Div for $elem {
             type Output = $elem;
             fn div(self, rhs: Self) -> Self {
@@ -243,21 +847,148 @@ macro_rules! make_field {
             }
         }
 
-        impl From<$int> for $elem {
-            fn from(x: $int) -> Self {
-                Self($fp.elem(u128::try_from(x).unwrap()))
+        impl Sum for $elem {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::zero(), Add::add)
+            }
+        }
+
+        impl<'a> Sum<&'a $elem> for $elem {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.copied().sum()
+            }
+        }
+
+        impl Product for $elem {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::one(), Mul::mul)
+            }
+        }
+
+        impl<'a> Product<&'a $elem> for $elem {
+            fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.copied().product()
+            }
+        }
+
+        impl TryFrom<$int> for $elem {
+            type Error = FieldError;
+
+            fn try_from(x: $int) -> Result<Self, FieldError> {
+                let int = $repr::from(x);
+                if int >= $fp.p {
+                    return Err(FieldError::ModulusOverflow);
+                }
+                Ok(Self($fp.elem(int)))
             }
         }
 
         impl From<$elem> for $int {
             fn from(x: $elem) -> Self {
-                $int::try_from($fp.from_elem(x.0)).unwrap()
+                $int::from($fp.from_elem(x.0))
+            }
+        }
+
+        impl From<i64> for $elem {
+            /// Maps `x` to `x (mod p)`. In particular, a negative `x` with `|x| < p` is mapped to
+            /// `p - |x|`.
+            fn from(x: i64) -> Self {
+                let abs = Self($fp.elem((u128::from(x.unsigned_abs()) % $fp.p as u128) as $repr));
+                Self::conditional_select(&abs, &-abs, Choice::from((x < 0) as u8))
+            }
+        }
+
+        impl TryFrom<i128> for $elem {
+            type Error = FieldError;
+
+            /// Maps `x` to `x (mod p)`. In particular, a negative `x` is mapped to `p - |x|`. An
+            /// error is returned if `|x|` is larger than or equal to the modulus.
+            fn try_from(x: i128) -> Result<Self, FieldError> {
+                let abs = x.unsigned_abs();
+                if abs >= $fp.p as u128 {
+                    return Err(FieldError::ModulusOverflow);
+                }
+                let abs = Self($fp.elem(abs as $repr));
+                Ok(Self::conditional_select(&abs, &-abs, Choice::from((x < 0) as u8)))
+            }
+        }
+
+        impl $elem {
+            /// Decodes an element as a signed integer whose absolute value is at most `bound`,
+            /// i.e., inverts the conversions from `i64` and `i128`. The element `x` is decoded as
+            /// `x` if `x <= bound` and as `x - p` if `p - x <= bound`. The decoding is unique if
+            /// `bound` is smaller than `p / 2`.
+            ///
+            /// # Errors
+            ///
+            /// Fails if neither of these holds, or if the result does not fit in an `i128`.
+            pub fn to_signed(&self, bound: u128) -> Result<i128, FieldError> {
+                let x = $fp.from_elem(self.0) as u128;
+                let p = $fp.p as u128;
+                if x <= bound {
+                    i128::try_from(x).map_err(|_| FieldError::SignedOutOfBounds)
+                } else if p - x <= bound {
+                    i128::try_from(p - x)
+                        .map(|abs| -abs)
+                        .map_err(|_| FieldError::SignedOutOfBounds)
+                } else {
+                    Err(FieldError::SignedOutOfBounds)
+                }
             }
         }
 
         impl PartialEq<$int> for $elem {
             fn eq(&self, rhs: &$int) -> bool {
-                $fp.from_elem(self.0) == u128::try_from(*rhs).unwrap()
+                $fp.from_elem(self.0) == $repr::from(*rhs)
+            }
+        }
+
+        impl Serialize for $elem {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(&self.to_bytes())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $elem {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_bytes(FieldElementVisitor(PhantomData))
+            }
+        }
+
+        #[cfg(feature = "rand")]
+        impl rand::distributions::Distribution<$elem> for rand::distributions::Standard {
+            fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> $elem {
+                sample_uniform(rng)
+            }
+        }
+
+        #[cfg(feature = "fuzz")]
+        impl<'a> arbitrary::Arbitrary<'a> for $elem {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                // Reduce the integer so that every input yields a valid element.
+                let int = u.arbitrary::<$repr>()? % $fp.p;
+                Ok(Self($fp.elem(int)))
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <$repr as arbitrary::Arbitrary>::size_hint(depth)
+            }
+        }
+
+        impl FromStr for $elem {
+            type Err = FieldError;
+
+            /// Parses the decimal representation of an integer smaller than the modulus, as
+            /// written by `Display`.
+            fn from_str(s: &str) -> Result<Self, FieldError> {
+                check_decimal(s)?;
+                // The input consists of digits only, so parsing fails only if the integer is too
+                // large for the internal representation.
+                let int = s.parse::<$repr>().map_err(|_| FieldError::ModulusOverflow)?;
+                if int >= $fp.p {
+                    return Err(FieldError::ModulusOverflow);
+                }
+                Ok(Self($fp.elem(int)))
             }
         }
 
@@ -273,34 +1004,42 @@ macro_rules! make_field {
             }
         }
 
-        impl FieldElement for $elem {
-            const BYTES: usize = $bytes;
-            type Integer = $int;
-            type IntegerTryFromError = <Self::Integer as TryFrom<usize>>::Error;
+        impl LazyAdd for $elem {
+            const LAZY_ADD_CAPACITY: u128 = u128::MAX / ($fp.p as u128 - 1);
 
-            fn pow(&self, exp: Self::Integer) -> Self {
-                Self($fp.pow(self.0, u128::try_from(exp).unwrap()))
+            fn to_wide(&self) -> u128 {
+                self.0 as u128
             }
 
-            fn inv(&self) -> Self {
-                Self($fp.inv(self.0))
+            fn from_wide(sum: u128) -> Self {
+                Self($fp.reduce_wide(sum))
             }
+        }
+
+        impl FieldElement for $elem {
+            const BYTES: usize = $bytes;
+            const ID: FieldId = FieldId::$elem;
 
-            fn modulus() -> Self::Integer {
-                $fp.p as $int
+            fn inv(&self) -> Self {
+                Self($fp.inv(self.0))
             }
 
             fn append_to(&self, bytes: &mut Vec<u8>) {
-                let int = $fp.from_elem(self.0);
-                let mut slice = [0; Self::BYTES];
-                for i in 0..Self::BYTES {
-                    slice[i] = ((int >> (i << 3)) & 0xff) as u8;
-                }
-                bytes.extend_from_slice(&slice);
+                bytes.extend_from_slice(&self.to_bytes());
             }
 
             fn read_from(bytes: &[u8]) -> Result<Self, FieldError> {
-                $elem::try_from_bytes(bytes, u128::MAX)
+                $elem::try_from_bytes(bytes, $repr::MAX)
+            }
+
+            fn encode_into<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_bytes())
+            }
+
+            fn decode_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; $bytes];
+                reader.read_exact(&mut bytes)?;
+                Self::read_from(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
             }
 
             fn try_from_random(bytes: &[u8]) -> Result<Self, FieldError> {
@@ -311,16 +1050,19 @@ macro_rules! make_field {
                 Self($fp.g)
             }
 
-            fn generator_order() -> Self::Integer {
-                1 << (Self::Integer::try_from($fp.num_roots).unwrap())
-            }
-
             fn root(l: usize) -> Option<Self> {
-                if l < min($fp.roots.len(), $fp.num_roots+1) {
-                    Some(Self($fp.roots[l]))
-                } else {
-                    None
+                if l > $fp.num_roots {
+                    return None;
+                }
+                if l < $fp.roots.len() {
+                    return Some(Self($fp.roots[l]));
                 }
+                // Roots beyond the precomputed table are computed by squaring the generator.
+                let mut x = $fp.g;
+                for _ in l..$fp.num_roots {
+                    x = $fp.mul(x, x);
+                }
+                Some(Self(x))
             }
 
             fn zero() -> Self {
@@ -331,13 +1073,34 @@ macro_rules! make_field {
                 Self($fp.roots[0])
             }
         }
+
+        impl FieldElementWithInteger for $elem {
+            type Integer = $int;
+            type IntegerTryFromError = <Self::Integer as TryFrom<usize>>::Error;
+
+            const MODULUS: Self::Integer = $fp.p as $int;
+
+            fn from_reduced(x: Self::Integer) -> Self {
+                Self($fp.elem($repr::from(x)))
+            }
+
+            fn pow(&self, exp: Self::Integer) -> Self {
+                Self($fp.pow(self.0, $repr::from(exp)))
+            }
+
+            fn generator_order() -> Self::Integer {
+                1 << $fp.num_roots
+            }
+        }
     };
 }
 
 make_field!(
-    /// `GF(4293918721)`, a 32-bit field. The generator has order `2^20`.
+    /// `GF(4293918721)`, a 32-bit field. The generator has order `2^20`. Elements are represented
+    /// by a `u32`, so arithmetic never requires 128-bit multiplication.
     Field32,
     u32,
+    u32,
     FP32,
     4
 );
@@ -347,6 +1110,7 @@ make_field!(
     /// change.)** `GF(15564440312192434177)`, a 64-bit field. The generator has order `2^59`.
     Field64,
     u64,
+    u64,
     FP64,
     8
 );
@@ -356,6 +1120,7 @@ make_field!(
     ///  change.)** `GF(779190469673491460259841)`, an 80-bit field. The generator has order `2^72`.
     Field80,
     u128,
+    u128,
     FP80,
     10
 );
@@ -366,10 +1131,867 @@ make_field!(
     ///  has order `2^118`.
     Field126,
     u128,
+    u128,
     FP126,
     16
 );
 
+make_field!(
+    /// `GF(340282366920938462946865773367900766209)`, a 128-bit field. The generator has order
+    /// `2^66`. These are the parameters of the 128-bit field specified in
+    /// [draft-irtf-cfrg-vdaf](https://datatracker.ietf.org/doc/draft-irtf-cfrg-vdaf/), i.e.,
+    /// `p = 2^66 * 4611686018427387897 + 1` with generator `7^4611686018427387897`.
+    Field128,
+    u128,
+    u128,
+    FP128,
+    16
+);
+
+make_field!(
+    /// `GF(2305843009213693951)`, a 61-bit field with Mersenne prime modulus `2^61 - 1`.
+    /// Multiplication is reduced with a shift and an addition rather than Montgomery reduction,
+    /// making this the fastest field in this module. The generator has order `2^1`, so this field
+    /// is not suitable for FFT-based polynomial arithmetic beyond inputs of size 2.
+    Field61,
+    u64,
+    u64,
+    FP61,
+    8
+);
+
+/// `GF(2^255 - 19)`, a 255-bit field. The generator has order `2^2`.
+///
+/// Unlike the other fields in this module, the modulus of this field does not fit in a `u128`.
+/// Elements are represented by four 64-bit limbs in little-endian order. As a result, `Field255`
+/// implements [`FieldElement`] but not [`FieldElementWithInteger`]; exponentiation and the modulus
+/// are provided as inherent methods over limbs instead.
+#[derive(Clone, Copy, Default)]
+pub struct Field255([u64; LIMBS]);
+
+impl Field255 {
+    fn try_from_bytes(bytes: &[u8], mask: u64) -> Result<Self, FieldError> {
+        if Self::BYTES > bytes.len() {
+            return Err(FieldError::FromBytesShortRead);
+        }
+
+        let mut int = [0; LIMBS];
+        for (i, chunk) in bytes[..Self::BYTES].chunks_exact(8).enumerate() {
+            int[i] = u64::from_le_bytes(<[u8; 8]>::try_from(chunk).unwrap());
+        }
+
+        int[LIMBS - 1] &= mask;
+
+        if !FP255.is_reduced(&int) {
+            return Err(FieldError::FromBytesModulusOverflow);
+        }
+        Ok(Self(int))
+    }
+
+    /// Modular exponentation, i.e., `self^exp (mod p)`. The exponent is given by four 64-bit limbs
+    /// in little-endian order. The runtime does not depend on the values of `self`
+    /// or `exp`.
+    pub fn pow(&self, exp: [u64; LIMBS]) -> Self {
+        Self(FP255.pow(&self.0, &exp))
+    }
+
+    /// The prime modulus `p` as four 64-bit limbs in little-endian order.
+    pub const MODULUS: [u64; LIMBS] = FP255.p;
+
+    /// Returns the prime modulus `p` as four 64-bit limbs in little-endian order.
+    pub fn modulus() -> [u64; LIMBS] {
+        Self::MODULUS
+    }
+}
+
+impl FieldElement for Field255 {
+    const BYTES: usize = 32;
+    const ID: FieldId = FieldId::Field255;
+
+    fn inv(&self) -> Self {
+        Self(FP255.inv(&self.0))
+    }
+
+    fn append_to(&self, bytes: &mut Vec<u8>) {
+        for limb in self.0.iter() {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    fn read_from(bytes: &[u8]) -> Result<Self, FieldError> {
+        Self::try_from_bytes(bytes, u64::MAX)
+    }
+
+    fn encode_into<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for limb in self.0.iter() {
+            writer.write_all(&limb.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn decode_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; 32];
+        reader.read_exact(&mut bytes)?;
+        Self::read_from(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn try_from_random(bytes: &[u8]) -> Result<Self, FieldError> {
+        Self::try_from_bytes(bytes, u64::MAX >> 1)
+    }
+
+    fn generator() -> Self {
+        Self(FP255.g)
+    }
+
+    fn root(l: usize) -> Option<Self> {
+        if l < min(FP255.roots.len(), FP255.num_roots + 1) {
+            Some(Self(FP255.roots[l]))
+        } else {
+            None
+        }
+    }
+
+    fn zero() -> Self {
+        Self([0; LIMBS])
+    }
+
+    fn one() -> Self {
+        Self(FP255.roots[0])
+    }
+}
+
+impl PartialEq for Field255 {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.ct_eq(rhs).into()
+    }
+}
+
+impl Eq for Field255 {}
+
+impl Hash for Field255 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Elements are fully reduced, so equal elements have equal limbs.
+        self.0.hash(state);
+    }
+}
+
+impl ConstantTimeEq for Field255 {
+    fn ct_eq(&self, rhs: &Self) -> Choice {
+        self.0[..].ct_eq(&rhs.0[..])
+    }
+}
+
+impl ConditionallySelectable for Field255 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut out = *a;
+        for (o, b) in out.0.iter_mut().zip(b.0.iter()) {
+            o.conditional_assign(b, choice);
+        }
+        out
+    }
+}
+
+impl Serialize for Field255 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Field255 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(FieldElementVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Field255 {}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Field255> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Field255 {
+        sample_uniform(rng)
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl<'a> arbitrary::Arbitrary<'a> for Field255 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut int = u.arbitrary::<[u64; LIMBS]>()?;
+        int[LIMBS - 1] &= u64::MAX >> 1;
+        // Fewer than one in 2^250 inputs are rejected here.
+        if !FP255.is_reduced(&int) {
+            return Err(arbitrary::Error::IncorrectFormat);
+        }
+        Ok(Self(int))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u64; LIMBS] as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+impl Add for Field255 {
+    type Output = Field255;
+    fn add(self, rhs: Self) -> Self {
+        Self(FP255.add(&self.0, &rhs.0))
+    }
+}
+
+impl Add for &Field255 {
+    type Output = Field255;
+    fn add(self, rhs: Self) -> Field255 {
+        *self + *rhs
+    }
+}
+
+impl AddAssign for Field255 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Field255 {
+    type Output = Field255;
+    fn sub(self, rhs: Self) -> Self {
+        Self(FP255.sub(&self.0, &rhs.0))
+    }
+}
+
+impl Sub for &Field255 {
+    type Output = Field255;
+    fn sub(self, rhs: Self) -> Field255 {
+        *self - *rhs
+    }
+}
+
+impl SubAssign for Field255 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Field255 {
+    type Output = Field255;
+    fn mul(self, rhs: Self) -> Self {
+        Self(FP255.mul(&self.0, &rhs.0))
+    }
+}
+
+impl Mul for &Field255 {
+    type Output = Field255;
+    fn mul(self, rhs: Self) -> Field255 {
+        *self * *rhs
+    }
+}
+
+impl MulAssign for Field255 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<u64> for Field255 {
+    type Output = Field255;
+    fn mul(self, rhs: u64) -> Self {
+        if rhs <= MUL_SMALL_MAX {
+            return mul_small(self, rhs);
+        }
+        self * Self::from(rhs)
+    }
+}
+
+impl MulAssign<u64> for Field255 {
+    fn mul_assign(&mut self, rhs: u64) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for Field255 {
+    type Output = Field255;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl Div for &Field255 {
+    type Output = Field255;
+    fn div(self, rhs: Self) -> Field255 {
+        *self / *rhs
+    }
+}
+
+impl DivAssign for Field255 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Field255 {
+    type Output = Field255;
+    fn neg(self) -> Self {
+        Self(FP255.neg(&self.0))
+    }
+}
+
+impl Neg for &Field255 {
+    type Output = Field255;
+    fn neg(self) -> Field255 {
+        -(*self)
+    }
+}
+
+impl Sum for Field255 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Field255> for Field255 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Product for Field255 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Field255> for Field255 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+impl From<u64> for Field255 {
+    fn from(x: u64) -> Self {
+        Self([x, 0, 0, 0])
+    }
+}
+
+impl From<u128> for Field255 {
+    fn from(x: u128) -> Self {
+        Self([x as u64, (x >> 64) as u64, 0, 0])
+    }
+}
+
+impl Display for Field255 {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        // Convert to decimal by repeatedly dividing by the largest power of 10 that fits in a u64.
+        const TEN_POW_19: u64 = 10_000_000_000_000_000_000;
+        let mut int = self.0;
+        let mut digits = Vec::new();
+        loop {
+            let mut rem = 0u128;
+            for limb in int.iter_mut().rev() {
+                let t = (rem << 64) | (*limb as u128);
+                *limb = (t / TEN_POW_19 as u128) as u64;
+                rem = t % TEN_POW_19 as u128;
+            }
+            digits.push(rem as u64);
+            if int.iter().all(|limb| *limb == 0) {
+                break;
+            }
+        }
+
+        let mut iter = digits.iter().rev();
+        write!(f, "{}", iter.next().unwrap())?;
+        for d in iter {
+            write!(f, "{:019}", d)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Field255 {
+    type Err = FieldError;
+
+    /// Parses the decimal representation of an integer smaller than the modulus, as written by
+    /// `Display`.
+    fn from_str(s: &str) -> Result<Self, FieldError> {
+        check_decimal(s)?;
+        let mut int = [0; LIMBS];
+        for c in s.bytes() {
+            // int = 10 * int + digit
+            let mut carry = u128::from(c - b'0');
+            for limb in int.iter_mut() {
+                let t = 10 * u128::from(*limb) + carry;
+                *limb = t as u64;
+                carry = t >> 64;
+            }
+            if carry != 0 {
+                return Err(FieldError::ModulusOverflow);
+            }
+        }
+        if !FP255.is_reduced(&int) {
+            return Err(FieldError::ModulusOverflow);
+        }
+        Ok(Self(int))
+    }
+}
+
+impl Debug for Field255 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// `GF(p^2)` for the modulus `p` of [`Field64`], a field of size roughly `2^128`. Elements are
+/// represented as `c0 + c1 * x`, where `c0` and `c1` are elements of `Field64` and `x^2 = 5`.
+///
+/// This allows protocols to secret share inputs over `Field64` while drawing verification
+/// challenges from a larger field, which reduces the soundness error. Elements of `Field64` are
+/// embedded with `From<Field64>`. The generator and roots of unity are those of `Field64`.
+#[derive(Clone, Copy, Default)]
+pub struct Field64x2([Field64; 2]);
+
+impl Field64x2 {
+    /// Returns the element `c0 + c1 * x`.
+    pub fn new(c0: Field64, c1: Field64) -> Self {
+        Self([c0, c1])
+    }
+
+    /// Returns the coefficients `[c0, c1]` of `self = c0 + c1 * x`.
+    pub fn coefficients(&self) -> [Field64; 2] {
+        self.0
+    }
+
+    // Multiplies by `x^2 = 5`, a quadratic non-residue modulo `p`.
+    fn mul_by_non_residue(a: Field64) -> Field64 {
+        let a2 = a + a;
+        a2 + a2 + a
+    }
+
+    fn try_from_bytes(
+        bytes: &[u8],
+        read: fn(&[u8]) -> Result<Field64, FieldError>,
+    ) -> Result<Self, FieldError> {
+        if Self::BYTES > bytes.len() {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        Ok(Self([
+            read(&bytes[..Field64::BYTES])?,
+            read(&bytes[Field64::BYTES..Self::BYTES])?,
+        ]))
+    }
+}
+
+impl FieldElement for Field64x2 {
+    const BYTES: usize = 2 * Field64::BYTES;
+    const ID: FieldId = FieldId::Field64x2;
+
+    fn inv(&self) -> Self {
+        // (c0 + c1 * x)^-1 = (c0 - c1 * x) / (c0^2 - 5 * c1^2)
+        let [c0, c1] = self.0;
+        let norm_inv = (c0 * c0 - Self::mul_by_non_residue(c1 * c1)).inv();
+        Self([c0 * norm_inv, -c1 * norm_inv])
+    }
+
+    fn append_to(&self, bytes: &mut Vec<u8>) {
+        self.0[0].append_to(bytes);
+        self.0[1].append_to(bytes);
+    }
+
+    fn read_from(bytes: &[u8]) -> Result<Self, FieldError> {
+        Self::try_from_bytes(bytes, Field64::read_from)
+    }
+
+    // The coefficients are written in the same order as by `append_to()`, each in big-endian byte
+    // order, rather than reversing the whole encoding.
+    fn append_to_be(&self, bytes: &mut Vec<u8>) {
+        self.0[0].append_to_be(bytes);
+        self.0[1].append_to_be(bytes);
+    }
+
+    fn read_from_be(bytes: &[u8]) -> Result<Self, FieldError> {
+        Self::try_from_bytes(bytes, Field64::read_from_be)
+    }
+
+    fn encode_into<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0[0].encode_into(writer)?;
+        self.0[1].encode_into(writer)
+    }
+
+    fn decode_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self([
+            Field64::decode_from(reader)?,
+            Field64::decode_from(reader)?,
+        ]))
+    }
+
+    fn try_from_random(bytes: &[u8]) -> Result<Self, FieldError> {
+        Self::try_from_bytes(bytes, Field64::try_from_random)
+    }
+
+    // Each coefficient is sampled from `2 * Field64::BYTES` bytes.
+    fn from_random_rejection_free(bytes: &[u8]) -> Result<Self, FieldError> {
+        if bytes.len() < 2 * Self::BYTES {
+            return Err(FieldError::FromBytesShortRead);
+        }
+        let (c0, c1) = bytes[..2 * Self::BYTES].split_at(Self::BYTES);
+        Ok(Self([
+            Field64::from_random_rejection_free(c0)?,
+            Field64::from_random_rejection_free(c1)?,
+        ]))
+    }
+
+    fn generator() -> Self {
+        Field64::generator().into()
+    }
+
+    fn root(l: usize) -> Option<Self> {
+        Field64::root(l).map(Self::from)
+    }
+
+    fn zero() -> Self {
+        Field64::zero().into()
+    }
+
+    fn one() -> Self {
+        Field64::one().into()
+    }
+}
+
+impl From<Field64> for Field64x2 {
+    fn from(c0: Field64) -> Self {
+        Self([c0, Field64::zero()])
+    }
+}
+
+impl PartialEq for Field64x2 {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.ct_eq(rhs).into()
+    }
+}
+
+impl Eq for Field64x2 {}
+
+impl Hash for Field64x2 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0[0].hash(state);
+        self.0[1].hash(state);
+    }
+}
+
+impl ConstantTimeEq for Field64x2 {
+    fn ct_eq(&self, rhs: &Self) -> Choice {
+        self.0[0].ct_eq(&rhs.0[0]) & self.0[1].ct_eq(&rhs.0[1])
+    }
+}
+
+impl ConditionallySelectable for Field64x2 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self([
+            Field64::conditional_select(&a.0[0], &b.0[0], choice),
+            Field64::conditional_select(&a.0[1], &b.0[1], choice),
+        ])
+    }
+}
+
+impl Serialize for Field64x2 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(Self::BYTES);
+        self.append_to(&mut bytes);
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Field64x2 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(FieldElementVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Field64x2 {}
+
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Field64x2> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Field64x2 {
+        sample_uniform(rng)
+    }
+}
+
+#[cfg(feature = "fuzz")]
+impl<'a> arbitrary::Arbitrary<'a> for Field64x2 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(u.arbitrary()?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[Field64; 2] as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+impl Add for Field64x2 {
+    type Output = Field64x2;
+    fn add(self, rhs: Self) -> Self {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl Add for &Field64x2 {
+    type Output = Field64x2;
+    fn add(self, rhs: Self) -> Field64x2 {
+        *self + *rhs
+    }
+}
+
+impl AddAssign for Field64x2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Field64x2 {
+    type Output = Field64x2;
+    fn sub(self, rhs: Self) -> Self {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
+impl Sub for &Field64x2 {
+    type Output = Field64x2;
+    fn sub(self, rhs: Self) -> Field64x2 {
+        *self - *rhs
+    }
+}
+
+impl SubAssign for Field64x2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Field64x2 {
+    type Output = Field64x2;
+    fn mul(self, rhs: Self) -> Self {
+        // Karatsuba: c1 = (a0 + a1)(b0 + b1) - a0 * b0 - a1 * b1.
+        let [a0, a1] = self.0;
+        let [b0, b1] = rhs.0;
+        let v0 = a0 * b0;
+        let v1 = a1 * b1;
+        Self([
+            v0 + Self::mul_by_non_residue(v1),
+            (a0 + a1) * (b0 + b1) - v0 - v1,
+        ])
+    }
+}
+
+impl Mul for &Field64x2 {
+    type Output = Field64x2;
+    fn mul(self, rhs: Self) -> Field64x2 {
+        *self * *rhs
+    }
+}
+
+impl MulAssign for Field64x2 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<u64> for Field64x2 {
+    type Output = Field64x2;
+    fn mul(self, rhs: u64) -> Self {
+        Self([self.0[0] * rhs, self.0[1] * rhs])
+    }
+}
+
+impl MulAssign<u64> for Field64x2 {
+    fn mul_assign(&mut self, rhs: u64) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for Field64x2 {
+    type Output = Field64x2;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl Div for &Field64x2 {
+    type Output = Field64x2;
+    fn div(self, rhs: Self) -> Field64x2 {
+        *self / *rhs
+    }
+}
+
+impl DivAssign for Field64x2 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Field64x2 {
+    type Output = Field64x2;
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
+
+impl Neg for &Field64x2 {
+    type Output = Field64x2;
+    fn neg(self) -> Field64x2 {
+        -(*self)
+    }
+}
+
+impl Sum for Field64x2 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Field64x2> for Field64x2 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Product for Field64x2 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl<'a> Product<&'a Field64x2> for Field64x2 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+impl Display for Field64x2 {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} + {}x", self.0[0], self.0[1])
+    }
+}
+
+impl FromStr for Field64x2 {
+    type Err = FieldError;
+
+    /// Parses an element written as `"c0 + c1x"` by `Display`, or an element `c0` of the base field
+    /// written as a decimal integer.
+    fn from_str(s: &str) -> Result<Self, FieldError> {
+        match s.strip_suffix('x') {
+            Some(rest) => {
+                let (c0, c1) = rest.split_once(" + ").ok_or(FieldError::InvalidString)?;
+                Ok(Self([c0.parse()?, c1.parse()?]))
+            }
+            None => Ok(Self::from(s.parse::<Field64>()?)),
+        }
+    }
+}
+
+impl Debug for Field64x2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+// The wire encoding of a field element is the fixed-length encoding written by `append_to()`.
+macro_rules! impl_field_codec {
+    ($($elem:ident),*) => {
+        $(
+            impl Encode for $elem {
+                fn encode(&self, bytes: &mut Vec<u8>) {
+                    self.append_to(bytes);
+                }
+
+                fn encoded_len(&self) -> Option<usize> {
+                    Some(Self::ENCODED_LEN)
+                }
+            }
+
+            impl Decode for $elem {
+                fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+                    let mut value = [0; <$elem as FieldElement>::ENCODED_LEN];
+                    bytes.read_exact(&mut value)?;
+                    Self::read_from(&value).map_err(|e| CodecError::Other(Box::new(e)))
+                }
+            }
+        )*
+    };
+}
+
+impl_field_codec!(Field32, Field64, Field80, Field126, Field128, Field61, Field255, Field64x2);
+
+/// Sums vectors of field elements, deferring modular reduction for as long as possible. For small
+/// fields this is considerably faster than summing with `merge_vector()`, since the unreduced sums
+/// only have to be reduced once, when the result is output.
+#[derive(Clone, Debug)]
+pub struct FieldAccumulator<F: LazyAdd> {
+    sums: Vec<u128>,
+    // The maximum number of field elements that contributed to any of the sums since they were
+    // last reduced.
+    count: u128,
+    phantom: PhantomData<F>,
+}
+
+impl<F: LazyAdd> FieldAccumulator<F> {
+    /// Constructs an accumulator for vectors of length `len`. The initial sum is zero.
+    pub fn new(len: usize) -> Self {
+        Self {
+            sums: vec![0; len],
+            count: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds `other_vector` to the sum.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `other_vector` does not have the same length as the accumulator.
+    pub fn add(&mut self, other_vector: &[F]) -> Result<(), FieldError> {
+        if self.sums.len() != other_vector.len() {
+            return Err(FieldError::InputSizeMismatch);
+        }
+
+        if F::LAZY_ADD_CAPACITY < 2 {
+            // The sum of two field elements may overflow, so reduce after each addition.
+            for_each_chunk(&mut self.sums, other_vector, |sums, xs| {
+                for (s, x) in sums.iter_mut().zip(xs.iter()) {
+                    *s = (F::from_wide(*s) + *x).to_wide();
+                }
+            });
+            return Ok(());
+        }
+
+        if self.count == F::LAZY_ADD_CAPACITY {
+            self.reduce();
+        }
+        for_each_chunk(&mut self.sums, other_vector, |sums, xs| {
+            for (s, x) in sums.iter_mut().zip(xs.iter()) {
+                *s += x.to_wide();
+            }
+        });
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// Returns the sum of the vectors added so far.
+    pub fn finish(self) -> Vec<F> {
+        self.sums.into_iter().map(F::from_wide).collect()
+    }
+
+    fn reduce(&mut self) {
+        for s in self.sums.iter_mut() {
+            *s = F::from_wide(*s).to_wide();
+        }
+        self.count = 1;
+    }
+}
+
 /// Merge two vectors of fields by summing other_vector into accumulator.
 ///
 /// # Errors
@@ -378,53 +2000,316 @@ make_field!(
 pub fn merge_vector<F: FieldElement>(
     accumulator: &mut [F],
     other_vector: &[F],
+) -> Result<(), FieldError> {
+    merge_vector_with(&CpuBulkFieldOps, accumulator, other_vector)
+}
+
+/// Like [`merge_vector`], but the additions are performed by `backend`.
+///
+/// # Errors
+///
+/// Fails if the two vectors do not have the same length.
+pub fn merge_vector_with<F: FieldElement, B: BulkFieldOps<F> + ?Sized>(
+    backend: &B,
+    accumulator: &mut [F],
+    other_vector: &[F],
 ) -> Result<(), FieldError> {
     if accumulator.len() != other_vector.len() {
         return Err(FieldError::InputSizeMismatch);
     }
-    for (a, o) in accumulator.iter_mut().zip(other_vector.iter()) {
-        *a += *o;
+    backend.add_assign(accumulator, other_vector);
+    Ok(())
+}
+
+/// Vectors of at least this length are summed on the rayon thread pool if the `multithreaded`
+/// feature is enabled. Below this, the overhead of distributing the work outweighs the speedup.
+#[cfg(feature = "multithreaded")]
+const PARALLEL_THRESHOLD: usize = 1 << 17;
+
+/// The number of elements summed by each task of the rayon thread pool.
+#[cfg(feature = "multithreaded")]
+const PARALLEL_CHUNK_LEN: usize = 1 << 14;
+
+// Calls `f` on corresponding chunks of `out` and `inp`, which must have the same length. If the
+// `multithreaded` feature is enabled and the input is long enough, the chunks are processed in
+// parallel; otherwise `f` is called once on the whole input.
+fn for_each_chunk<T: Send, U: Sync>(out: &mut [T], inp: &[U], f: impl Fn(&mut [T], &[U]) + Sync) {
+    #[cfg(feature = "multithreaded")]
+    {
+        use rayon::prelude::*;
+
+        if out.len() >= PARALLEL_THRESHOLD {
+            out.par_chunks_mut(PARALLEL_CHUNK_LEN)
+                .zip(inp.par_chunks(PARALLEL_CHUNK_LEN))
+                .for_each(|(out, inp)| f(out, inp));
+            return;
+        }
+    }
+
+    f(out, inp)
+}
+
+/// Returns the inner product of two vectors of field elements, i.e., the sum of `a[i] * b[i]` over
+/// each `i`.
+///
+/// # Errors
+///
+/// Fails if the two vectors do not have the same length.
+pub fn inner_product<F: FieldElement>(a: &[F], b: &[F]) -> Result<F, FieldError> {
+    if a.len() != b.len() {
+        return Err(FieldError::InputSizeMismatch);
+    }
+    Ok(bulk::inner_product(a, b))
+}
+
+/// A backend for the arithmetic on long slices of field elements that dominates aggregation, e.g.,
+/// one that offloads the work to a GPU or FPGA. Each method has a default implementation that runs
+/// on the CPU, so a backend only needs to override the operations it accelerates.
+///
+/// The caller ensures that the slices passed to `add_assign()`, `mul_assign()` and
+/// `inner_product()` have the same length.
+pub trait BulkFieldOps<F: FieldElement>: Debug + Send + Sync {
+    /// Sets `out[i] += rhs[i]` for each `i`.
+    fn add_assign(&self, out: &mut [F], rhs: &[F]) {
+        for_each_chunk(out, rhs, bulk::add_assign)
+    }
+
+    /// Sets `out[i] *= rhs[i]` for each `i`.
+    fn mul_assign(&self, out: &mut [F], rhs: &[F]) {
+        bulk::mul_assign(out, rhs)
+    }
+
+    /// Returns the sum of `lhs[i] * rhs[i]` over each `i`.
+    fn inner_product(&self, lhs: &[F], rhs: &[F]) -> F {
+        bulk::inner_product(lhs, rhs)
+    }
+
+    /// Sets `outp` to the number-theoretic transform (i.e., the DFT) of `inp`. See
+    /// [`discrete_fourier_transform`] for the meaning of the parameters and the errors returned.
+    fn ntt(&self, outp: &mut [F], inp: &[F], size: usize) -> Result<(), FftError> {
+        discrete_fourier_transform(outp, inp, size)
     }
+}
+
+/// The default [`BulkFieldOps`] backend, which runs on the CPU.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuBulkFieldOps;
+
+impl<F: FieldElement> BulkFieldOps<F> for CpuBulkFieldOps {}
+
+/// Swaps the contents of `a` and `b` if `choice` is set and leaves them unchanged otherwise. The
+/// runtime does not depend on `choice`.
+///
+/// # Errors
+///
+/// Fails if the two slices do not have the same length.
+pub fn conditional_swap<F: FieldElement>(
+    a: &mut [F],
+    b: &mut [F],
+    choice: Choice,
+) -> Result<(), FieldError> {
+    if a.len() != b.len() {
+        return Err(FieldError::InputSizeMismatch);
+    }
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        F::conditional_swap(x, y, choice);
+    }
+
+    Ok(())
+}
+
+/// Replaces each element of `xs` with its multiplicative inverse using Montgomery's trick, which
+/// costs a single inversion and `3(n - 1)` multiplications for `n` elements. As with
+/// `FieldElement::inv()`, the inverse of 0 is undefined; zeros are left as is and do not affect
+/// the other outputs.
+pub fn batch_inv<F: FieldElement>(xs: &mut [F]) {
+    // prefix[i] is the product of the non-zero elements of xs[..i].
+    let mut prefix = Vec::with_capacity(xs.len());
+    let mut acc = F::one();
+    for x in xs.iter() {
+        prefix.push(acc);
+        if *x != F::zero() {
+            acc *= *x;
+        }
+    }
+
+    // acc_inv is the inverse of the product of the non-zero elements of xs[..=i].
+    let mut acc_inv = acc.inv();
+    for (x, p) in xs.iter_mut().zip(prefix).rev() {
+        if *x != F::zero() {
+            let x_inv = acc_inv * p;
+            acc_inv *= *x;
+            *x = x_inv;
+        }
+    }
+}
+
+/// Generate a vector of uniform random field elements.
+pub fn rand<F: FieldElement>(len: usize) -> Result<Vec<F>, getrandom::Error> {
+    Ok(Prng::<F>::new_with_length(len)?.collect())
+}
+
+/// Like [`rand`], except that the randomness is drawn from `rng` rather than from the operating
+/// system.
+pub fn rand_with_rng<F: FieldElement, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    len: usize,
+) -> Result<Vec<F>, rand_core::Error> {
+    Ok(Prng::<F>::from_rng(rng, Some(len))?.collect())
+}
 
-    Ok(())
+/// Deterministically expands `seed` into a vector of `len` field elements using the same
+/// generator as [`rand`]. The output only depends on the seed, which makes this function suitable
+/// for generating reproducible test inputs. The output is secret only if the seed is.
+pub fn random_vector_seeded<F: FieldElement>(len: usize, seed: &[u8; 32]) -> Vec<F> {
+    Prng::<F>::from_seed(&Seed::from(*seed)).take(len).collect()
 }
 
-/// Generate a vector of uniform random field elements.
-pub fn rand<F: FieldElement>(len: usize) -> Result<Vec<F>, getrandom::Error> {
-    Ok(Prng::new_with_length(len)?.collect())
+/// Deterministically maps an arbitrary byte string to a vector of `len` field elements.
+///
+/// The input is hashed with SHAKE128 together with the domain-separation tag `dst`, and the first
+/// `SEED_LENGTH` bytes of the output seed a [`Prng`] over [`SeedStreamShake128`], which outputs
+/// uniformly distributed field elements by rejection sampling. Distinct applications should use
+/// distinct tags so that their outputs are independent.
+pub fn hash_to_field<F: FieldElement>(dst: &[u8], input: &[u8], len: usize) -> Vec<F> {
+    // Prefix the tag with its length so that (dst, input) pairs are encoded unambiguously.
+    let mut msg = Vec::with_capacity(8 + dst.len() + input.len());
+    msg.extend_from_slice(&(dst.len() as u64).to_be_bytes());
+    msg.extend_from_slice(dst);
+    msg.extend_from_slice(input);
+
+    let mut seed = [0; SEED_LENGTH];
+    SeedStreamShake128::from_input(&msg).fill(&mut seed);
+    Prng::<F, SeedStreamShake128>::from_seed(&Seed::from(seed))
+        .take(len)
+        .collect()
 }
 
 /// Outputs an additive secret sharing of the input.
+///
+/// The shares are returned as [`SecretVec`]s, so they are erased from memory when they are dropped
+/// if the `zeroize` feature is enabled.
 pub fn split<F: FieldElement>(
     inp: &[F],
     num_shares: usize,
-) -> Result<Vec<Vec<F>>, getrandom::Error> {
+) -> Result<Vec<SecretVec<F>>, getrandom::Error> {
     if num_shares == 0 {
         return Ok(vec![]);
     }
 
-    let mut outp = vec![vec![F::zero(); inp.len()]; num_shares];
-    for j in 0..inp.len() {
-        outp[0][j] = inp[j];
+    Ok(split_with_prng(inp, num_shares, Prng::new()?)
+        .into_iter()
+        .map(SecretVec::from)
+        .collect())
+}
+
+/// Like [`split`], except that the randomness is drawn from `rng` rather than from the operating
+/// system.
+pub fn split_with_rng<F: FieldElement, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    inp: &[F],
+    num_shares: usize,
+) -> Result<Vec<SecretVec<F>>, rand_core::Error> {
+    if num_shares == 0 {
+        return Ok(vec![]);
     }
 
-    let mut prng = Prng::new()?;
-    for i in 1..num_shares {
-        for j in 0..inp.len() {
-            let r = prng.next().unwrap();
-            outp[i][j] = r;
-            outp[0][j] -= r;
-        }
+    Ok(split_with_prng(inp, num_shares, Prng::from_rng(rng, None)?)
+        .into_iter()
+        .map(SecretVec::from)
+        .collect())
+}
+
+/// Like [`split`], except that the randomness is expanded from `seed`. The shares are determined
+/// by the input and the seed, which makes this function suitable for generating known-answer
+/// tests. The shares are secret only if the seed is, so unlike those of [`split`], they are
+/// returned as plain vectors.
+pub fn split_with_seed<F: FieldElement>(
+    seed: &Seed<SEED_LENGTH>,
+    inp: &[F],
+    num_shares: usize,
+) -> Vec<Vec<F>> {
+    if num_shares == 0 {
+        return vec![];
+    }
+
+    split_with_prng(inp, num_shares, Prng::from_seed(seed))
+}
+
+/// Like [`split`], except that every share but the first is compressed to a PRNG seed, from which
+/// [`expand_share`] recovers it. Only the first share grows with the length of the input, which
+/// makes the shares sent to all but one of the servers much smaller for high-dimension inputs.
+///
+/// Returns the first share and the seeds of the remaining `num_shares - 1` shares. If
+/// `num_shares` is 0, both are empty.
+pub fn split_compressed<F: FieldElement>(
+    inp: &[F],
+    num_shares: usize,
+) -> Result<(SecretVec<F>, Vec<Seed<SEED_LENGTH>>), getrandom::Error> {
+    if num_shares == 0 {
+        return Ok((SecretVec::default(), vec![]));
+    }
+
+    let mut first = SecretVec::new(inp.to_vec());
+    let seeds = split_compressed_in_place(first.as_mut_vec(), num_shares - 1)?;
+    Ok((first, seeds))
+}
+
+// Subtracts `num_seeds` shares expanded from fresh seeds from `share`, turning it into the first
+// share of a compressed sharing of its original value, and returns the seeds.
+pub(crate) fn split_compressed_in_place<F: FieldElement>(
+    share: &mut [F],
+    num_seeds: usize,
+) -> Result<Vec<Seed<SEED_LENGTH>>, getrandom::Error> {
+    (0..num_seeds)
+        .map(|_| {
+            let seed = Seed::generate()?;
+            for (s, r) in share.iter_mut().zip(Prng::<F>::from_seed(&seed)) {
+                *s -= r;
+            }
+            Ok(seed)
+        })
+        .collect()
+}
+
+/// Expands a share compressed by [`split_compressed`] to `len` field elements.
+pub fn expand_share<F: FieldElement>(seed: &Seed<SEED_LENGTH>, len: usize) -> Vec<F> {
+    let mut out = Vec::new();
+    expand_share_into(seed, len, &mut out);
+    out
+}
+
+/// Like [`expand_share`], but writes the share to `out`, replacing its contents and reusing its
+/// allocation.
+pub fn expand_share_into<F: FieldElement>(seed: &Seed<SEED_LENGTH>, len: usize, out: &mut Vec<F>) {
+    out.clear();
+    out.extend(Prng::<F>::from_seed(seed).take(len));
+}
+
+fn split_with_prng<F: FieldElement>(
+    inp: &[F],
+    num_shares: usize,
+    mut prng: Prng<F>,
+) -> Vec<Vec<F>> {
+    let mut outp = vec![vec![F::zero(); inp.len()]; num_shares];
+    outp[0].copy_from_slice(inp);
+
+    let (first, rest) = outp.split_at_mut(1);
+    for share in rest.iter_mut() {
+        prng.fill(share);
+        bulk::sub_assign(&mut first[0], share);
     }
 
-    Ok(outp)
+    outp
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codec::{decode_u16_items, encode_u16_items};
     use crate::fp::MAX_ROOTS;
     use assert_matches::assert_matches;
+    use serde::de::value::BytesDeserializer;
 
     #[test]
     fn test_accumulate() {
@@ -443,25 +2328,39 @@ mod tests {
         assert_matches!(result, Err(FieldError::InputSizeMismatch));
     }
 
-    fn field_element_test<F: FieldElement>() {
+    fn field_element_test<F: FieldElementWithInteger>() {
         let mut prng: Prng<F> = Prng::new().unwrap();
         let int_modulus = F::modulus();
         let int_one = F::Integer::try_from(1).unwrap();
         let zero = F::zero();
         let one = F::one();
-        let two = F::from(F::Integer::try_from(2).unwrap());
-        let four = F::from(F::Integer::try_from(4).unwrap());
+        let two = F::from_reduced(F::Integer::try_from(2).unwrap());
+        let four = F::from_reduced(F::Integer::try_from(4).unwrap());
+
+        // ct_eq
+        assert!(bool::from(one.ct_eq(&one)));
+        assert!(!bool::from(one.ct_eq(&zero)));
+        assert!(bool::from(F::from_reduced(int_modulus).ct_eq(&zero)));
+
+        // conditional_select
+        assert_eq!(F::conditional_select(&one, &two, Choice::from(0)), one);
+        assert_eq!(F::conditional_select(&one, &two, Choice::from(1)), two);
+
+        // try_from
+        assert_eq!(F::try_from(int_one), Ok(one));
+        assert_eq!(F::try_from(int_modulus - int_one), Ok(-one));
+        assert_eq!(F::try_from(int_modulus), Err(FieldError::ModulusOverflow));
 
         // add
-        assert_eq!(F::from(int_modulus - int_one) + one, zero);
+        assert_eq!(F::from_reduced(int_modulus - int_one) + one, zero);
         assert_eq!(one + one, two);
-        assert_eq!(two + F::from(int_modulus), two);
+        assert_eq!(two + F::from_reduced(int_modulus), two);
 
         // sub
-        assert_eq!(zero - one, F::from(int_modulus - int_one));
+        assert_eq!(zero - one, F::from_reduced(int_modulus - int_one));
         assert_eq!(one - one, zero);
-        assert_eq!(two - F::from(int_modulus), two);
-        assert_eq!(one - F::from(int_modulus - int_one), two);
+        assert_eq!(two - F::from_reduced(int_modulus), two);
+        assert_eq!(one - F::from_reduced(int_modulus - int_one), two);
 
         // add + sub
         for _ in 0..100 {
@@ -476,7 +2375,7 @@ mod tests {
         assert_eq!(two * two, four);
         assert_eq!(two * one, two);
         assert_eq!(two * zero, zero);
-        assert_eq!(one * F::from(int_modulus), zero);
+        assert_eq!(one * F::from_reduced(int_modulus), zero);
 
         // div
         assert_eq!(four / two, two);
@@ -496,6 +2395,15 @@ mod tests {
             assert_eq!(f.inv() * f, one);
         }
 
+        // sum + product
+        let v = vec![one, two, four];
+        assert_eq!(v.iter().sum::<F>(), two + two + two + one);
+        assert_eq!(v.iter().copied().sum::<F>(), two + two + two + one);
+        assert_eq!(v.iter().product::<F>(), four * two);
+        assert_eq!(v.iter().copied().product::<F>(), four * two);
+        assert_eq!(Vec::<F>::new().iter().sum::<F>(), zero);
+        assert_eq!(Vec::<F>::new().iter().product::<F>(), one);
+
         // pow
         assert_eq!(two.pow(F::Integer::try_from(0).unwrap()), one);
         assert_eq!(two.pow(int_one), two);
@@ -505,7 +2413,12 @@ mod tests {
 
         // roots
         let mut int_order = F::generator_order();
-        for l in 0..MAX_ROOTS + 1 {
+        let int_zero = F::Integer::try_from(0).unwrap();
+        for l in 0.. {
+            if int_order == int_zero {
+                assert_eq!(F::root(l), None, "unexpected F::root({})", l);
+                break;
+            }
             assert_eq!(
                 F::generator().pow(int_order),
                 F::root(l).unwrap(),
@@ -520,7 +2433,7 @@ mod tests {
             zero,
             one,
             prng.next().unwrap(),
-            F::from(int_modulus - int_one),
+            F::from_reduced(int_modulus - int_one),
         ];
         for want in test_inputs.iter() {
             let mut bytes = vec![];
@@ -550,4 +2463,1140 @@ mod tests {
     fn test_field126() {
         field_element_test::<Field126>();
     }
+
+    #[test]
+    fn test_conditional_swap() {
+        let a: Vec<Field32> = rand(10).unwrap();
+        let b: Vec<Field32> = rand(10).unwrap();
+
+        let (mut x, mut y) = (a.clone(), b.clone());
+        conditional_swap(&mut x, &mut y, Choice::from(0)).unwrap();
+        assert_eq!((&x, &y), (&a, &b));
+
+        conditional_swap(&mut x, &mut y, Choice::from(1)).unwrap();
+        assert_eq!((&x, &y), (&b, &a));
+
+        assert_eq!(
+            conditional_swap(&mut x, &mut y[..9], Choice::from(1)),
+            Err(FieldError::InputSizeMismatch)
+        );
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use zeroize::Zeroize;
+
+        let mut x = Field126::from_reduced(1337);
+        x.zeroize();
+        assert_eq!(x, Field126::zero());
+
+        let mut x = -Field255::one();
+        x.zeroize();
+        assert_eq!(x, Field255::zero());
+
+        let mut shares = split(&[Field64::from_reduced(1), Field64::from_reduced(2)], 3).unwrap();
+        shares.zeroize();
+        assert!(shares.is_empty());
+    }
+
+    fn serde_test<F: FieldElement + Serialize + for<'de> Deserialize<'de>>() {
+        let x: F = rand(1).unwrap()[0];
+        let mut want = vec![];
+        x.append_to(&mut want);
+
+        // Byte strings are deserialized directly.
+        let de = BytesDeserializer::<serde::de::value::Error>::new(&want);
+        assert_eq!(F::deserialize(de).unwrap(), x);
+        let de = BytesDeserializer::<serde::de::value::Error>::new(&want[1..]);
+        assert!(F::deserialize(de).is_err());
+
+        // Formats without a byte string type use a sequence of bytes.
+        let encoded = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<u8>>(&encoded).unwrap(), want);
+        assert_eq!(serde_json::from_str::<F>(&encoded).unwrap(), x);
+
+        // Encodings of integers larger than or equal to the modulus are rejected.
+        let encoded = serde_json::to_string(&vec![0xffu8; F::BYTES]).unwrap();
+        assert!(serde_json::from_str::<F>(&encoded).is_err());
+
+        // Encodings of the wrong length are rejected.
+        let encoded = serde_json::to_string(&want[..F::BYTES - 1]).unwrap();
+        assert!(serde_json::from_str::<F>(&encoded).is_err());
+        let mut long = want.clone();
+        long.push(0);
+        let encoded = serde_json::to_string(&long).unwrap();
+        assert!(serde_json::from_str::<F>(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        // The encoding does not depend on the internal representation.
+        let encoded = serde_json::to_string(&Field32::from_reduced(1337)).unwrap();
+        assert_eq!(encoded, "[57,5,0,0]");
+
+        serde_test::<Field32>();
+        serde_test::<Field61>();
+        serde_test::<Field64>();
+        serde_test::<Field80>();
+        serde_test::<Field126>();
+        serde_test::<Field128>();
+        serde_test::<Field255>();
+    }
+
+    fn field_accumulator_test<F: LazyAdd>(num_vectors: usize) {
+        let vectors: Vec<Vec<F>> = (0..num_vectors).map(|_| rand(10).unwrap()).collect();
+        let mut acc = FieldAccumulator::new(10);
+        let mut want = vec![F::zero(); 10];
+        for v in vectors.iter() {
+            acc.add(v).unwrap();
+            merge_vector(&mut want, v).unwrap();
+        }
+        assert_eq!(acc.add(&want[..9]), Err(FieldError::InputSizeMismatch));
+        assert_eq!(acc.finish(), want);
+
+        // The largest element sums to -num_vectors (mod p).
+        let mut acc = FieldAccumulator::new(1);
+        for _ in 0..num_vectors {
+            acc.add(&[-F::one()]).unwrap();
+        }
+        let n = from_usize::<F>(num_vectors);
+        assert_eq!(acc.finish(), vec![-n]);
+    }
+
+    #[test]
+    fn test_field_accumulator() {
+        field_accumulator_test::<Field32>(100);
+        field_accumulator_test::<Field61>(100);
+        field_accumulator_test::<Field64>(100);
+        // These fields can only sum a few elements before the sums must be reduced.
+        assert_eq!(Field126::LAZY_ADD_CAPACITY, 4);
+        assert_eq!(Field128::LAZY_ADD_CAPACITY, 1);
+        field_accumulator_test::<Field80>(100);
+        field_accumulator_test::<Field126>(100);
+        field_accumulator_test::<Field128>(100);
+    }
+
+    #[test]
+    fn test_slice_encoding() {
+        let values: Vec<Field80> = rand(10).unwrap();
+        let bytes = Field80::slice_into_byte_vec(&values);
+        assert_eq!(bytes.len(), 10 * Field80::BYTES);
+        assert_eq!(Field80::byte_slice_into_vec(&bytes).unwrap(), values);
+
+        assert_eq!(
+            Field80::byte_slice_into_vec(&bytes[..bytes.len() - 1]),
+            Err(FieldError::FromBytesShortRead)
+        );
+        assert_eq!(
+            Field80::byte_slice_into_vec(&[0xff; 10]),
+            Err(FieldError::FromBytesModulusOverflow)
+        );
+        assert!(Field80::slice_into_byte_vec(&[]).is_empty());
+        assert!(Field80::byte_slice_into_vec(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_streaming_encoding() {
+        let values: Vec<Field64> = rand(10).unwrap();
+        let mut writer = Vec::new();
+        for x in values.iter() {
+            x.encode_into(&mut writer).unwrap();
+        }
+        assert_eq!(writer, Field64::slice_into_byte_vec(&values));
+
+        let mut reader = std::io::Cursor::new(writer);
+        for want in values.iter() {
+            assert_eq!(Field64::decode_from(&mut reader).unwrap(), *want);
+        }
+        assert_eq!(
+            Field64::decode_from(&mut reader).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        let err = Field64::decode_from(&mut &[0xff; 8][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.into_inner().unwrap().downcast::<FieldError>().unwrap(),
+            Box::new(FieldError::FromBytesModulusOverflow)
+        );
+    }
+
+    fn codec_test<F: FieldElement>() {
+        let values: Vec<F> = rand(10).unwrap();
+        let mut bytes = Vec::new();
+        encode_u16_items(&mut bytes, &(), &values).unwrap();
+        assert_eq!(bytes.len(), 2 + 10 * F::ENCODED_LEN);
+        assert_eq!(bytes[2..], F::slice_into_byte_vec(&values)[..]);
+
+        let decoded: Vec<F> = decode_u16_items(&(), &mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(decoded, values);
+        assert_eq!(values[0].encoded_len(), Some(F::ENCODED_LEN));
+
+        let err = F::get_decoded(&vec![0xff; F::ENCODED_LEN]).unwrap_err();
+        match err {
+            CodecError::Other(e) => assert_eq!(
+                e.downcast::<FieldError>().unwrap(),
+                Box::new(FieldError::FromBytesModulusOverflow)
+            ),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_codec() {
+        codec_test::<Field32>();
+        codec_test::<Field64>();
+        codec_test::<Field80>();
+        codec_test::<Field126>();
+        codec_test::<Field128>();
+        codec_test::<Field61>();
+        codec_test::<Field255>();
+        codec_test::<Field64x2>();
+    }
+
+    #[test]
+    fn test_hash_to_field() {
+        let got: Vec<Field32> = hash_to_field(b"test", b"hello", 10);
+        assert_eq!(got.len(), 10);
+        assert_eq!(hash_to_field::<Field32>(b"test", b"hello", 10), got);
+
+        // The output depends on the tag and on the input.
+        assert_ne!(hash_to_field::<Field32>(b"other", b"hello", 10), got);
+        assert_ne!(hash_to_field::<Field32>(b"test", b"world", 10), got);
+        assert_ne!(hash_to_field::<Field32>(b"testh", b"ello", 10), got);
+
+        // Shorter outputs are prefixes of longer ones.
+        assert_eq!(hash_to_field::<Field32>(b"test", b"hello", 3), got[..3]);
+        assert!(hash_to_field::<Field126>(b"test", b"", 0).is_empty());
+    }
+
+    fn sqrt_test<F: FieldElementWithInteger>() {
+        let mut prng: Prng<F> = Prng::new().unwrap();
+        assert_eq!(F::zero().sqrt(), Some(F::zero()));
+        assert!(F::zero().is_square());
+        assert_eq!(F::one().sqrt().map(|x| x * x), Some(F::one()));
+
+        for _ in 0..100 {
+            let x = prng.next().unwrap();
+            let square = x * x;
+            assert!(square.is_square());
+            let root = square.sqrt().unwrap();
+            assert!(root == x || root == -x);
+
+            assert_eq!(x.is_square(), x.sqrt().is_some());
+        }
+
+        // The generator is not a square.
+        assert!(!F::generator().is_square());
+        assert_eq!(F::generator().sqrt(), None);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        sqrt_test::<Field32>();
+        sqrt_test::<Field61>();
+        sqrt_test::<Field64>();
+        sqrt_test::<Field80>();
+        sqrt_test::<Field126>();
+        sqrt_test::<Field128>();
+    }
+
+    #[test]
+    fn test_batch_inv() {
+        let mut xs: Vec<Field64> = rand(10).unwrap();
+        xs[0] = Field64::zero();
+        xs[5] = Field64::zero();
+        let want: Vec<Field64> = xs
+            .iter()
+            .map(|x| if *x == Field64::zero() { *x } else { x.inv() })
+            .collect();
+        batch_inv(&mut xs);
+        assert_eq!(xs, want);
+
+        let mut xs: Vec<Field64> = vec![];
+        batch_inv(&mut xs);
+        assert!(xs.is_empty());
+    }
+
+    #[test]
+    fn test_field128() {
+        field_element_test::<Field128>();
+    }
+
+    #[test]
+    fn test_field61() {
+        field_element_test::<Field61>();
+    }
+
+    #[test]
+    fn test_field255() {
+        let one = Field255::one();
+        let two = one + one;
+        let four = two * two;
+        assert_eq!(four, Field255::from(4u64));
+        assert_eq!(four - two - two, Field255::zero());
+        assert_eq!(four / two, two);
+        assert_eq!([one, two, four].iter().sum::<Field255>(), four + two + one);
+        assert_eq!(
+            [two, four].iter().copied().product::<Field255>(),
+            four * two
+        );
+        assert_eq!(-one + one, Field255::zero());
+        assert_eq!(two * two.inv(), one);
+        assert_eq!(
+            two.pow([255, 0, 0, 0]),
+            Field255::from(1u128 << 127) * Field255::from(1u128 << 127) * two
+        );
+
+        // The modulus minus one.
+        let neg_one = -one;
+        assert_eq!(
+            neg_one.to_string(),
+            "57896044618658097711785492504343953926634992332820282019728792003956564819948"
+        );
+        assert_eq!(format!("{:?}", Field255::from(1234u64)), "1234");
+
+        // Roots of unity.
+        let mut l = 0;
+        while let Some(root) = Field255::root(l) {
+            let mut x = root;
+            for _ in 0..l {
+                x *= x;
+            }
+            assert_eq!(x, one);
+            l += 1;
+        }
+        assert_eq!(l, 3);
+        assert_eq!(Field255::generator(), Field255::root(2).unwrap());
+        assert_eq!(Field255::generator() * Field255::generator(), neg_one);
+
+        // Encoding.
+        let x = neg_one * Field255::from(u128::MAX);
+        let mut bytes = vec![];
+        x.append_to(&mut bytes);
+        assert_eq!(bytes.len(), Field255::BYTES);
+        assert_eq!(Field255::read_from(&bytes).unwrap(), x);
+        assert_eq!(Field255::try_from_random(&bytes).unwrap(), x);
+
+        let mut bytes = vec![];
+        neg_one.append_to(&mut bytes);
+        bytes[0] += 1;
+        assert_matches!(
+            Field255::read_from(&bytes),
+            Err(FieldError::FromBytesModulusOverflow)
+        );
+        assert_matches!(
+            Field255::read_from(&bytes[..31]),
+            Err(FieldError::FromBytesShortRead)
+        );
+        bytes[31] |= 0x80;
+        assert_matches!(
+            Field255::try_from_random(&bytes),
+            Err(FieldError::FromBytesModulusOverflow)
+        );
+        bytes[0] -= 1;
+        assert_eq!(Field255::try_from_random(&bytes).unwrap(), neg_one);
+
+        // Generic code that only requires `FieldElement`.
+        let vec: Vec<Field255> = rand(10).unwrap();
+        let shares = split(&vec, 2).unwrap();
+        let mut sum = shares[0].to_vec();
+        merge_vector(&mut sum, &shares[1]).unwrap();
+        assert_eq!(sum, vec);
+
+        let bytes = Field255::slice_into_byte_vec(&vec);
+        assert_eq!(Field255::byte_slice_into_vec(&bytes).unwrap(), vec);
+        assert_eq!(from_usize::<Field255>(1 << 20), Field255::from(1u64 << 20));
+    }
+
+    fn bitvector_test<F: FieldElementWithInteger>(modulus_bits: usize) {
+        let int = |x: usize| F::Integer::try_from(x).unwrap();
+
+        for value in [0, 1, 2, 0xff, 1337] {
+            let encoded = encode_into_bitvector::<F>(int(value), 11).unwrap();
+            assert_eq!(encoded.len(), 11);
+            for (l, bit) in encoded.iter().enumerate() {
+                assert_eq!(F::Integer::from(*bit), int((value >> l) & 1));
+            }
+            assert_eq!(decode_bitvector(&encoded).unwrap(), int(value));
+        }
+
+        assert_eq!(
+            encode_into_bitvector::<F>(int(1 << 11), 11),
+            Err(FieldError::IntegerTooLarge)
+        );
+        assert_eq!(
+            encode_into_bitvector::<F>(int(1), 0),
+            Err(FieldError::IntegerTooLarge)
+        );
+        assert_eq!(encode_into_bitvector::<F>(int(0), 0), Ok(vec![]));
+
+        // The largest supported length.
+        let max = F::modulus() >> int(1);
+        let encoded = encode_into_bitvector::<F>(max, modulus_bits - 1).unwrap();
+        assert_eq!(decode_bitvector(&encoded).unwrap(), max);
+        assert_eq!(
+            encode_into_bitvector::<F>(int(0), modulus_bits),
+            Err(FieldError::BitVectorTooLong)
+        );
+        assert_eq!(
+            decode_bitvector(&vec![F::zero(); modulus_bits]),
+            Err(FieldError::BitVectorTooLong)
+        );
+
+        let mut encoded = encode_into_bitvector::<F>(int(5), 8).unwrap();
+        encoded[3] = F::from_reduced(int(2));
+        assert_eq!(decode_bitvector(&encoded), Err(FieldError::InvalidBit));
+    }
+
+    #[test]
+    fn test_bitvector() {
+        bitvector_test::<Field32>(32);
+        bitvector_test::<Field61>(61);
+        bitvector_test::<Field64>(64);
+        bitvector_test::<Field80>(80);
+        bitvector_test::<Field126>(126);
+        bitvector_test::<Field128>(128);
+    }
+
+    #[test]
+    fn test_rand_and_split_with_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let x: Vec<Field64> = rand_with_rng(&mut rng, 10).unwrap();
+        assert_eq!(x.len(), 10);
+
+        // The output is determined by the RNG.
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(rand_with_rng::<Field64, _>(&mut rng, 10).unwrap(), x);
+        let y: Vec<Field64> = rand_with_rng(&mut rng, 10).unwrap();
+        assert_ne!(x, y);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let shares = split_with_rng(&mut rng, &x, 3).unwrap();
+        assert_eq!(shares.len(), 3);
+        let mut sum = vec![Field64::zero(); x.len()];
+        for share in shares.iter() {
+            merge_vector(&mut sum, share).unwrap();
+        }
+        assert_eq!(sum, x);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(split_with_rng(&mut rng, &x, 3).unwrap(), shares);
+        assert!(split_with_rng(&mut rng, &x, 0).unwrap().is_empty());
+
+        let seed = Seed::from([7; SEED_LENGTH]);
+        let shares = split_with_seed(&seed, &x, 3);
+        assert_eq!(shares, split_with_seed(&seed, &x, 3));
+        let mut sum = vec![Field64::zero(); x.len()];
+        for share in shares.iter() {
+            merge_vector(&mut sum, share).unwrap();
+        }
+        assert_eq!(sum, x);
+        assert_ne!(
+            split_with_seed(&Seed::from([8; SEED_LENGTH]), &x, 3),
+            shares
+        );
+        assert!(split_with_seed(&seed, &x, 0).is_empty());
+    }
+
+    fn endianness_test<F: FieldElement>() {
+        let values: Vec<F> = rand(10).unwrap();
+        for value in values.iter() {
+            let mut le = Vec::new();
+            value.append_to(&mut le);
+            let mut be = vec![0xaa];
+            value.append_to_be(&mut be);
+            assert_eq!(be[0], 0xaa);
+            if F::ID != FieldId::Field64x2 {
+                le.reverse();
+                assert_eq!(be[1..], le[..]);
+            }
+            assert_eq!(F::read_from_be(&be[1..]).unwrap(), *value);
+        }
+        assert_matches!(
+            F::read_from_be(&vec![0; F::BYTES - 1]),
+            Err(FieldError::FromBytesShortRead)
+        );
+
+        assert_eq!(
+            Endianness::Little.slice_into_byte_vec(&values),
+            F::slice_into_byte_vec(&values)
+        );
+        for endianness in [Endianness::Little, Endianness::Big].iter() {
+            let bytes = endianness.slice_into_byte_vec(&values);
+            assert_eq!(endianness.byte_slice_into_vec::<F>(&bytes).unwrap(), values);
+            assert_matches!(
+                endianness.byte_slice_into_vec::<F>(&bytes[1..]),
+                Err(FieldError::FromBytesShortRead)
+            );
+        }
+    }
+
+    #[test]
+    fn test_endianness() {
+        endianness_test::<Field32>();
+        endianness_test::<Field64>();
+        endianness_test::<Field126>();
+        endianness_test::<Field255>();
+        endianness_test::<Field64x2>();
+
+        // The big-endian encoding of a prime field element is that of its integer representative.
+        let mut bytes = Vec::new();
+        Field64::from_reduced(0x0102).append_to_be(&mut bytes);
+        assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(
+            Endianness::Big.read_from::<Field64>(&bytes).unwrap(),
+            Field64::from_reduced(0x0102)
+        );
+
+        // The coefficients of an extension field element keep their order.
+        let x = Field64x2::new(Field64::from_reduced(0x0102), Field64::from_reduced(0x0304));
+        let mut bytes = Vec::new();
+        x.append_to_be(&mut bytes);
+        assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 0, 0, 3, 4]);
+        assert_eq!(Field64x2::read_from_be(&bytes).unwrap(), x);
+
+        // An encoding of the modulus is rejected in either byte order.
+        let mut p = FP64.p.to_be_bytes().to_vec();
+        assert!(Field64::read_from_be(&p).is_err());
+        p.reverse();
+        assert!(Field64::read_from(&p).is_err());
+    }
+
+    #[test]
+    fn test_split_compressed() {
+        let x: Vec<Field64> = rand(1000).unwrap();
+        let (first, seeds) = split_compressed(&x, 3).unwrap();
+        assert_eq!(seeds.len(), 2);
+
+        let mut sum = first.to_vec();
+        for seed in seeds.iter() {
+            merge_vector(&mut sum, &expand_share(seed, x.len())).unwrap();
+        }
+        assert_eq!(sum, x);
+
+        let (first, seeds) = split_compressed(&x, 1).unwrap();
+        assert_eq!(first[..], x[..]);
+        assert!(seeds.is_empty());
+        let (first, seeds) = split_compressed(&x, 0).unwrap();
+        assert!(first.is_empty() && seeds.is_empty());
+
+        let mut out = vec![Field64::zero(); 5];
+        expand_share_into(&Seed::from([1; SEED_LENGTH]), 3, &mut out);
+        assert_eq!(
+            out,
+            expand_share::<Field64>(&Seed::from([1; SEED_LENGTH]), 3)
+        );
+    }
+
+    #[cfg(feature = "fuzz")]
+    fn arbitrary_test<F: FieldElement + for<'a> arbitrary::Arbitrary<'a>>() {
+        use arbitrary::Arbitrary;
+
+        let bytes: Vec<u8> = (0..4096).map(|_| rand::random()).collect();
+        let mut u = arbitrary::Unstructured::new(&bytes);
+        for _ in 0..100 {
+            // Every generated element has a canonical encoding.
+            let x = F::arbitrary(&mut u).unwrap();
+            let mut encoded = Vec::new();
+            x.append_to(&mut encoded);
+            assert_eq!(F::read_from(&encoded).unwrap(), x);
+        }
+    }
+
+    #[cfg(feature = "fuzz")]
+    #[test]
+    fn test_arbitrary() {
+        arbitrary_test::<Field32>();
+        arbitrary_test::<Field64>();
+        arbitrary_test::<Field61>();
+        arbitrary_test::<Field80>();
+        arbitrary_test::<Field126>();
+        arbitrary_test::<Field128>();
+        arbitrary_test::<Field255>();
+        arbitrary_test::<Field64x2>();
+    }
+
+    #[test]
+    fn test_merge_vector_long() {
+        // Long enough to be summed in parallel if the `multithreaded` feature is enabled.
+        let len = (1 << 17) + 3;
+        let x: Vec<Field64> = rand(len).unwrap();
+        let y: Vec<Field64> = rand(len).unwrap();
+        let want: Vec<Field64> = x.iter().zip(y.iter()).map(|(a, b)| *a + *b).collect();
+
+        let mut got = x.clone();
+        merge_vector(&mut got, &y).unwrap();
+        assert_eq!(got, want);
+
+        let mut acc = FieldAccumulator::new(len);
+        acc.add(&x).unwrap();
+        acc.add(&y).unwrap();
+        assert_eq!(acc.finish(), want);
+    }
+
+    #[test]
+    fn test_decode_aggregate() {
+        let aggregate: Vec<Field64> = (0..10).map(Field64::from_reduced).collect();
+        assert_eq!(
+            decode_aggregate(&aggregate, 9).unwrap(),
+            (0..10).collect::<Vec<u64>>()
+        );
+        assert_eq!(
+            decode_aggregate(&aggregate, 8),
+            Err(FieldError::AggregateOutOfBounds)
+        );
+        assert!(decode_aggregate::<Field64>(&[], 0).unwrap().is_empty());
+
+        // A negative sum wraps around the modulus.
+        let aggregate = vec![Field32::one(), -Field32::one()];
+        assert_eq!(
+            decode_aggregate(&aggregate, 1 << 20),
+            Err(FieldError::AggregateOutOfBounds)
+        );
+    }
+
+    fn pow_bytes_test<F: FieldElementWithInteger>()
+    where
+        F::Integer: Into<u128>,
+    {
+        let x: F = rand(1).unwrap()[0];
+        for exp in [0u128, 1, 2, 3, 255, 256, 0xdead_beef].iter() {
+            let want = x.pow(F::Integer::try_from(*exp as usize).unwrap());
+            assert_eq!(x.pow_bytes(&exp.to_be_bytes()), want, "exp = {}", exp);
+            // Leading zeros do not change the result.
+            let mut exp_be = vec![0; 40];
+            exp_be.extend_from_slice(&exp.to_be_bytes());
+            assert_eq!(x.pow_bytes(&exp_be), want, "exp = {}", exp);
+        }
+        assert_eq!(x.pow_bytes(&[]), F::one());
+
+        // x^(k * (p - 1) + 1) = x for any k, in particular for exponents larger than the modulus.
+        let p_minus_one: u128 = (F::modulus() - F::one().into()).into();
+        let mut exp_be = p_minus_one.to_be_bytes().to_vec();
+        exp_be.extend_from_slice(&[0; 16]);
+        *exp_be.last_mut().unwrap() = 1;
+        // exp = (p - 1) * 2^128 + 1
+        assert_eq!(x.pow_bytes(&exp_be), x);
+    }
+
+    #[test]
+    fn test_pow_bytes() {
+        pow_bytes_test::<Field32>();
+        pow_bytes_test::<Field64>();
+        pow_bytes_test::<Field61>();
+        pow_bytes_test::<Field80>();
+        pow_bytes_test::<Field126>();
+        pow_bytes_test::<Field128>();
+
+        // Compare against the four-limb exponent of `Field255::pow()`.
+        let x: Field255 = rand(1).unwrap()[0];
+        let exp: [u64; LIMBS] = [
+            0x0123_4567_89ab_cdef,
+            0xfedc_ba98_7654_3210,
+            0xffff_ffff_ffff_ffff,
+            0x7fff_ffff_ffff_fff0,
+        ];
+        let exp_be: Vec<u8> = exp.iter().rev().flat_map(|l| l.to_be_bytes()).collect();
+        assert_eq!(x.pow_bytes(&exp_be), x.pow(exp));
+
+        // In GF(p^2), x^(p^2 - 1) = 1 for non-zero x.
+        let x: Field64x2 = rand(1).unwrap()[0];
+        let p = u128::from(Field64::modulus());
+        assert_eq!(x.pow_bytes(&(p * p - 1).to_be_bytes()), Field64x2::one());
+    }
+
+    fn string_test<F: FieldElement + FromStr<Err = FieldError>>() {
+        for x in rand::<F>(10).unwrap() {
+            assert_eq!(x.to_string().parse::<F>().unwrap(), x);
+            assert_eq!(F::from_hex(&x.to_hex()).unwrap(), x);
+            assert_eq!(F::from_hex(&x.to_hex().to_uppercase()).unwrap(), x);
+        }
+
+        let one = F::one();
+        assert_eq!("1".parse::<F>().unwrap(), one);
+        assert_eq!("0001".parse::<F>().unwrap(), one);
+        // The encoding is little-endian.
+        assert_eq!(&one.to_hex()[..2], "01");
+
+        for bad in ["", "-1", "+1", " 1", "1.0", "0x1", "one"].iter() {
+            assert_eq!(
+                bad.parse::<F>(),
+                Err(FieldError::InvalidString),
+                "{:?}",
+                bad
+            );
+        }
+        assert_eq!(
+            "1".repeat(100).parse::<F>(),
+            Err(FieldError::ModulusOverflow)
+        );
+
+        let hex = one.to_hex();
+        for bad in [
+            &hex[1..],
+            &hex[..hex.len() - 2],
+            &format!("{}00", hex),
+            &hex.replace('1', "g"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                F::from_hex(bad),
+                Err(FieldError::InvalidString),
+                "{:?}",
+                bad
+            );
+        }
+        assert_eq!(
+            F::from_hex(&"ff".repeat(F::BYTES)),
+            Err(FieldError::FromBytesModulusOverflow)
+        );
+    }
+
+    #[test]
+    fn test_string() {
+        string_test::<Field32>();
+        string_test::<Field64>();
+        string_test::<Field61>();
+        string_test::<Field80>();
+        string_test::<Field126>();
+        string_test::<Field128>();
+        string_test::<Field255>();
+        string_test::<Field64x2>();
+
+        // The modulus itself is rejected.
+        let p = Field64::modulus();
+        assert_eq!(
+            (p - 1).to_string().parse::<Field64>().unwrap(),
+            -Field64::one()
+        );
+        assert_eq!(
+            p.to_string().parse::<Field64>(),
+            Err(FieldError::ModulusOverflow)
+        );
+        let p = "57896044618658097711785492504343953926634992332820282019728792003956564819949";
+        assert_eq!(p.parse::<Field255>(), Err(FieldError::ModulusOverflow));
+        assert_eq!(
+            "57896044618658097711785492504343953926634992332820282019728792003956564819948"
+                .parse::<Field255>()
+                .unwrap(),
+            -Field255::one()
+        );
+
+        assert_eq!(
+            "2 + 3x".parse::<Field64x2>().unwrap(),
+            Field64x2::new(Field64::from_reduced(2), Field64::from_reduced(3))
+        );
+        assert_eq!("2 + x".parse::<Field64x2>(), Err(FieldError::InvalidString));
+        assert_eq!("2+3x".parse::<Field64x2>(), Err(FieldError::InvalidString));
+    }
+
+    #[test]
+    fn test_random_vector_seeded() {
+        let seed = [1; 32];
+        let x: Vec<Field64> = random_vector_seeded(100, &seed);
+        assert_eq!(x.len(), 100);
+        assert_eq!(random_vector_seeded::<Field64>(100, &seed), x);
+        // A shorter vector is a prefix of a longer one.
+        assert_eq!(random_vector_seeded::<Field64>(10, &seed), &x[..10]);
+        assert_ne!(random_vector_seeded::<Field64>(100, &[2; 32]), x);
+        assert!(random_vector_seeded::<Field64>(0, &seed).is_empty());
+
+        // The output matches the share that a server derives from the same seed.
+        let share: Vec<Field126> = expand_share(&Seed::from(seed), 50);
+        assert_eq!(random_vector_seeded::<Field126>(50, &seed), share);
+    }
+
+    fn mul_u64_test<F: FieldElement>() {
+        let x: F = rand(1).unwrap()[0];
+        for c in [0, 1, 2, 3, 7, 16, 17, 1 << 20, u64::MAX].iter() {
+            // Compute `c` as a field element by doubling.
+            let mut want = F::zero();
+            for i in (0..64).rev() {
+                want += want;
+                if (c >> i) & 1 == 1 {
+                    want += F::one();
+                }
+            }
+            want *= x;
+
+            assert_eq!(x * *c, want, "c = {}", c);
+            let mut got = x;
+            got *= *c;
+            assert_eq!(got, want, "c = {}", c);
+        }
+
+        // Generic code can multiply by integer literals.
+        assert_eq!(x * 3, x + x + x);
+    }
+
+    #[test]
+    fn test_mul_u64() {
+        mul_u64_test::<Field32>();
+        mul_u64_test::<Field64>();
+        mul_u64_test::<Field61>();
+        mul_u64_test::<Field80>();
+        mul_u64_test::<Field126>();
+        mul_u64_test::<Field128>();
+        mul_u64_test::<Field255>();
+        mul_u64_test::<Field64x2>();
+    }
+
+    fn from_random_rejection_free_test<F: FieldElement>(modulus: &num_bigint::BigUint) {
+        use num_bigint::BigUint;
+
+        let len = 2 * F::BYTES;
+        let mut inputs = vec![vec![0; len], vec![0xff; len]];
+        for _ in 0..20 {
+            inputs.push((0..len).map(|_| rand::random()).collect());
+        }
+        for bytes in inputs.iter() {
+            let x = F::from_random_rejection_free(bytes).unwrap();
+            let mut encoded = Vec::new();
+            x.append_to(&mut encoded);
+            assert_eq!(
+                BigUint::from_bytes_le(&encoded),
+                BigUint::from_bytes_le(bytes) % modulus
+            );
+        }
+
+        // Extra bytes are ignored.
+        let mut bytes = inputs[2].clone();
+        let want = F::from_random_rejection_free(&bytes).unwrap();
+        bytes.push(1);
+        assert_eq!(F::from_random_rejection_free(&bytes).unwrap(), want);
+        assert_eq!(
+            F::from_random_rejection_free(&bytes[..len - 1]),
+            Err(FieldError::FromBytesShortRead)
+        );
+    }
+
+    #[test]
+    fn test_from_random_rejection_free() {
+        use num_bigint::BigUint;
+
+        fn modulus<F: FieldElementWithInteger>() -> BigUint
+        where
+            F::Integer: Into<u128>,
+        {
+            BigUint::from(F::modulus().into())
+        }
+
+        from_random_rejection_free_test::<Field32>(&modulus::<Field32>());
+        from_random_rejection_free_test::<Field64>(&modulus::<Field64>());
+        from_random_rejection_free_test::<Field61>(&modulus::<Field61>());
+        from_random_rejection_free_test::<Field80>(&modulus::<Field80>());
+        from_random_rejection_free_test::<Field126>(&modulus::<Field126>());
+        from_random_rejection_free_test::<Field128>(&modulus::<Field128>());
+        let p255 = Field255::modulus()
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u8), |acc, limb| (acc << 64) + limb);
+        from_random_rejection_free_test::<Field255>(&p255);
+
+        // Each coefficient of an element of `Field64x2` is sampled independently.
+        let bytes: Vec<u8> = (0..32).map(|_| rand::random()).collect();
+        let x = Field64x2::from_random_rejection_free(&bytes).unwrap();
+        assert_eq!(
+            x.coefficients(),
+            [
+                Field64::from_random_rejection_free(&bytes[..16]).unwrap(),
+                Field64::from_random_rejection_free(&bytes[16..]).unwrap(),
+            ]
+        );
+        assert_eq!(
+            Field64x2::from_random_rejection_free(&bytes[..31]),
+            Err(FieldError::FromBytesShortRead)
+        );
+    }
+
+    #[test]
+    fn test_associated_consts() {
+        // The constants can be used in const contexts.
+        const LEN: usize = Field64::ENCODED_LEN;
+        let x: Field64 = rand(1).unwrap()[0];
+        let mut buf = [0; LEN];
+        x.encode_into(&mut &mut buf[..]).unwrap();
+        assert_eq!(Field64::read_from(&buf).unwrap(), x);
+
+        assert_eq!(Field32::ENCODED_LEN, Field32::BYTES);
+        assert_eq!(Field255::ENCODED_LEN, Field255::BYTES);
+        assert_eq!(Field64x2::ENCODED_LEN, 16);
+
+        const P: u64 = Field64::MODULUS;
+        assert_eq!(P, Field64::modulus());
+        assert_eq!(Field32::MODULUS, 4293918721);
+        assert_eq!(Field126::MODULUS, Field126::modulus());
+        assert_eq!(Field255::MODULUS, Field255::modulus());
+
+        // The identifiers are part of the wire format.
+        assert_eq!(Field32::ID as u8, 1);
+        assert_eq!(Field64::ID as u8, 2);
+        assert_eq!(Field80::ID as u8, 3);
+        assert_eq!(Field126::ID as u8, 4);
+        assert_eq!(Field128::ID as u8, 5);
+        assert_eq!(Field61::ID as u8, 6);
+        assert_eq!(Field255::ID as u8, 7);
+        assert_eq!(Field64x2::ID as u8, 8);
+    }
+
+    #[cfg(feature = "rand")]
+    fn standard_distribution_test<F: FieldElement>()
+    where
+        rand::distributions::Standard: rand::distributions::Distribution<F>,
+    {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let xs: Vec<F> = (0..100).map(|_| rng.gen()).collect();
+        // The samples are distinct with overwhelming probability.
+        assert!(xs.windows(2).all(|w| w[0] != w[1]));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(rng.gen::<F>(), xs[0]);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_standard_distribution() {
+        standard_distribution_test::<Field32>();
+        standard_distribution_test::<Field64>();
+        standard_distribution_test::<Field61>();
+        standard_distribution_test::<Field80>();
+        standard_distribution_test::<Field126>();
+        standard_distribution_test::<Field128>();
+        standard_distribution_test::<Field255>();
+        standard_distribution_test::<Field64x2>();
+    }
+
+    #[test]
+    fn test_signed() {
+        let one = Field64::one();
+        assert_eq!(Field64::from(-1i64), -one);
+        assert_eq!(Field64::from(1i64), one);
+        assert_eq!(Field64::from(0i64), Field64::zero());
+        assert_eq!(Field64::from(i64::MIN), -Field64::from_reduced(1 << 63));
+        assert_eq!(
+            Field64::try_from(-5i128).unwrap(),
+            -Field64::from_reduced(5)
+        );
+        assert_eq!(
+            Field64::try_from(-i128::from(Field64::modulus())),
+            Err(FieldError::ModulusOverflow)
+        );
+        assert_eq!(
+            Field64::try_from(i128::from(Field64::modulus())),
+            Err(FieldError::ModulusOverflow)
+        );
+
+        // Integers larger than the modulus are reduced by `From<i64>`.
+        let x = i64::from(Field32::modulus()) + 3;
+        assert_eq!(Field32::from(x), Field32::from_reduced(3));
+        assert_eq!(Field32::from(-x), -Field32::from_reduced(3));
+
+        for x in [0i64, 1, -1, 1000, -1000, 1 << 40, -(1 << 40)].iter() {
+            assert_eq!(
+                Field64::from(*x).to_signed(1 << 40).unwrap(),
+                i128::from(*x)
+            );
+            assert_eq!(
+                Field126::from(*x).to_signed(1 << 40).unwrap(),
+                i128::from(*x)
+            );
+        }
+        assert_eq!(
+            Field64::from(1001i64).to_signed(1000),
+            Err(FieldError::SignedOutOfBounds)
+        );
+        assert_eq!(
+            Field64::from(-1001i64).to_signed(1000),
+            Err(FieldError::SignedOutOfBounds)
+        );
+
+        // The result must fit in an `i128`.
+        let x = Field128::from_reduced(1 << 127);
+        assert_eq!(x.to_signed(u128::MAX), Err(FieldError::SignedOutOfBounds));
+        let x = -(1 << 100);
+        assert_eq!(Field128::try_from(x).unwrap().to_signed(1 << 100), Ok(x));
+    }
+
+    #[test]
+    fn test_inner_product() {
+        let a: Vec<Field64> = (1..6).map(Field64::from_reduced).collect();
+        let b: Vec<Field64> = (6..11).map(Field64::from_reduced).collect();
+        assert_eq!(inner_product(&a, &b).unwrap(), Field64::from_reduced(130));
+        assert_eq!(inner_product::<Field64>(&[], &[]).unwrap(), Field64::zero());
+        assert_eq!(
+            inner_product(&a, &b[..4]),
+            Err(FieldError::InputSizeMismatch)
+        );
+
+        let a: Vec<Field255> = rand(33).unwrap();
+        let b: Vec<Field255> = rand(33).unwrap();
+        let want: Field255 = a.iter().zip(b.iter()).map(|(x, y)| *x * *y).sum();
+        assert_eq!(inner_product(&a, &b).unwrap(), want);
+    }
+
+    #[test]
+    fn test_bulk_field_ops() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts the calls and the additions offloaded to the backend and computes them with the
+        // default.
+        #[derive(Debug, Default)]
+        struct Counting(AtomicUsize, AtomicUsize);
+
+        impl BulkFieldOps<Field64> for Counting {
+            fn add_assign(&self, out: &mut [Field64], rhs: &[Field64]) {
+                self.0.fetch_add(out.len(), Ordering::Relaxed);
+                self.1.fetch_add(1, Ordering::Relaxed);
+                CpuBulkFieldOps.add_assign(out, rhs)
+            }
+        }
+
+        let backend = Counting::default();
+        let x: Vec<Field64> = rand(100).unwrap();
+        let y: Vec<Field64> = rand(100).unwrap();
+        let mut got = x.clone();
+        merge_vector_with(&backend, &mut got, &y).unwrap();
+        let want: Vec<Field64> = x.iter().zip(y.iter()).map(|(a, b)| *a + *b).collect();
+        assert_eq!(got, want);
+        assert_eq!(backend.0.load(Ordering::Relaxed), 100);
+        assert_eq!(backend.1.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            merge_vector_with(&backend, &mut got, &y[..99]),
+            Err(FieldError::InputSizeMismatch)
+        );
+
+        // Long vectors are passed to the backend in a single call, too.
+        let mut long = vec![Field64::one(); 1 << 18];
+        merge_vector_with(&backend, &mut long, &vec![Field64::one(); 1 << 18]).unwrap();
+        assert_eq!(backend.1.load(Ordering::Relaxed), 2);
+        assert!(long.iter().all(|x| *x == Field64::one() + Field64::one()));
+
+        // Methods that are not overridden fall back to the CPU.
+        let mut got = x.clone();
+        backend.mul_assign(&mut got, &y);
+        let want: Vec<Field64> = x.iter().zip(y.iter()).map(|(a, b)| *a * *b).collect();
+        assert_eq!(got, want);
+        assert_eq!(backend.inner_product(&x, &y), want.iter().sum::<Field64>());
+
+        let mut got = vec![Field64::zero(); 128];
+        let mut want = vec![Field64::zero(); 128];
+        backend.ntt(&mut got, &x, 128).unwrap();
+        discrete_fourier_transform(&mut want, &x, 128).unwrap();
+        assert_eq!(got, want);
+    }
+
+    #[test]
+    fn test_field64x2() {
+        let one = Field64x2::one();
+        let two = one + one;
+        let x = Field64x2::new(Field64::zero(), Field64::one());
+        assert_eq!(x * x, Field64x2::from(Field64::from_reduced(5)));
+        assert_eq!(two * x * x, Field64x2::from(Field64::from_reduced(10)));
+        assert_eq!(x.coefficients(), [Field64::zero(), Field64::one()]);
+        assert_eq!(Field64x2::zero() - one, -one);
+        assert_eq!(x.to_string(), "0 + 1x");
+
+        // The base field is embedded.
+        let a = Field64::from_reduced(1337);
+        let b = Field64::from_reduced(42);
+        assert_eq!(
+            Field64x2::from(a) * Field64x2::from(b),
+            Field64x2::from(a * b)
+        );
+        assert_eq!(
+            Field64x2::from(a) / Field64x2::from(b),
+            Field64x2::from(a / b)
+        );
+
+        let mut prng: Prng<Field64x2> = Prng::new().unwrap();
+        for _ in 0..100 {
+            let f = prng.next().unwrap();
+            let g = prng.next().unwrap();
+            let h = prng.next().unwrap();
+            assert_eq!(f * (g + h), f * g + f * h);
+            assert_eq!((f * g) * h, f * (g * h));
+            assert_eq!(f + g - g, f);
+            if f != Field64x2::zero() {
+                assert_eq!(f * f.inv(), one);
+                assert_eq!(f * g / f, g);
+            }
+        }
+
+        // Roots of unity.
+        let mut l = 0;
+        while let Some(root) = Field64x2::root(l) {
+            let mut y = root;
+            for _ in 0..l {
+                y *= y;
+            }
+            assert_eq!(y, one);
+            l += 1;
+        }
+        assert_eq!(Field64::root(l), None);
+
+        // Encoding.
+        let y = Field64x2::new(a, b);
+        let mut bytes = vec![];
+        y.append_to(&mut bytes);
+        assert_eq!(bytes.len(), Field64x2::BYTES);
+        assert_eq!(Field64x2::read_from(&bytes).unwrap(), y);
+        let mut reader = &bytes[..];
+        assert_eq!(Field64x2::decode_from(&mut reader).unwrap(), y);
+        assert_matches!(
+            Field64x2::read_from(&bytes[..15]),
+            Err(FieldError::FromBytesShortRead)
+        );
+        bytes[8..].copy_from_slice(&[0xff; 8]);
+        assert_matches!(
+            Field64x2::read_from(&bytes),
+            Err(FieldError::FromBytesModulusOverflow)
+        );
+        serde_test::<Field64x2>();
+    }
+
+    #[test]
+    fn test_from_usize() {
+        for n in [0, 1, 2, 3, 1000, u32::MAX as usize, usize::MAX] {
+            assert_eq!(
+                from_usize::<Field64>(n),
+                Field64::from_reduced(n as u64),
+                "n = {}",
+                n
+            );
+            assert_eq!(
+                from_usize::<Field128>(n),
+                Field128::from_reduced(n as u128),
+                "n = {}",
+                n
+            );
+        }
+        assert_eq!(from_usize::<Field32>(4293918721), Field32::zero());
+        assert_eq!(from_usize::<Field61>(1 << 61), Field61::one());
+    }
+
+    #[test]
+    fn test_integer_usize_conversions() {
+        assert_eq!(integer_from_usize::<Field32>(1000), Ok(1000));
+        assert_eq!(
+            integer_from_usize::<Field128>(usize::MAX),
+            Ok(usize::MAX as u128)
+        );
+        assert_eq!(usize_from_integer::<Field64>(1000), Ok(1000));
+        assert_eq!(
+            usize_from_integer::<Field128>(u128::MAX),
+            Err(FieldError::IntegerConversion)
+        );
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            integer_from_usize::<Field32>(1 << 32),
+            Err(FieldError::IntegerConversion)
+        );
+    }
 }
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The binary field `GF(2^128)`.
+//!
+//! Unlike the prime-order fields in this module, [`Field2_128`] has characteristic 2: addition and
+//! subtraction are both XOR, so additive secret shares of an element are XOR shares of its
+//! encoding. The multiplicative group has odd order `2^128 - 1`, so the field has no roots of
+//! unity of order `2^l` and does not implement `FieldElement`; only the arithmetic is provided.
+
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// `GF(2^128)`, represented as polynomials over `GF(2)` modulo the irreducible polynomial
+/// `x^128 + x^7 + x^2 + x + 1`. Bit `i` of the integer representation, as converted with
+/// `From<u128>`, is the coefficient of `x^i`. Multiplication uses the carry-less multiplication
+/// instruction if the CPU supports it.
+#[derive(Clone, Copy, Default)]
+pub struct Field2_128(u128);
+
+impl Field2_128 {
+    /// Size of each field element in bytes.
+    pub const BYTES: usize = 16;
+
+    /// Returns the additive identity.
+    pub fn zero() -> Self {
+        Self(0)
+    }
+
+    /// Returns the multiplicative identity.
+    pub fn one() -> Self {
+        Self(1)
+    }
+
+    /// Modular inversion, i.e., `self^(2^128 - 2)`. If `self` is 0, then the output is 0. The
+    /// runtime does not depend on the value of `self`.
+    pub fn inv(&self) -> Self {
+        // The exponent 2^128 - 2 has every bit set except the least significant one.
+        let mut out = Self::one();
+        for _ in 0..127 {
+            out = out * out * *self;
+        }
+        out * out
+    }
+
+    /// Returns the little-endian encoding of the element.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        self.0.to_le_bytes()
+    }
+
+    /// Decodes an element from its little-endian encoding. Every byte string of length `BYTES`
+    /// encodes an element.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Self {
+        Self(u128::from_le_bytes(*bytes))
+    }
+}
+
+// Returns the product of `a` and `b` as polynomials over `GF(2)`, split into the high and low 128
+// bits.
+fn clmul(a: u128, b: u128) -> (u128, u128) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("pclmulqdq") {
+            // Safety: the CPU supports the instruction.
+            return unsafe { clmul_x86(a, b) };
+        }
+    }
+    clmul_portable(a, b)
+}
+
+// Carry-less multiplication of two 64-bit words. The runtime does not depend on the inputs.
+fn clmul64(a: u64, b: u64) -> u128 {
+    let mut out = 0;
+    for i in 0..64 {
+        let mask = 0u128.wrapping_sub(u128::from((b >> i) & 1));
+        out ^= (u128::from(a) << i) & mask;
+    }
+    out
+}
+
+fn clmul_portable(a: u128, b: u128) -> (u128, u128) {
+    let (a0, a1) = (a as u64, (a >> 64) as u64);
+    let (b0, b1) = (b as u64, (b >> 64) as u64);
+    let lo = clmul64(a0, b0);
+    let hi = clmul64(a1, b1);
+    let mid = clmul64(a0, b1) ^ clmul64(a1, b0);
+    (hi ^ (mid >> 64), lo ^ (mid << 64))
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq", enable = "sse2")]
+unsafe fn clmul_x86(a: u128, b: u128) -> (u128, u128) {
+    use std::arch::x86_64::{__m128i, _mm_clmulepi64_si128};
+    use std::mem::transmute;
+
+    let a: __m128i = transmute(a);
+    let b: __m128i = transmute(b);
+    let lo: u128 = transmute(_mm_clmulepi64_si128(a, b, 0x00));
+    let hi: u128 = transmute(_mm_clmulepi64_si128(a, b, 0x11));
+    let mid0: u128 = transmute(_mm_clmulepi64_si128(a, b, 0x01));
+    let mid1: u128 = transmute(_mm_clmulepi64_si128(a, b, 0x10));
+    let mid = mid0 ^ mid1;
+    (hi ^ (mid >> 64), lo ^ (mid << 64))
+}
+
+// Reduces `hi * x^128 + lo` modulo `x^128 + x^7 + x^2 + x + 1`.
+fn reduce(hi: u128, lo: u128) -> u128 {
+    // x^128 = x^7 + x^2 + x + 1, so hi * x^128 = hi * (x^7 + x^2 + x + 1). The bits of this
+    // product above x^127 are folded in once more; they have degree at most 6.
+    let overflow = (hi >> 127) ^ (hi >> 126) ^ (hi >> 121);
+    let folded = overflow ^ (overflow << 1) ^ (overflow << 2) ^ (overflow << 7);
+    lo ^ hi ^ (hi << 1) ^ (hi << 2) ^ (hi << 7) ^ folded
+}
+
+impl PartialEq for Field2_128 {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.ct_eq(rhs).into()
+    }
+}
+
+impl Eq for Field2_128 {}
+
+impl ConstantTimeEq for Field2_128 {
+    fn ct_eq(&self, rhs: &Self) -> Choice {
+        self.0.ct_eq(&rhs.0)
+    }
+}
+
+impl ConditionallySelectable for Field2_128 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(u128::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Field2_128 {}
+
+impl From<u128> for Field2_128 {
+    fn from(x: u128) -> Self {
+        Self(x)
+    }
+}
+
+impl From<Field2_128> for u128 {
+    fn from(x: Field2_128) -> Self {
+        x.0
+    }
+}
+
+impl Add for Field2_128 {
+    type Output = Field2_128;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Add for &Field2_128 {
+    type Output = Field2_128;
+    fn add(self, rhs: Self) -> Field2_128 {
+        *self + *rhs
+    }
+}
+
+impl AddAssign for Field2_128 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Field2_128 {
+    type Output = Field2_128;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Sub for &Field2_128 {
+    type Output = Field2_128;
+    fn sub(self, rhs: Self) -> Field2_128 {
+        *self - *rhs
+    }
+}
+
+impl SubAssign for Field2_128 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Field2_128 {
+    type Output = Field2_128;
+    fn mul(self, rhs: Self) -> Self {
+        let (hi, lo) = clmul(self.0, rhs.0);
+        Self(reduce(hi, lo))
+    }
+}
+
+impl Mul for &Field2_128 {
+    type Output = Field2_128;
+    fn mul(self, rhs: Self) -> Field2_128 {
+        *self * *rhs
+    }
+}
+
+impl MulAssign for Field2_128 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for Field2_128 {
+    type Output = Field2_128;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl Div for &Field2_128 {
+    type Output = Field2_128;
+    fn div(self, rhs: Self) -> Field2_128 {
+        *self / *rhs
+    }
+}
+
+impl DivAssign for Field2_128 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Field2_128 {
+    type Output = Field2_128;
+    fn neg(self) -> Self {
+        // Every element is its own additive inverse.
+        self
+    }
+}
+
+impl Neg for &Field2_128 {
+    type Output = Field2_128;
+    fn neg(self) -> Field2_128 {
+        -*self
+    }
+}
+
+impl Sum for Field2_128 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Field2_128> for Field2_128 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Product for Field2_128 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Field2_128> for Field2_128 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+impl Display for Field2_128 {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:#034x}", self.0)
+    }
+}
+
+impl Debug for Field2_128 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random() -> Field2_128 {
+        Field2_128(rand::random())
+    }
+
+    #[test]
+    fn test_clmul() {
+        for _ in 0..100 {
+            let (a, b): (u128, u128) = (rand::random(), rand::random());
+            // Compare against schoolbook multiplication over the bits.
+            let (mut hi, mut lo) = (0u128, 0u128);
+            for i in 0..128 {
+                if (b >> i) & 1 == 1 {
+                    lo ^= a << i;
+                    if i > 0 {
+                        hi ^= a >> (128 - i);
+                    }
+                }
+            }
+            assert_eq!(clmul_portable(a, b), (hi, lo));
+            assert_eq!(clmul(a, b), (hi, lo));
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let zero = Field2_128::zero();
+        let one = Field2_128::one();
+        let x = Field2_128::from(2);
+
+        // x^128 = x^7 + x^2 + x + 1.
+        let mut y = one;
+        for _ in 0..128 {
+            y *= x;
+        }
+        assert_eq!(y, Field2_128::from(0x87));
+
+        for _ in 0..20 {
+            let (a, b, c) = (random(), random(), random());
+            assert_eq!(a + a, zero);
+            assert_eq!(a - b, a + b);
+            assert_eq!(-a, a);
+            assert_eq!(a * b, b * a);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!(a * one, a);
+            assert_eq!(a * zero, zero);
+            assert_eq!(a * a.inv(), one);
+            assert_eq!((a * b) / b, a);
+            // Squaring is linear in characteristic 2.
+            assert_eq!((a + b) * (a + b), a * a + b * b);
+        }
+        assert_eq!(zero.inv(), zero);
+        assert_eq!(one.inv(), one);
+
+        let xs: Vec<Field2_128> = (0..10).map(|_| random()).collect();
+        assert_eq!(
+            xs.iter().sum::<Field2_128>(),
+            xs.iter().fold(zero, |a, b| a + *b)
+        );
+        assert_eq!(
+            xs.iter().product::<Field2_128>(),
+            xs.iter().fold(one, |a, b| a * *b)
+        );
+    }
+
+    #[test]
+    fn test_xor_sharing() {
+        // Additive shares are XOR shares of the encoding.
+        let secret = random();
+        let share = random();
+        let other = secret - share;
+        let xor: Vec<u8> = share
+            .to_bytes()
+            .iter()
+            .zip(other.to_bytes().iter())
+            .map(|(a, b)| a ^ b)
+            .collect();
+        assert_eq!(xor, secret.to_bytes());
+        assert_eq!(Field2_128::from_bytes(&share.to_bytes()), share);
+        assert_eq!(u128::from(share), u128::from_le_bytes(share.to_bytes()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Field2_128::from(0x87).to_string(),
+            "0x00000000000000000000000000000087"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Element-wise arithmetic over slices of field elements.
+//!
+//! Addition and subtraction are implemented by several backends (see [`FieldOps`]), one of which
+//! is selected at runtime depending on the features supported by the CPU. The vector backends run
+//! kernels for [`Field32`] and [`Field64`], which process several elements per instruction, and
+//! fall back to the scalar backend for other fields. Multiplication and inner products use scalar
+//! loops.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+
+use crate::field::FieldElement;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+use crate::field::{Field32, Field64};
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+use std::any::TypeId;
+
+/// A backend for the element-wise addition and subtraction of slices of field elements. In each
+/// method, `out` and `rhs` must have the same length.
+pub(crate) trait FieldOps {
+    /// Returns `true` if the backend is supported by the CPU.
+    fn is_supported() -> bool;
+
+    /// Sets `out[i] += rhs[i]` for each `i`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `is_supported()` returns `true`.
+    unsafe fn add_assign<F: FieldElement>(out: &mut [F], rhs: &[F]);
+
+    /// Sets `out[i] -= rhs[i]` for each `i`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `is_supported()` returns `true`.
+    unsafe fn sub_assign<F: FieldElement>(out: &mut [F], rhs: &[F]);
+}
+
+// Calls the given method of the best backend supported by the CPU.
+macro_rules! dispatch {
+    ($op:ident($($arg:expr),*)) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if Avx2::is_supported() {
+                // Safety: the backend is supported.
+                return unsafe { Avx2::$op($($arg),*) };
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if Neon::is_supported() {
+                // Safety: the backend is supported.
+                return unsafe { Neon::$op($($arg),*) };
+            }
+        }
+        // Safety: the scalar backend is supported on every CPU.
+        unsafe { Scalar::$op($($arg),*) }
+    }};
+}
+
+// Reinterprets `out` and `rhs` as slices of `R` if `F` is `E`, and returns `None` otherwise.
+//
+// Safety: `E` must be a `#[repr(transparent)]` wrapper around `R`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
+unsafe fn cast<'a, F: FieldElement, E: FieldElement, R>(
+    out: &'a mut [F],
+    rhs: &'a [F],
+) -> Option<(&'a mut [R], &'a [R])> {
+    if TypeId::of::<F>() != TypeId::of::<E>() {
+        return None;
+    }
+    Some((
+        &mut *(out as *mut [F] as *mut [R]),
+        &*(rhs as *const [F] as *const [R]),
+    ))
+}
+
+/// Sets `out[i] += rhs[i]` for each `i`. The slices must have the same length.
+pub(crate) fn add_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+    debug_assert_eq!(out.len(), rhs.len());
+    dispatch!(add_assign(out, rhs))
+}
+
+/// Sets `out[i] -= rhs[i]` for each `i`. The slices must have the same length.
+pub(crate) fn sub_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+    debug_assert_eq!(out.len(), rhs.len());
+    dispatch!(sub_assign(out, rhs))
+}
+
+/// Sets `out[i] *= rhs[i]` for each `i`. The slices must have the same length.
+pub(crate) fn mul_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+    debug_assert_eq!(out.len(), rhs.len());
+    for (x, y) in out.iter_mut().zip(rhs.iter()) {
+        *x *= *y;
+    }
+}
+
+/// Returns the sum of `lhs[i] * rhs[i]` over each `i`. The slices must have the same length.
+pub(crate) fn inner_product<F: FieldElement>(lhs: &[F], rhs: &[F]) -> F {
+    debug_assert_eq!(lhs.len(), rhs.len());
+
+    // The loop is unrolled into four independent sums so that consecutive multiplications do not
+    // depend on one another.
+    let mut acc = [F::zero(); 4];
+    let lhs_chunks = lhs.chunks_exact(4);
+    let rhs_chunks = rhs.chunks_exact(4);
+    let mut sum = F::zero();
+    for (x, y) in lhs_chunks.remainder().iter().zip(rhs_chunks.remainder()) {
+        sum += *x * *y;
+    }
+    for (x, y) in lhs_chunks.zip(rhs_chunks) {
+        acc[0] += x[0] * y[0];
+        acc[1] += x[1] * y[1];
+        acc[2] += x[2] * y[2];
+        acc[3] += x[3] * y[3];
+    }
+    sum + (acc[0] + acc[1]) + (acc[2] + acc[3])
+}
+
+/// The portable backend.
+pub(crate) struct Scalar;
+
+impl FieldOps for Scalar {
+    fn is_supported() -> bool {
+        true
+    }
+
+    unsafe fn add_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        for (x, y) in out.iter_mut().zip(rhs.iter()) {
+            *x += *y;
+        }
+    }
+
+    unsafe fn sub_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        for (x, y) in out.iter_mut().zip(rhs.iter()) {
+            *x -= *y;
+        }
+    }
+}
+
+/// The backend for x86 CPUs that support AVX2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) struct Avx2;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl FieldOps for Avx2 {
+    fn is_supported() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+
+    unsafe fn add_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        // `Field32` and `Field64` are transparent wrappers around `u32` and `u64`.
+        if let Some((out, rhs)) = cast::<F, Field32, u32>(out, rhs) {
+            return avx2::add_assign_field32(out, rhs);
+        }
+        if let Some((out, rhs)) = cast::<F, Field64, u64>(out, rhs) {
+            return avx2::add_assign_field64(out, rhs);
+        }
+        Scalar::add_assign(out, rhs)
+    }
+
+    unsafe fn sub_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        if let Some((out, rhs)) = cast::<F, Field32, u32>(out, rhs) {
+            return avx2::sub_assign_field32(out, rhs);
+        }
+        if let Some((out, rhs)) = cast::<F, Field64, u64>(out, rhs) {
+            return avx2::sub_assign_field64(out, rhs);
+        }
+        Scalar::sub_assign(out, rhs)
+    }
+}
+
+/// The backend for aarch64 CPUs that support NEON.
+#[cfg(target_arch = "aarch64")]
+pub(crate) struct Neon;
+
+#[cfg(target_arch = "aarch64")]
+impl FieldOps for Neon {
+    fn is_supported() -> bool {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+
+    unsafe fn add_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        // `Field32` and `Field64` are transparent wrappers around `u32` and `u64`.
+        if let Some((out, rhs)) = cast::<F, Field32, u32>(out, rhs) {
+            return neon::add_assign_field32(out, rhs);
+        }
+        if let Some((out, rhs)) = cast::<F, Field64, u64>(out, rhs) {
+            return neon::add_assign_field64(out, rhs);
+        }
+        Scalar::add_assign(out, rhs)
+    }
+
+    unsafe fn sub_assign<F: FieldElement>(out: &mut [F], rhs: &[F]) {
+        if let Some((out, rhs)) = cast::<F, Field32, u32>(out, rhs) {
+            return neon::sub_assign_field32(out, rhs);
+        }
+        if let Some((out, rhs)) = cast::<F, Field64, u64>(out, rhs) {
+            return neon::sub_assign_field64(out, rhs);
+        }
+        Scalar::sub_assign(out, rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{rand, Field126, Field32, Field61, Field64};
+    use crate::fp::{FP32, FP64};
+
+    // Checks backend `B` against the scalar backend, including on the given edge cases, e.g.,
+    // the representatives `0`, `1` and `p - 1`, whose sums and differences wrap around the
+    // modulus.
+    fn field_ops_test<F: FieldElement, B: FieldOps>(edge_cases: &[F]) {
+        if !B::is_supported() {
+            return;
+        }
+
+        for len in [0, 1, 7, 8, 9, 33, 100].iter() {
+            let mut x: Vec<F> = rand(*len).unwrap();
+            let mut y: Vec<F> = rand(*len).unwrap();
+            // The first elements cover each pair of edge cases.
+            let n = edge_cases.len();
+            for (i, (a, b)) in x.iter_mut().zip(y.iter_mut()).take(n * n).enumerate() {
+                *a = edge_cases[i % n];
+                *b = edge_cases[i / n];
+            }
+
+            let mut got = x.clone();
+            let mut want = x.clone();
+            unsafe {
+                B::add_assign(&mut got, &y);
+                Scalar::add_assign(&mut want, &y);
+            }
+            assert_eq!(got, want);
+
+            let mut got = x.clone();
+            let mut want = x.clone();
+            unsafe {
+                B::sub_assign(&mut got, &y);
+                Scalar::sub_assign(&mut want, &y);
+            }
+            assert_eq!(got, want);
+        }
+    }
+
+    fn backend_test<B: FieldOps>() {
+        field_ops_test::<Field32, B>(&[Field32(0), Field32(1), Field32(FP32.p - 1)]);
+        field_ops_test::<Field64, B>(&[Field64(0), Field64(1), Field64(FP64.p - 1)]);
+        field_ops_test::<Field126, B>(&[Field126::zero(), Field126::one(), -Field126::one()]);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn test_avx2() {
+        backend_test::<Avx2>();
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn test_neon() {
+        backend_test::<Neon>();
+    }
+
+    fn bulk_ops_test<F: FieldElement>() {
+        for len in [0, 1, 7, 8, 100].iter() {
+            let x: Vec<F> = rand(*len).unwrap();
+            let y: Vec<F> = rand(*len).unwrap();
+
+            let mut got = x.clone();
+            add_assign(&mut got, &y);
+            let want: Vec<F> = x.iter().zip(y.iter()).map(|(a, b)| *a + *b).collect();
+            assert_eq!(got, want);
+
+            let mut got = x.clone();
+            sub_assign(&mut got, &y);
+            let want: Vec<F> = x.iter().zip(y.iter()).map(|(a, b)| *a - *b).collect();
+            assert_eq!(got, want);
+
+            let mut got = x.clone();
+            mul_assign(&mut got, &y);
+            let want: Vec<F> = x.iter().zip(y.iter()).map(|(a, b)| *a * *b).collect();
+            assert_eq!(got, want);
+
+            let got = inner_product(&x, &y);
+            let want = want.iter().sum();
+            assert_eq!(got, want);
+        }
+    }
+
+    #[test]
+    fn test_field32() {
+        bulk_ops_test::<Field32>();
+    }
+
+    #[test]
+    fn test_field61() {
+        bulk_ops_test::<Field61>();
+    }
+
+    #[test]
+    fn test_field64() {
+        bulk_ops_test::<Field64>();
+    }
+
+    #[test]
+    fn test_field126() {
+        bulk_ops_test::<Field126>();
+    }
+}
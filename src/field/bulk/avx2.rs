@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! AVX2 kernels for the addition and subtraction of [`Field32`](crate::field::Field32) and
+//! [`Field64`](crate::field::Field64) elements, which process eight and four elements per
+//! instruction, respectively.
+//!
+//! The kernels operate on the representatives of the elements, i.e., integers in `[0, p)`. The
+//! sum `x + y` is computed as `x - (p - y)`, adding `p` back if the subtraction borrows, so that no
+//! intermediate value exceeds the word size. AVX2 only compares signed integers, so `a < b` is
+//! computed as `(a ^ MIN) < (b ^ MIN)` for the smallest signed integer `MIN`.
+
+use crate::fp::{FP32, FP64};
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+macro_rules! kernels {
+    (
+        $add:ident, $sub:ident, $repr:ty, $signed:ident, $fp:ident, $lanes:literal,
+        $set1:ident, $add_epi:ident, $sub_epi:ident, $cmpgt_epi:ident
+    ) => {
+        /// Sets `out[i] = out[i] + rhs[i] mod p` for each `i`.
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX2.
+        #[target_feature(enable = "avx2")]
+        pub(super) unsafe fn $add(out: &mut [$repr], rhs: &[$repr]) {
+            let p = $set1($fp.p as $signed);
+            let min = $set1($signed::MIN);
+            let mut out_chunks = out.chunks_exact_mut($lanes);
+            let mut rhs_chunks = rhs.chunks_exact($lanes);
+            for (x, y) in (&mut out_chunks).zip(&mut rhs_chunks) {
+                let x_vec = _mm256_loadu_si256(x.as_ptr() as *const __m256i);
+                let y_vec = _mm256_loadu_si256(y.as_ptr() as *const __m256i);
+                let q = $sub_epi(p, y_vec);
+                let borrow = $cmpgt_epi(_mm256_xor_si256(q, min), _mm256_xor_si256(x_vec, min));
+                let z = $add_epi($sub_epi(x_vec, q), _mm256_and_si256(borrow, p));
+                _mm256_storeu_si256(x.as_mut_ptr() as *mut __m256i, z);
+            }
+            for (x, y) in out_chunks
+                .into_remainder()
+                .iter_mut()
+                .zip(rhs_chunks.remainder())
+            {
+                *x = $fp.add(*x, *y);
+            }
+        }
+
+        /// Sets `out[i] = out[i] - rhs[i] mod p` for each `i`.
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support AVX2.
+        #[target_feature(enable = "avx2")]
+        pub(super) unsafe fn $sub(out: &mut [$repr], rhs: &[$repr]) {
+            let p = $set1($fp.p as $signed);
+            let min = $set1($signed::MIN);
+            let mut out_chunks = out.chunks_exact_mut($lanes);
+            let mut rhs_chunks = rhs.chunks_exact($lanes);
+            for (x, y) in (&mut out_chunks).zip(&mut rhs_chunks) {
+                let x_vec = _mm256_loadu_si256(x.as_ptr() as *const __m256i);
+                let y_vec = _mm256_loadu_si256(y.as_ptr() as *const __m256i);
+                let borrow = $cmpgt_epi(_mm256_xor_si256(y_vec, min), _mm256_xor_si256(x_vec, min));
+                let z = $add_epi($sub_epi(x_vec, y_vec), _mm256_and_si256(borrow, p));
+                _mm256_storeu_si256(x.as_mut_ptr() as *mut __m256i, z);
+            }
+            for (x, y) in out_chunks
+                .into_remainder()
+                .iter_mut()
+                .zip(rhs_chunks.remainder())
+            {
+                *x = $fp.sub(*x, *y);
+            }
+        }
+    };
+}
+
+kernels!(
+    add_assign_field32,
+    sub_assign_field32,
+    u32,
+    i32,
+    FP32,
+    8,
+    _mm256_set1_epi32,
+    _mm256_add_epi32,
+    _mm256_sub_epi32,
+    _mm256_cmpgt_epi32
+);
+
+kernels!(
+    add_assign_field64,
+    sub_assign_field64,
+    u64,
+    i64,
+    FP64,
+    4,
+    _mm256_set1_epi64x,
+    _mm256_add_epi64,
+    _mm256_sub_epi64,
+    _mm256_cmpgt_epi64
+);
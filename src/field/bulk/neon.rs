@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! NEON kernels for the addition and subtraction of [`Field32`](crate::field::Field32) and
+//! [`Field64`](crate::field::Field64) elements, which process four and two elements per
+//! instruction, respectively.
+//!
+//! The kernels operate on the representatives of the elements, i.e., integers in `[0, p)`. The
+//! sum `x + y` is computed as `x - (p - y)`, adding `p` back if the subtraction borrows, so that no
+//! intermediate value exceeds the word size.
+
+use crate::fp::{FP32, FP64};
+use std::arch::aarch64::*;
+
+macro_rules! kernels {
+    (
+        $add:ident, $sub:ident, $repr:ty, $fp:ident, $lanes:literal,
+        $ld:ident, $st:ident, $dup:ident, $addq:ident, $subq:ident, $cgtq:ident, $andq:ident
+    ) => {
+        /// Sets `out[i] = out[i] + rhs[i] mod p` for each `i`.
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support NEON.
+        #[target_feature(enable = "neon")]
+        pub(super) unsafe fn $add(out: &mut [$repr], rhs: &[$repr]) {
+            let p = $dup($fp.p);
+            let mut out_chunks = out.chunks_exact_mut($lanes);
+            let mut rhs_chunks = rhs.chunks_exact($lanes);
+            for (x, y) in (&mut out_chunks).zip(&mut rhs_chunks) {
+                let x_vec = $ld(x.as_ptr());
+                let y_vec = $ld(y.as_ptr());
+                let q = $subq(p, y_vec);
+                let borrow = $cgtq(q, x_vec);
+                let z = $addq($subq(x_vec, q), $andq(borrow, p));
+                $st(x.as_mut_ptr(), z);
+            }
+            for (x, y) in out_chunks
+                .into_remainder()
+                .iter_mut()
+                .zip(rhs_chunks.remainder())
+            {
+                *x = $fp.add(*x, *y);
+            }
+        }
+
+        /// Sets `out[i] = out[i] - rhs[i] mod p` for each `i`.
+        ///
+        /// # Safety
+        ///
+        /// The CPU must support NEON.
+        #[target_feature(enable = "neon")]
+        pub(super) unsafe fn $sub(out: &mut [$repr], rhs: &[$repr]) {
+            let p = $dup($fp.p);
+            let mut out_chunks = out.chunks_exact_mut($lanes);
+            let mut rhs_chunks = rhs.chunks_exact($lanes);
+            for (x, y) in (&mut out_chunks).zip(&mut rhs_chunks) {
+                let x_vec = $ld(x.as_ptr());
+                let y_vec = $ld(y.as_ptr());
+                let borrow = $cgtq(y_vec, x_vec);
+                let z = $addq($subq(x_vec, y_vec), $andq(borrow, p));
+                $st(x.as_mut_ptr(), z);
+            }
+            for (x, y) in out_chunks
+                .into_remainder()
+                .iter_mut()
+                .zip(rhs_chunks.remainder())
+            {
+                *x = $fp.sub(*x, *y);
+            }
+        }
+    };
+}
+
+kernels!(
+    add_assign_field32,
+    sub_assign_field32,
+    u32,
+    FP32,
+    4,
+    vld1q_u32,
+    vst1q_u32,
+    vdupq_n_u32,
+    vaddq_u32,
+    vsubq_u32,
+    vcgtq_u32,
+    vandq_u32
+);
+
+kernels!(
+    add_assign_field64,
+    sub_assign_field64,
+    u64,
+    FP64,
+    2,
+    vld1q_u64,
+    vst1q_u64,
+    vdupq_n_u64,
+    vaddq_u64,
+    vsubq_u64,
+    vcgtq_u64,
+    vandq_u64
+);
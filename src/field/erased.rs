@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Field elements and vectors whose field is selected at runtime.
+//!
+//! The types in this module dispatch over every field in this crate. They allow an application
+//! that handles several configurations, each with its own field, to run a single aggregation
+//! pipeline rather than one monomorphized copy per field. Operations on operands from different
+//! fields fail with [`FieldError::FieldMismatch`].
+
+use crate::field::{
+    merge_vector, Field126, Field128, Field255, Field32, Field61, Field64, Field64x2, Field80,
+    FieldElement, FieldError,
+};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    ops::Neg,
+};
+
+// Defines `FieldId`, `FieldEnum` and `FieldVec` with one variant per field, named after the
+// field's type. The discriminant of each `FieldId` variant is the identifier of the field in
+// versioned encodings and must not change.
+macro_rules! erased_fields {
+    ($($field:ident = $id:literal),* $(,)?) => {
+        /// Identifies a field, e.g., in [`FieldEnum`] and [`FieldVec`]. The value of a variant
+        /// cast to `u8` identifies the field in versioned encodings, such as the header written by
+        /// [`util::serialize_with_header`](crate::util::serialize_with_header).
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[repr(u8)]
+        pub enum FieldId {
+            $(
+                #[doc = concat!("[`", stringify!($field), "`].")]
+                $field = $id,
+            )*
+        }
+
+        impl FieldId {
+            /// Returns the size of an encoded element of the field in bytes.
+            pub fn encoded_len(&self) -> usize {
+                match self {
+                    $(FieldId::$field => $field::BYTES,)*
+                }
+            }
+        }
+
+        impl TryFrom<u8> for FieldId {
+            type Error = FieldError;
+
+            /// Returns the field identified by `id`, or [`FieldError::UnknownFieldId`].
+            fn try_from(id: u8) -> Result<Self, FieldError> {
+                match id {
+                    $($id => Ok(FieldId::$field),)*
+                    _ => Err(FieldError::UnknownFieldId(id)),
+                }
+            }
+        }
+
+        /// An element of a field selected at runtime.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum FieldEnum {
+            $(
+                #[doc = concat!("An element of [`", stringify!($field), "`].")]
+                $field($field),
+            )*
+        }
+
+        impl FieldEnum {
+            /// Returns the field the element belongs to.
+            pub fn field_id(&self) -> FieldId {
+                match self {
+                    $(FieldEnum::$field(_) => FieldId::$field,)*
+                }
+            }
+
+            /// Returns the additive identity of the given field.
+            pub fn zero(id: FieldId) -> Self {
+                match id {
+                    $(FieldId::$field => FieldEnum::$field($field::zero()),)*
+                }
+            }
+
+            /// Returns the multiplicative identity of the given field.
+            pub fn one(id: FieldId) -> Self {
+                match id {
+                    $(FieldId::$field => FieldEnum::$field($field::one()),)*
+                }
+            }
+
+            /// Returns `self + rhs`, or an error if the operands belong to different fields.
+            pub fn checked_add(self, rhs: Self) -> Result<Self, FieldError> {
+                match (self, rhs) {
+                    $((FieldEnum::$field(x), FieldEnum::$field(y)) => Ok(FieldEnum::$field(x + y)),)*
+                    _ => Err(FieldError::FieldMismatch),
+                }
+            }
+
+            /// Returns `self - rhs`, or an error if the operands belong to different fields.
+            pub fn checked_sub(self, rhs: Self) -> Result<Self, FieldError> {
+                match (self, rhs) {
+                    $((FieldEnum::$field(x), FieldEnum::$field(y)) => Ok(FieldEnum::$field(x - y)),)*
+                    _ => Err(FieldError::FieldMismatch),
+                }
+            }
+
+            /// Returns `self * rhs`, or an error if the operands belong to different fields.
+            pub fn checked_mul(self, rhs: Self) -> Result<Self, FieldError> {
+                match (self, rhs) {
+                    $((FieldEnum::$field(x), FieldEnum::$field(y)) => Ok(FieldEnum::$field(x * y)),)*
+                    _ => Err(FieldError::FieldMismatch),
+                }
+            }
+
+            /// Returns `self^-1`. If `self` is 0, then the output is undefined.
+            pub fn inv(&self) -> Self {
+                match self {
+                    $(FieldEnum::$field(x) => FieldEnum::$field(x.inv()),)*
+                }
+            }
+
+            /// Writes the encoding of the element, as written by `FieldElement::append_to()`, to
+            /// the end of `bytes`.
+            pub fn append_to(&self, bytes: &mut Vec<u8>) {
+                match self {
+                    $(FieldEnum::$field(x) => x.append_to(bytes),)*
+                }
+            }
+
+            /// Decodes an element of the given field as `FieldElement::read_from()` does.
+            pub fn read_from(id: FieldId, bytes: &[u8]) -> Result<Self, FieldError> {
+                Ok(match id {
+                    $(FieldId::$field => FieldEnum::$field($field::read_from(bytes)?),)*
+                })
+            }
+        }
+
+        impl Neg for FieldEnum {
+            type Output = FieldEnum;
+            fn neg(self) -> Self {
+                match self {
+                    $(FieldEnum::$field(x) => FieldEnum::$field(-x),)*
+                }
+            }
+        }
+
+        impl Display for FieldEnum {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                match self {
+                    $(FieldEnum::$field(x) => write!(f, "{}", x),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$field> for FieldEnum {
+                fn from(x: $field) -> Self {
+                    FieldEnum::$field(x)
+                }
+            }
+        )*
+
+        /// A vector of elements of a field selected at runtime, e.g., the accumulator of an
+        /// aggregation.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum FieldVec {
+            $(
+                #[doc = concat!("A vector of [`", stringify!($field), "`] elements.")]
+                $field(Vec<$field>),
+            )*
+        }
+
+        impl FieldVec {
+            /// Returns a vector of `len` zeros of the given field.
+            pub fn zeros(id: FieldId, len: usize) -> Self {
+                match id {
+                    $(FieldId::$field => FieldVec::$field(vec![$field::zero(); len]),)*
+                }
+            }
+
+            /// Returns the field of the entries.
+            pub fn field_id(&self) -> FieldId {
+                match self {
+                    $(FieldVec::$field(_) => FieldId::$field,)*
+                }
+            }
+
+            /// Returns the number of entries.
+            pub fn len(&self) -> usize {
+                match self {
+                    $(FieldVec::$field(v) => v.len(),)*
+                }
+            }
+
+            /// Returns `true` if the vector has no entries.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Returns the `i`-th entry, or `None` if `i` is out of bounds.
+            pub fn get(&self, i: usize) -> Option<FieldEnum> {
+                match self {
+                    $(FieldVec::$field(v) => v.get(i).copied().map(FieldEnum::from),)*
+                }
+            }
+
+            /// Adds `other` to `self` entry by entry, as [`merge_vector`] does.
+            ///
+            /// # Errors
+            ///
+            /// Fails if the vectors belong to different fields or do not have the same length.
+            pub fn merge(&mut self, other: &FieldVec) -> Result<(), FieldError> {
+                match (self, other) {
+                    $((FieldVec::$field(x), FieldVec::$field(y)) => merge_vector(x, y),)*
+                    _ => Err(FieldError::FieldMismatch),
+                }
+            }
+
+            /// Encodes the vector as `FieldElement::slice_into_byte_vec()` does.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                match self {
+                    $(FieldVec::$field(v) => $field::slice_into_byte_vec(v),)*
+                }
+            }
+
+            /// Decodes a vector of elements of the given field as
+            /// `FieldElement::byte_slice_into_vec()` does.
+            pub fn from_bytes(id: FieldId, bytes: &[u8]) -> Result<Self, FieldError> {
+                Ok(match id {
+                    $(FieldId::$field => FieldVec::$field($field::byte_slice_into_vec(bytes)?),)*
+                })
+            }
+        }
+    };
+}
+
+erased_fields!(
+    Field32 = 1,
+    Field64 = 2,
+    Field80 = 3,
+    Field126 = 4,
+    Field128 = 5,
+    Field61 = 6,
+    Field255 = 7,
+    Field64x2 = 8,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::rand;
+
+    const IDS: [FieldId; 8] = [
+        FieldId::Field32,
+        FieldId::Field64,
+        FieldId::Field80,
+        FieldId::Field126,
+        FieldId::Field128,
+        FieldId::Field61,
+        FieldId::Field255,
+        FieldId::Field64x2,
+    ];
+
+    #[test]
+    fn test_field_enum() {
+        for id in IDS.iter() {
+            let zero = FieldEnum::zero(*id);
+            let one = FieldEnum::one(*id);
+            assert_eq!(zero.field_id(), *id);
+            let two = one.checked_add(one).unwrap();
+            assert_eq!(two.checked_sub(one).unwrap(), one);
+            assert_eq!(two.checked_mul(two.inv()).unwrap(), one);
+            assert_eq!(one.checked_add(-one).unwrap(), zero);
+            if *id != FieldId::Field64x2 {
+                assert_eq!(two.to_string(), "2");
+            }
+
+            let mut bytes = Vec::new();
+            two.append_to(&mut bytes);
+            assert_eq!(bytes.len(), id.encoded_len());
+            assert_eq!(FieldEnum::read_from(*id, &bytes).unwrap(), two);
+
+            assert_eq!(FieldId::try_from(*id as u8).unwrap(), *id);
+        }
+        assert_eq!(FieldId::try_from(0), Err(FieldError::UnknownFieldId(0)));
+        assert_eq!(FieldId::try_from(9), Err(FieldError::UnknownFieldId(9)));
+
+        let x = FieldEnum::from(Field32::one());
+        let y = FieldEnum::from(Field64::one());
+        assert_eq!(x.checked_add(y), Err(FieldError::FieldMismatch));
+        assert_eq!(x.checked_sub(y), Err(FieldError::FieldMismatch));
+        assert_eq!(x.checked_mul(y), Err(FieldError::FieldMismatch));
+    }
+
+    #[test]
+    fn test_field_vec() {
+        let x: Vec<Field64> = rand(10).unwrap();
+        let y: Vec<Field64> = rand(10).unwrap();
+        let want: Vec<Field64> = x.iter().zip(y.iter()).map(|(a, b)| *a + *b).collect();
+
+        // Run the same pipeline for each field: decode the shares, then sum them.
+        let mut acc = FieldVec::zeros(FieldId::Field64, 10);
+        for share in [&x, &y].iter() {
+            let bytes = Field64::slice_into_byte_vec(share);
+            acc.merge(&FieldVec::from_bytes(acc.field_id(), &bytes).unwrap())
+                .unwrap();
+        }
+        assert_eq!(acc, FieldVec::Field64(want.clone()));
+        assert_eq!(acc.len(), 10);
+        assert_eq!(acc.get(3), Some(FieldEnum::Field64(want[3])));
+        assert_eq!(acc.get(10), None);
+        assert_eq!(
+            FieldVec::from_bytes(FieldId::Field64, &acc.to_bytes()).unwrap(),
+            acc
+        );
+
+        assert_eq!(
+            acc.merge(&FieldVec::zeros(FieldId::Field32, 10)),
+            Err(FieldError::FieldMismatch)
+        );
+        assert_eq!(
+            acc.merge(&FieldVec::zeros(FieldId::Field64, 9)),
+            Err(FieldError::InputSizeMismatch)
+        );
+        for id in IDS.iter() {
+            let v = FieldVec::zeros(*id, 0);
+            assert!(v.is_empty());
+            assert_eq!(v.field_id(), *id);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Shamir threshold secret sharing.
+//!
+//! Unlike the additive sharing computed by [`split`](crate::field::split), where every share is
+//! needed to recover the input, a `t`-of-`n` Shamir sharing can be reconstructed from any `t` of
+//! its `n` shares, while any `t - 1` shares reveal nothing about the input. Each element of the
+//! input is the constant term of its own random polynomial of degree `t - 1`, and share `i` holds
+//! the evaluations of these polynomials at `x = i`, for `i = 1, ..., n`.
+
+use crate::field::FieldElement;
+use crate::prng::Prng;
+use rand_core::{CryptoRng, RngCore};
+
+/// Errors from Shamir secret sharing.
+#[derive(Debug, thiserror::Error)]
+pub enum ShamirError {
+    /// The threshold is zero or larger than the number of shares.
+    #[error("threshold must be between 1 and the number of shares")]
+    InvalidThreshold,
+    /// `shamir_reconstruct()` was called without any shares.
+    #[error("no shares to reconstruct from")]
+    NoShares,
+    /// The shares passed to `shamir_reconstruct()` have different lengths.
+    #[error("shares have different lengths")]
+    ShareLengthMismatch,
+    /// A share passed to `shamir_reconstruct()` has index 0, or two shares have the same index.
+    #[error("share indices must be non-zero and distinct")]
+    InvalidIndex,
+    /// Failure to get randomness from the operating system.
+    #[error("getrandom error")]
+    GetRandom(#[from] getrandom::Error),
+    /// Failure to get randomness from a caller-supplied generator.
+    #[error("rng error")]
+    Rng(#[from] rand_core::Error),
+}
+
+/// One share of a Shamir secret sharing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShamirShare<F: FieldElement> {
+    /// The point at which the sharing polynomials were evaluated. Shares are numbered from 1.
+    pub index: u64,
+    /// The evaluations of the sharing polynomials, one per element of the input.
+    pub data: Vec<F>,
+}
+
+/// Outputs a `threshold`-of-`num_shares` Shamir secret sharing of the input. Any `threshold` of
+/// the shares can be passed to [`shamir_reconstruct`] to recover the input.
+pub fn shamir_split<F: FieldElement>(
+    inp: &[F],
+    threshold: usize,
+    num_shares: usize,
+) -> Result<Vec<ShamirShare<F>>, ShamirError> {
+    check_threshold(threshold, num_shares)?;
+    Ok(shamir_split_with_prng(
+        inp,
+        threshold,
+        num_shares,
+        Prng::new()?,
+    ))
+}
+
+/// Like [`shamir_split`], except that the randomness is drawn from `rng` rather than from the
+/// operating system.
+pub fn shamir_split_with_rng<F: FieldElement, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    inp: &[F],
+    threshold: usize,
+    num_shares: usize,
+) -> Result<Vec<ShamirShare<F>>, ShamirError> {
+    check_threshold(threshold, num_shares)?;
+    Ok(shamir_split_with_prng(
+        inp,
+        threshold,
+        num_shares,
+        Prng::from_rng(rng, None)?,
+    ))
+}
+
+pub(super) fn check_threshold(threshold: usize, num_shares: usize) -> Result<(), ShamirError> {
+    if threshold == 0 || threshold > num_shares {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    Ok(())
+}
+
+fn shamir_split_with_prng<F: FieldElement>(
+    inp: &[F],
+    threshold: usize,
+    num_shares: usize,
+    mut prng: Prng<F>,
+) -> Vec<ShamirShare<F>> {
+    let coeffs = random_polys(inp, threshold, &mut prng);
+    (1..=num_shares as u64)
+        .map(|index| ShamirShare {
+            index,
+            data: eval_polys(&coeffs, F::one() * index),
+        })
+        .collect()
+}
+
+// Returns the coefficients of random polynomials of degree `threshold - 1` whose constant terms
+// are the elements of `constants`. `coeffs[k][j]` is the coefficient of x^k of the polynomial for
+// `constants[j]`.
+pub(super) fn random_polys<F: FieldElement>(
+    constants: &[F],
+    threshold: usize,
+    prng: &mut Prng<F>,
+) -> Vec<Vec<F>> {
+    let mut coeffs = vec![constants.to_vec()];
+    for _ in 1..threshold {
+        let mut c = vec![F::zero(); constants.len()];
+        prng.fill(&mut c);
+        coeffs.push(c);
+    }
+    coeffs
+}
+
+// Evaluates the polynomials returned by `random_polys()` at `x`.
+pub(super) fn eval_polys<F: FieldElement>(coeffs: &[Vec<F>], x: F) -> Vec<F> {
+    let len = coeffs.first().map_or(0, Vec::len);
+    (0..len)
+        .map(|j| {
+            // Horner's method, from the leading coefficient down.
+            coeffs.iter().rev().fold(F::zero(), |acc, c| acc * x + c[j])
+        })
+        .collect()
+}
+
+/// Recovers the input from the shares of a Shamir secret sharing by Lagrange interpolation at
+/// zero. At least as many shares as the threshold used to split the input must be provided,
+/// otherwise the output is unrelated to the input.
+pub fn shamir_reconstruct<F: FieldElement>(
+    shares: &[ShamirShare<F>],
+) -> Result<Vec<F>, ShamirError> {
+    let len = shares.first().ok_or(ShamirError::NoShares)?.data.len();
+    if shares.iter().any(|share| share.data.len() != len) {
+        return Err(ShamirError::ShareLengthMismatch);
+    }
+
+    let xs: Vec<F> = shares.iter().map(|share| F::one() * share.index).collect();
+    for (i, x) in xs.iter().enumerate() {
+        if *x == F::zero() || xs[..i].contains(x) {
+            return Err(ShamirError::InvalidIndex);
+        }
+    }
+
+    let mut outp = vec![F::zero(); len];
+    for (i, share) in shares.iter().enumerate() {
+        // The Lagrange basis polynomial for x_i, evaluated at zero.
+        let mut num = F::one();
+        let mut den = F::one();
+        for (m, x) in xs.iter().enumerate() {
+            if m != i {
+                num *= *x;
+                den *= *x - xs[i];
+            }
+        }
+        let basis = num / den;
+
+        for (o, y) in outp.iter_mut().zip(share.data.iter()) {
+            *o += basis * *y;
+        }
+    }
+
+    Ok(outp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{rand, Field32, Field64};
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_roundtrip() {
+        let inp: Vec<Field64> = rand(10).unwrap();
+        let shares = shamir_split(&inp, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        // Any three shares suffice.
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]].iter() {
+            let subset: Vec<_> = subset.iter().map(|i| shares[*i].clone()).collect();
+            assert_eq!(shamir_reconstruct(&subset).unwrap(), inp);
+        }
+        assert_eq!(shamir_reconstruct(&shares).unwrap(), inp);
+
+        // Two shares do not.
+        assert_ne!(shamir_reconstruct(&shares[..2]).unwrap(), inp);
+
+        // With a threshold of 1, every share is the input.
+        let shares = shamir_split(&inp, 1, 3).unwrap();
+        assert!(shares.iter().all(|share| share.data == inp));
+    }
+
+    #[test]
+    fn test_errors() {
+        let inp = [Field32::from(1), Field32::from(2)];
+        assert_matches!(shamir_split(&inp, 0, 3), Err(ShamirError::InvalidThreshold));
+        assert_matches!(shamir_split(&inp, 4, 3), Err(ShamirError::InvalidThreshold));
+
+        let mut shares = shamir_split(&inp, 2, 3).unwrap();
+        assert_matches!(
+            shamir_reconstruct::<Field32>(&[]),
+            Err(ShamirError::NoShares)
+        );
+
+        shares[1].index = shares[0].index;
+        assert_matches!(shamir_reconstruct(&shares), Err(ShamirError::InvalidIndex));
+        shares[1].index = 0;
+        assert_matches!(shamir_reconstruct(&shares), Err(ShamirError::InvalidIndex));
+
+        shares[1].index = 2;
+        shares[1].data.pop();
+        assert_matches!(
+            shamir_reconstruct(&shares),
+            Err(ShamirError::ShareLengthMismatch)
+        );
+    }
+}
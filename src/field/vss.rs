@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Verifiable secret sharing (VSS) of vectors of [`Field255`] elements.
+//!
+//! [`vss_split`] outputs a `t`-of-`n` Shamir sharing of the input, like
+//! [`shamir_split`](crate::field::shamir_split), together with Pedersen commitments to the
+//! coefficients of the sharing polynomials \[[Ped91](https://doi.org/10.1007/3-540-46766-1_9)\].
+//! Before accumulating its share, each server checks it against the commitments with
+//! [`VssShare::verify`]. If every server accepts its share and all of the servers received the
+//! same commitments, then the shares lie on the committed polynomials, so any `t` of them
+//! reconstruct the same vector: a client cannot send inconsistent shares to different servers.
+//!
+//! The servers must check that they received the same commitments, e.g., by exchanging the hash
+//! of their encoding, since otherwise a client can send each server commitments that only match
+//! its own share. The commitments do not show that the input is valid; that is the job of the
+//! proof system in [`pcp`](crate::pcp).
+//!
+//! The commitment to a coefficient `a` is `g^a * h^b` for a random blinding coefficient `b`, so
+//! the commitments reveal nothing about the input. They are binding as long as nobody knows the
+//! discrete logarithm of `h` to the base `g`. `g` and `h` generate the subgroup of order
+//! `p = 2^255 - 19` of the integers modulo the 2048-bit prime `P = 2kp + 1`, and are derived by
+//! hashing fixed strings, so that nobody knows this logarithm.
+
+use crate::codec::{CodecError, Encode, ParameterizedDecode};
+use crate::field::shamir::{check_threshold, eval_polys, random_polys};
+use crate::field::{Field255, FieldElement, ShamirError, ShamirShare};
+use crate::prng::Prng;
+use crypto_bigint::modular::constant_mod::{Residue, ResidueParams};
+use crypto_bigint::{impl_modulus, Encoding, MultiExponentiate, U2048, U256, U64};
+use std::io::{Cursor, Read};
+
+impl_modulus!(
+    VssModulus,
+    U2048,
+    "e5ae2a5fef75731c0fff9c916c6207c871718f48bd1739fb0202aee1c24724acf85091e8d1a020b9e008a202687db98af5516bd03dc6a436531e33fff885285f4b78137d7d800318eb59bc5cdd109382556180e7ec8b68eaff6fa0857ff80197e14ff477d0f6d73abc69ba6e544f0071eb26034333bde6b67991bfe4580e9dadcd90d4a272c0b975fa4ae7d21a5eda8d918d9520279927a3767cbe19e62974f01bb1949840b7c77d5723b80b6d9352f9687579561a827db1bd93c7d13a6110e4bb399446b29d981303186eda093151995d74cab21a9b21bc07d890728f05dc9a9ff161d67cb8b58efcc01e1e35a76eb34ca474499a27f272a804ee94d1b01563"
+);
+
+// An integer modulo `P`.
+type GroupElement = Residue<VssModulus, { U2048::LIMBS }>;
+
+// The length in bytes of an encoded group element.
+const ELEMENT_LEN: usize = 256;
+
+const G: GroupElement = GroupElement::new(&U2048::from_be_hex(
+    "54ea51a8c1d1333af7fdd9e3ad13b709443bcc3ee2090b36fe917019f96b0ee229f285f8aaaaa54f5689bb81ff5218bd601b7435bd037fc098de1867bc67c4e1fa5f63317598ae52b953a4f1a3c141be47afe3622d58dcd94283741ac5c57779ab1c0f4ecdf748fb1a378bd73c61b546d9b040788025cae457370155e7b070495e50fa2ea0c70c7dd3e5dd18b38b5db1ecaa6c1e6af10a889e1d3175185c79a415b473347f9ca4123653a51cd1d8519964a7a56251f1961b8a46d3d62a813a66c662c11a5fcbc4351c715cd184ae3016785c7ee89abc33e5f4a4ccc4b4a2044fd4fbe76054d10c7b1e3fee0c54611faf5d393868ab395b9f03f8d7cb311eb783",
+));
+
+const H: GroupElement = GroupElement::new(&U2048::from_be_hex(
+    "435f561ea086e9ebef7bed7981b3a3b19107c9cdd614830c24344ba5aa57d854355ca99c46d36b1dbd1978ccb4138201e9cedb4fd604a7d11798041de41e0197aac417214d4d340edbb8c4f3d3a919ebd3777a10ce26cc9a8980edaa31b1d1ca24fe26d05571a2201a51f22b563c351c66ca76f50363503a8f1a8807f05791ab124d1e136b13197d8f40ad0d02a205c8a8d7e258fbe80997887ce58c0e82f86467f73aa2ea48c03bc91c07a4a20d7d42c5daa1079480df5cbf7ba47ad680f971b5ed56c615eaf766e7a0803abe51b6737822aac031d7aec1fd48ca83affd270ff577b724f0c17bc681eff619162cf8bc588bd0351130b980daba50869f246480",
+));
+
+/// One share of a verifiable secret sharing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VssShare {
+    /// The Shamir share, which can be passed to
+    /// [`shamir_reconstruct`](crate::field::shamir_reconstruct) once it has been verified.
+    pub share: ShamirShare<Field255>,
+    /// The evaluations of the blinding polynomials, one per element of the input.
+    pub blind: Vec<Field255>,
+}
+
+impl VssShare {
+    /// Returns true if the share is the evaluation at its index of the polynomials that
+    /// `commitments` commits to.
+    pub fn verify(&self, commitments: &VssCommitments) -> bool {
+        let len = self.share.data.len();
+        if self.share.index == 0
+            || self.blind.len() != len
+            || commitments.coeffs.iter().any(|c| c.len() != len)
+        {
+            return false;
+        }
+
+        // The index is public, so its bit length may leak.
+        let x = U64::from_u64(self.share.index);
+        let x_bits = x.bits();
+        self.share
+            .data
+            .iter()
+            .zip(self.blind.iter())
+            .enumerate()
+            .all(|(j, (value, blind))| {
+                // Computes the product of C_k^(x^k) over k by Horner's method, where C_k is the
+                // commitment to the coefficient of x^k.
+                let want = commitments
+                    .coeffs
+                    .iter()
+                    .rev()
+                    .fold(GroupElement::ONE, |acc, c| {
+                        acc.pow_bounded_exp(&x, x_bits) * c[j]
+                    });
+                commit(value, blind) == want
+            })
+    }
+}
+
+/// Pedersen commitments to the coefficients of the polynomials of a verifiable secret sharing.
+///
+/// The encoding is the sequence of commitments, each a 256-byte big-endian integer. Decoding takes
+/// the threshold and the length of the input as a parameter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VssCommitments {
+    // coeffs[k][j] commits to the coefficient of x^k of the polynomial for element j of the input.
+    coeffs: Vec<Vec<GroupElement>>,
+}
+
+impl Encode for VssCommitments {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        for c in self.coeffs.iter().flatten() {
+            bytes.extend_from_slice(&c.retrieve().to_be_bytes());
+        }
+    }
+}
+
+impl ParameterizedDecode<(usize, usize)> for VssCommitments {
+    fn decode_with_param(
+        (threshold, len): &(usize, usize),
+        bytes: &mut Cursor<&[u8]>,
+    ) -> Result<Self, CodecError> {
+        if *threshold == 0 {
+            return Err(CodecError::UnexpectedValue);
+        }
+
+        let mut coeffs = Vec::with_capacity(*threshold);
+        for _ in 0..*threshold {
+            let mut c = Vec::with_capacity(*len);
+            for _ in 0..*len {
+                let mut buf = [0; ELEMENT_LEN];
+                bytes.read_exact(&mut buf)?;
+                // Elements outside of the subgroup of order p are not rejected: `verify()` only
+                // constrains their components in the subgroup, which is all that binding needs.
+                let int = U2048::from_be_slice(&buf);
+                if int == U2048::ZERO || int >= VssModulus::MODULUS {
+                    return Err(CodecError::UnexpectedValue);
+                }
+                c.push(GroupElement::new(&int));
+            }
+            coeffs.push(c);
+        }
+        Ok(Self { coeffs })
+    }
+}
+
+/// Outputs a `threshold`-of-`num_shares` verifiable secret sharing of the input, along with the
+/// commitments that each share is verified against. The commitments must be sent to every server.
+pub fn vss_split(
+    inp: &[Field255],
+    threshold: usize,
+    num_shares: usize,
+) -> Result<(Vec<VssShare>, VssCommitments), ShamirError> {
+    check_threshold(threshold, num_shares)?;
+    let mut prng = Prng::new()?;
+    let blind_constants: Vec<Field255> = (&mut prng).take(inp.len()).collect();
+    let polys = random_polys(inp, threshold, &mut prng);
+    let blind_polys = random_polys(&blind_constants, threshold, &mut prng);
+
+    let coeffs = polys
+        .iter()
+        .zip(blind_polys.iter())
+        .map(|(c, b)| c.iter().zip(b.iter()).map(|(c, b)| commit(c, b)).collect())
+        .collect();
+
+    let shares = (1..=num_shares as u64)
+        .map(|index| {
+            let x = Field255::from(index);
+            VssShare {
+                share: ShamirShare {
+                    index,
+                    data: eval_polys(&polys, x),
+                },
+                blind: eval_polys(&blind_polys, x),
+            }
+        })
+        .collect();
+
+    Ok((shares, VssCommitments { coeffs }))
+}
+
+// Returns the Pedersen commitment g^value * h^blind.
+fn commit(value: &Field255, blind: &Field255) -> GroupElement {
+    GroupElement::multi_exponentiate(&[(G, to_uint(value)), (H, to_uint(blind))])
+}
+
+fn to_uint(x: &Field255) -> U256 {
+    let mut bytes = Vec::with_capacity(Field255::BYTES);
+    x.append_to(&mut bytes);
+    U256::from_le_slice(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{rand, shamir_reconstruct};
+    use assert_matches::assert_matches;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::Shake128;
+
+    // Returns the first `len` bytes of SHAKE128 of `input`, as a big-endian integer.
+    fn shake(input: &[u8], len: usize) -> U2048 {
+        let mut buf = [0; ELEMENT_LEN];
+        let mut hasher = Shake128::default();
+        hasher.update(input);
+        XofReader::read(&mut hasher.finalize_xof(), &mut buf[ELEMENT_LEN - len..]);
+        U2048::from_be_slice(&buf)
+    }
+
+    #[test]
+    fn test_parameters() {
+        let one = U2048::ONE;
+        let p = one.shl_vartime(255).wrapping_sub(&U2048::from_u8(19));
+
+        // P = 2kp + 1, where k is derived from a hash with its top two bits set, then incremented
+        // until P is prime.
+        let mask = one.shl_vartime(1790).wrapping_sub(&one);
+        let k = shake(b"prio vss modulus", 224)
+            .bitand(&mask)
+            .bitor(&U2048::from_u8(3).shl_vartime(1790))
+            .wrapping_add(&U2048::from_u16(2329));
+        let modulus = k.wrapping_mul(&p).shl_vartime(1).wrapping_add(&one);
+        assert_eq!(modulus, VssModulus::MODULUS);
+        assert_eq!(modulus.bits(), 2048);
+        // A Fermat test, base 2.
+        let two = GroupElement::new(&U2048::from_u8(2));
+        assert_eq!(two.pow(&modulus.wrapping_sub(&one)), GroupElement::ONE);
+
+        // The generators are hashes raised to the power of the cofactor 2k.
+        let cofactor = k.shl_vartime(1);
+        for (label, generator) in [(&b"g"[..], G), (&b"h"[..], H)].iter() {
+            let x = shake(&[&b"prio vss generator "[..], label].concat(), ELEMENT_LEN);
+            let x = GroupElement::new(&x.wrapping_rem(&modulus));
+            assert_eq!(x.pow(&cofactor), *generator);
+            assert_ne!(*generator, GroupElement::ONE);
+            assert_eq!(generator.pow(&p), GroupElement::ONE);
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let inp: Vec<Field255> = rand(3).unwrap();
+        let (shares, commitments) = vss_split(&inp, 2, 3).unwrap();
+        assert_eq!(shares.len(), 3);
+        for share in shares.iter() {
+            assert!(share.verify(&commitments));
+        }
+
+        let shamir_shares: Vec<_> = shares.iter().map(|s| s.share.clone()).collect();
+        assert_eq!(shamir_reconstruct(&shamir_shares[1..]).unwrap(), inp);
+    }
+
+    #[test]
+    fn test_verify_inconsistent() {
+        let inp: Vec<Field255> = rand(2).unwrap();
+        let (shares, commitments) = vss_split(&inp, 2, 3).unwrap();
+
+        // A tampered value or blinding value.
+        let mut share = shares[0].clone();
+        share.share.data[1] += Field255::one();
+        assert!(!share.verify(&commitments));
+        let mut share = shares[0].clone();
+        share.blind[0] += Field255::one();
+        assert!(!share.verify(&commitments));
+
+        // A share checked at the wrong index.
+        let mut share = shares[0].clone();
+        share.share.index = 2;
+        assert!(!share.verify(&commitments));
+        share.share.index = 0;
+        assert!(!share.verify(&commitments));
+
+        // A share of a different input.
+        let (other, _) = vss_split(&inp, 2, 3).unwrap();
+        assert!(!other[0].verify(&commitments));
+
+        // A share of the wrong length.
+        let mut share = shares[0].clone();
+        share.share.data.pop();
+        share.blind.pop();
+        assert!(!share.verify(&commitments));
+    }
+
+    #[test]
+    fn test_invalid_threshold() {
+        let inp: Vec<Field255> = rand(1).unwrap();
+        assert_matches!(vss_split(&inp, 0, 3), Err(ShamirError::InvalidThreshold));
+        assert_matches!(vss_split(&inp, 4, 3), Err(ShamirError::InvalidThreshold));
+    }
+
+    #[test]
+    fn test_encode() {
+        let inp: Vec<Field255> = rand(2).unwrap();
+        let (shares, commitments) = vss_split(&inp, 3, 3).unwrap();
+        let bytes = commitments.get_encoded();
+        assert_eq!(bytes.len(), 3 * 2 * ELEMENT_LEN);
+
+        let decoded = VssCommitments::get_decoded_with_param(&(3, 2), &bytes).unwrap();
+        assert_eq!(decoded, commitments);
+        assert!(shares[2].verify(&decoded));
+
+        assert_matches!(
+            VssCommitments::get_decoded_with_param(&(3, 2), &bytes[..bytes.len() - 1]),
+            Err(CodecError::Io(_))
+        );
+        assert_matches!(
+            VssCommitments::get_decoded_with_param(&(0, 2), &[]),
+            Err(CodecError::UnexpectedValue)
+        );
+        let mut bytes = bytes;
+        bytes[..ELEMENT_LEN].copy_from_slice(&VssModulus::MODULUS.to_be_bytes());
+        assert_matches!(
+            VssCommitments::get_decoded_with_param(&(3, 2), &bytes),
+            Err(CodecError::UnexpectedValue)
+        );
+    }
+}
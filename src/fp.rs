@@ -1,22 +1,44 @@
 // SPDX-License-Identifier: MPL-2.0
 
-//! Finite field arithmetic for any field GF(p) for which p < 2^126.
+//! Finite field arithmetic for any field GF(p) for which p < 2^128, as well as for a handful of
+//! fields with special moduli.
+//!
+//! Most applications should use the field element types in [`crate::field`]. This module exposes
+//! the underlying modular arithmetic on raw integer representations for applications that need to
+//! operate on them directly, e.g., to implement custom batching or to offload arithmetic to a
+//! GPU. [`FieldParameters`], [`FieldParameters32`], and [`FieldParameters64`] implement
+//! Montgomery arithmetic for moduli of up to 128, 32, and 64 bits respectively; the parameters of
+//! the fields in [`crate::field`] are provided as constants.
+//!
+//! Elements are mapped to their internal representation with `elem()` and back with
+//! `from_elem()`. The arithmetic methods expect and return internal representations in `[0, p)`;
+//! their output is unspecified for other inputs.
+//!
+//! ```
+//! use prio::fp::FP64;
+//!
+//! let x = FP64.elem(3);
+//! let y = FP64.elem(5);
+//! assert_eq!(FP64.from_elem(FP64.mul(x, y)), 15);
+//! assert_eq!(FP64.from_elem(FP64.sub(x, y)), FP64.p - 2);
+//! assert_eq!(FP64.from_elem(FP64.mul(x, FP64.inv(x))), 1);
+//! assert_eq!(FP64.from_elem(FP64.pow(x, 4)), 81);
+//! ```
 
 #[cfg(test)]
 use rand::{prelude::*, Rng};
+use subtle::{Choice, ConditionallySelectable};
 
 /// For each set of field parameters we pre-compute the 1st, 2nd, 4th, ..., 2^20-th principal roots
-/// of unity. The largest of these is used to run the FFT algorithm on an input of size 2^20. This
-/// is the largest input size we would ever need for the cryptographic applications in this crate.
-pub(crate) const MAX_ROOTS: usize = 20;
+/// of unity, which suffice to run the FFT algorithm on inputs of size up to 2^20. Roots of larger
+/// order, up to the order of the generator, are computed on demand by `FieldElement::root()`.
+pub const MAX_ROOTS: usize = 20;
 
-/// This structure represents the parameters of a finite field GF(p) for which p < 2^126.
+/// This structure represents the parameters of a finite field GF(p) for which p < 2^128.
 #[derive(Debug, PartialEq, Eq)]
-pub(crate) struct FieldParameters {
+pub struct FieldParameters {
     /// The prime modulus `p`.
     pub p: u128,
-    /// `p * 2`.
-    pub p2: u128,
     /// `mu = -p^(-1) mod 2^64`.
     pub mu: u64,
     /// `r2 = (2^128)^2 mod p`.
@@ -34,18 +56,111 @@ pub(crate) struct FieldParameters {
 }
 
 impl FieldParameters {
-    /// Addition.
-    pub fn add(&self, x: u128, y: u128) -> u128 {
-        let (z, carry) = x.wrapping_add(y).overflowing_sub(self.p2);
-        let m = 0u128.wrapping_sub(carry as u128);
-        z.wrapping_add(m & self.p2)
+    /// Computes the parameters of `GF(p)` from the prime modulus `p` and a generator `g` of the
+    /// multiplicative subgroup of order `2^num_roots`. This is a `const fn`, so the parameters of
+    /// each field in this module are computed, and validated, at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is even, if `2^num_roots` does not divide `p - 1`, or if `g` does not have
+    /// order `2^num_roots`. Whether `p` is prime is not checked; use [`Self::check`] for that.
+    pub const fn new(p: u128, g: u128, num_roots: usize) -> Self {
+        assert!(p > 2 && p % 2 == 1, "p must be an odd prime");
+        assert!(g < p, "g must be smaller than p");
+        assert!(
+            num_roots > 0 && num_roots < 128 && (p - 1) & ((1 << num_roots) - 1) == 0,
+            "2^num_roots must divide p - 1"
+        );
+
+        let mut fp = Self {
+            p,
+            mu: neg_inv_mod_2_64(p as u64),
+            r2: 0,
+            g: 0,
+            num_roots,
+            bit_mask: u128::MAX >> p.leading_zeros(),
+            roots: [0; MAX_ROOTS + 1],
+        };
+
+        // r2 = 2^256 (mod p), computed by doubling.
+        let mut r2 = 1;
+        let mut i = 0;
+        while i < 256 {
+            r2 = fp.add(r2, r2);
+            i += 1;
+        }
+        fp.r2 = r2;
+
+        // roots[l] = g^(2^(num_roots - l)) for each l <= MAX_ROOTS.
+        fp.g = fp.elem(g);
+        let mut x = fp.g;
+        let mut l = num_roots;
+        loop {
+            if l <= MAX_ROOTS {
+                fp.roots[l] = x;
+            }
+            if l == 0 {
+                break;
+            }
+            x = fp.mul(x, x);
+            l -= 1;
+        }
+
+        let one = fp.elem(1);
+        assert!(fp.roots[0] == one, "g^(2^num_roots) must be 1");
+        assert!(fp.roots[1] == fp.neg(one), "g must have order 2^num_roots");
+        fp
+    }
+
+    /// Checks that the parameters are consistent, i.e., that `p` is prime (with overwhelming
+    /// probability) and that the remaining parameters are equal to those computed by
+    /// [`Self::new`]. Parameters of new fields should be checked in a unit test.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are inconsistent.
+    pub fn check(&self) {
+        assert!(
+            is_probable_prime(self.p, |x, e| self.from_elem(self.pow(self.elem(x), e))),
+            "p is not prime"
+        );
+        assert_eq!(
+            self,
+            &Self::new(self.p, self.from_elem(self.g), self.num_roots),
+            "parameters do not match those computed from p, g, and num_roots"
+        );
+    }
+
+    /// Addition. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn add(&self, x: u128, y: u128) -> u128 {
+        //   0,x
+        // + 0,y
+        // =====
+        //   c,z
+        let (z, carry) = x.overflowing_add(y);
+        //     c, z
+        // -   0, p
+        // ========
+        // b1,s1,s0
+        let (s0, b0) = z.overflowing_sub(self.p);
+        let (_s1, b1) = (carry as u128).overflowing_sub(b0 as u128);
+        // if b1 == 1: return z
+        // else:       return s0
+        let m = 0u128.wrapping_sub(b1 as u128);
+        (z & m) | (s0 & !m)
     }
 
-    /// Subtraction.
-    pub fn sub(&self, x: u128, y: u128) -> u128 {
-        let (z, carry) = x.overflowing_sub(y);
-        let m = 0u128.wrapping_sub(carry as u128);
-        z.wrapping_add(m & self.p2)
+    /// Subtraction. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn sub(&self, x: u128, y: u128) -> u128 {
+        //     0, x
+        // -   0, y
+        // ========
+        //    b0,z0
+        let (z0, b0) = x.overflowing_sub(y);
+        // if b0 == 1: return z0 + p
+        // else:       return z0
+        let m = 0u128.wrapping_sub(b0 as u128);
+        z0.wrapping_add(m & self.p)
     }
 
     /// Multiplication of field elements in the Montgomery domain. This uses the REDC algorithm
@@ -54,7 +169,7 @@ impl FieldParameters {
     ///
     /// Example usage:
     /// assert_eq!(fp.from_elem(fp.mul(fp.elem(23), fp.elem(2))), 46);
-    pub fn mul(&self, x: u128, y: u128) -> u128 {
+    pub const fn mul(&self, x: u128, y: u128) -> u128 {
         let x = [lo64(x), hi64(x)];
         let y = [lo64(y), hi64(y)];
         let p = [lo64(self.p), hi64(self.p)];
@@ -152,31 +267,53 @@ impl FieldParameters {
         cc = hi64(result);
         result = zz[3] + hi + cc;
         zz[3] = lo64(result);
+        cc = hi64(result);
+
+        // z = (z3,z2)
+        let prod = zz[2] | (zz[3] << 64);
+
+        // Final subtraction
+        // If z >= p, then z = z - p
 
-        zz[2] | (zz[3] << 64)
+        //     0, z
+        // -   0, p
+        // ========
+        // b1,s1,s0
+        let (s0, b0) = prod.overflowing_sub(self.p);
+        let (_s1, b1) = cc.overflowing_sub(b0 as u128);
+        // if b1 == 1: return z
+        // else:       return s0
+        let mask = 0u128.wrapping_sub(b1 as u128);
+        (prod & mask) | (s0 & !mask)
     }
 
-    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. Note that the
-    /// runtime of this algorithm is linear in the bit length of `exp`.
+    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the values of `x` or `exp`.
     pub fn pow(&self, x: u128, exp: u128) -> u128 {
+        self.pow_bits(x, exp, 128)
+    }
+
+    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulus. The runtime of this
+    /// algorithm is linear in the bit length of `p` and does not depend on the value of `x`.
+    pub fn inv(&self, x: u128) -> u128 {
+        self.pow_bits(x, self.p - 2, 128 - self.p.leading_zeros())
+    }
+
+    // Computes `x^exp (mod p)`, where `exp < 2^bits`, using the square-and-multiply ladder. The
+    // multiplication is carried out for every bit of the exponent, and its result is selected in
+    // constant time.
+    fn pow_bits(&self, x: u128, exp: u128, bits: u32) -> u128 {
         let mut t = self.elem(1);
-        for i in (0..128 - exp.leading_zeros()).rev() {
+        for i in (0..bits).rev() {
             t = self.mul(t, t);
-            if (exp >> i) & 1 != 0 {
-                t = self.mul(t, x);
-            }
+            let u = self.mul(t, x);
+            t = u128::conditional_select(&t, &u, Choice::from(((exp >> i) & 1) as u8));
         }
         t
     }
 
-    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulu. Note that the runtime of
-    /// this algorithm is linear in the bit length of `p`.
-    pub fn inv(&self, x: u128) -> u128 {
-        self.pow(x, self.p - 2)
-    }
-
     /// Negation, i.e., `-x (mod p)` where `p` is the modulus.
-    pub fn neg(&self, x: u128) -> u128 {
+    pub const fn neg(&self, x: u128) -> u128 {
         self.sub(0, x)
     }
 
@@ -187,13 +324,13 @@ impl FieldParameters {
     /// let integer = 1; // Standard integer representation
     /// let elem = fp.elem(integer); // Internal representation in the Montgomery domain
     /// assert_eq!(elem, 2564090464);
-    pub fn elem(&self, x: u128) -> u128 {
-        modp(self.mul(x, self.r2), self.p)
+    pub const fn elem(&self, x: u128) -> u128 {
+        self.mul(x, self.r2)
     }
 
     /// Returns a random field element mapped.
     #[cfg(test)]
-    pub fn rand_elem<R: Rng + ?Sized>(&self, rng: &mut R) -> u128 {
+    pub(crate) fn rand_elem<R: Rng + ?Sized>(&self, rng: &mut R) -> u128 {
         let uniform = rand::distributions::Uniform::from(0..self.p);
         self.elem(uniform.sample(rng))
     }
@@ -204,25 +341,27 @@ impl FieldParameters {
     /// let elem = 2564090464; // Internal representation in the Montgomery domain
     /// let integer = fp.from_elem(elem); // Standard integer representation
     /// assert_eq!(integer, 1);
-    pub fn from_elem(&self, x: u128) -> u128 {
-        modp(self.mul(x, 1), self.p)
+    pub const fn from_elem(&self, x: u128) -> u128 {
+        self.mul(x, 1)
+    }
+
+    /// Reduces a sum of field elements in their internal representation, i.e., maps `x` to the
+    /// field element whose internal representation is equal to `x (mod p)`.
+    pub fn reduce_wide(&self, x: u128) -> u128 {
+        // Since x < 2^128 and 1 < p, the first product is a valid input to REDC. It outputs
+        // x * R^-1 (mod p), which the second product maps back to x (mod p).
+        self.mul(self.mul(x, 1), self.r2)
     }
 
     #[cfg(test)]
-    pub fn check(&self, p: u128, g: u128, order: u128) {
+    pub(crate) fn check_against(&self, p: u128, g: u128, order: u128) {
         use modinverse::modinverse;
+
+        self.check();
         use num_bigint::{BigInt, ToBigInt};
         use std::cmp::max;
 
-        if let Some(x) = p.checked_next_power_of_two() {
-            if x > 1 << 126 {
-                panic!("p >= 2^126");
-            }
-        } else {
-            panic!("p >= 2^126");
-        }
         assert_eq!(self.p, p, "p mismatch");
-        assert_eq!(self.p2, p << 1, "p2 mismatch");
 
         let mu = match modinverse((-(p as i128)).rem_euclid(1 << 64), 1 << 64) {
             Some(mu) => mu as u64,
@@ -260,253 +399,1283 @@ impl FieldParameters {
         assert_eq!(&self.roots, &roots[..MAX_ROOTS + 1], "roots mismatch");
         assert_eq!(self.from_elem(self.roots[0]), 1, "first root is not one");
 
-        let bit_mask: u128 = p.next_power_of_two() - 1;
+        let bit_mask: u128 = u128::MAX >> p.leading_zeros();
         assert_eq!(self.bit_mask, bit_mask, "bit_mask mismatch");
     }
 }
 
-fn lo64(x: u128) -> u128 {
+// Returns `-p^-1 (mod 2^64)` for odd `p`, computed with Newton's iteration. Since `p * p = 1
+// (mod 8)`, the initial guess is correct in the lowest 3 bits, and each step doubles the number
+// of correct bits.
+const fn neg_inv_mod_2_64(p: u64) -> u64 {
+    let mut inv = p;
+    let mut i = 0;
+    while i < 5 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+// Runs the Miller-Rabin primality test on `p`, an odd integer larger than 2, using the first 20
+// primes as bases. `pow(x, e)` must return `x^e (mod p)`.
+fn is_probable_prime(p: u128, pow: impl Fn(u128, u128) -> u128) -> bool {
+    const BASES: [u128; 20] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+    ];
+
+    // Write p - 1 = d * 2^s for odd d.
+    let s = (p - 1).trailing_zeros();
+    let d = (p - 1) >> s;
+    'outer: for &a in BASES.iter() {
+        if a % p == 0 {
+            continue;
+        }
+        let mut x = pow(a, d);
+        if x == 1 || x == p - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = pow(x, 2);
+            if x == p - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+const fn lo64(x: u128) -> u128 {
     x & ((1 << 64) - 1)
 }
 
-fn hi64(x: u128) -> u128 {
+const fn hi64(x: u128) -> u128 {
     x >> 64
 }
 
-fn modp(x: u128, p: u128) -> u128 {
-    let (z, carry) = x.overflowing_sub(p);
-    let m = 0u128.wrapping_sub(carry as u128);
-    z.wrapping_add(m & p)
+/// The parameters of [`crate::field::Field80`].
+pub const FP80: FieldParameters = FieldParameters::new(
+    779190469673491460259841, // 80-bit prime
+    41782115852031095118226,  // generator
+    72,                       // log2 of the order of the generator
+);
+
+/// The parameters of [`crate::field::Field126`].
+pub const FP126: FieldParameters = FieldParameters::new(
+    74769074762901517850839147140769382401, // 126-bit prime
+    43421413544015439978138831414974882540, // generator
+    118,                                    // log2 of the order of the generator
+);
+
+/// The parameters of [`crate::field::Field128`].
+pub const FP128: FieldParameters = FieldParameters::new(
+    340282366920938462946865773367900766209, // 128-bit prime
+    145091266659756586618791329697897684742, // generator
+    66,                                      // log2 of the order of the generator
+);
+
+/// This structure represents the parameters of a finite field GF(p) for which p < 2^32. It is
+/// the same as `FieldParameters64`, except that elements are represented by a `u32` and are mapped
+/// to the Montgomery domain with `R = 2^32`. Multiplication only requires a 32x32->64 bit
+/// widening multiplication, which is fast on targets without native 128-bit arithmetic, such as
+/// WebAssembly and 32-bit embedded CPUs.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FieldParameters32 {
+    /// The prime modulus `p`.
+    pub p: u32,
+    /// `mu = -p^(-1) mod 2^32`.
+    pub mu: u32,
+    /// `r2 = (2^32)^2 mod p`.
+    pub r2: u32,
+    /// The `2^num_roots`-th -principal root of unity. This element is used to generate the
+    /// elements of `roots`.
+    pub g: u32,
+    /// The number of principal roots of unity in `roots`.
+    pub num_roots: usize,
+    /// Equal to `2^b - 1`, where `b` is the length of `p` in bits.
+    pub bit_mask: u32,
+    /// `roots[l]` is the `2^l`-th principal root of unity, i.e., `roots[l]` has order `2^l` in the
+    /// multiplicative group. `root[l]` is equal to one by definition.
+    pub roots: [u32; MAX_ROOTS + 1],
 }
 
-pub(crate) const FP32: FieldParameters = FieldParameters {
-    p: 4293918721, // 32-bit prime
-    p2: 8587837442,
-    mu: 17302828673139736575,
-    r2: 1676699750,
-    g: 1074114499,
-    num_roots: 20,
-    bit_mask: 4294967295,
-    roots: [
-        2564090464, 1729828257, 306605458, 2294308040, 1648889905, 57098624, 2788941825,
-        2779858277, 368200145, 2760217336, 594450960, 4255832533, 1372848488, 721329415,
-        3873251478, 1134002069, 7138597, 2004587313, 2989350643, 725214187, 1074114499,
-    ],
-};
+impl FieldParameters32 {
+    /// Computes the parameters of `GF(p)` from the prime modulus `p` and a generator `g` of the
+    /// multiplicative subgroup of order `2^num_roots`. See [`FieldParameters::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is even, if `2^num_roots` does not divide `p - 1`, or if `g` does not have
+    /// order `2^num_roots`. Whether `p` is prime is not checked; use [`Self::check`] for that.
+    pub const fn new(p: u32, g: u32, num_roots: usize) -> Self {
+        assert!(p > 2 && p % 2 == 1, "p must be an odd prime");
+        assert!(g < p, "g must be smaller than p");
+        assert!(
+            num_roots > 0 && num_roots < 32 && (p - 1) & ((1 << num_roots) - 1) == 0,
+            "2^num_roots must divide p - 1"
+        );
 
-pub(crate) const FP64: FieldParameters = FieldParameters {
-    p: 15564440312192434177, // 64-bit prime
-    p2: 31128880624384868354,
-    mu: 15564440312192434175,
-    r2: 13031533328350459868,
-    g: 8693478717884812021,
-    num_roots: 59,
-    bit_mask: 18446744073709551615,
-    roots: [
-        3501465310287461188,
-        12062975001904972989,
-        14847933823983913979,
-        5743743733744043357,
-        12036183376424650304,
-        1310071208805268988,
-        351359342873885390,
-        760642505652925971,
-        8075457983432319221,
-        14554120515039960006,
-        9277695709938157757,
-        5146332056710123439,
-        9547487945110664452,
-        1379816102304800478,
-        8461341165309158767,
-        12152693588256515089,
-        9516424165972384563,
-        8278889272850348764,
-        6847784946159064188,
-        875721217475244711,
-        3028669228647031529,
-    ],
-};
+        let mut fp = Self {
+            p,
+            // The inverse modulo 2^64 is also the inverse modulo 2^32.
+            mu: neg_inv_mod_2_64(p as u64) as u32,
+            r2: 0,
+            g: 0,
+            num_roots,
+            bit_mask: u32::MAX >> p.leading_zeros(),
+            roots: [0; MAX_ROOTS + 1],
+        };
 
-pub(crate) const FP80: FieldParameters = FieldParameters {
-    p: 779190469673491460259841, // 80-bit prime
-    p2: 1558380939346982920519682,
-    mu: 18446744073709551615,
-    r2: 699883506621195336351723,
-    g: 470015708362303528848629,
-    num_roots: 72,
-    bit_mask: 1208925819614629174706175,
-    roots: [
-        146393360532246310485619,
-        632797109141245149774222,
-        671768715528862959481,
-        155287852188866912681838,
-        84398650169430234366422,
-        591732619446824370107997,
-        369489067863767193117628,
-        65351307276236357745139,
-        250263845222966534834802,
-        615370028124972287024172,
-        428271082931219526829234,
-        82144483146855494501530,
-        655790508505248218964487,
-        715547187733913654852114,
-        29653674159319497967645,
-        208078234303463777930443,
-        495449125070884366403280,
-        409220521346165172951210,
-        134217175002192449913815,
-        87718316256013518265593,
-        261278801525790549618040,
-    ],
-};
+        // r2 = 2^64 (mod p), computed by doubling.
+        let mut r2 = 1;
+        let mut i = 0;
+        while i < 64 {
+            r2 = fp.add(r2, r2);
+            i += 1;
+        }
+        fp.r2 = r2;
 
-pub(crate) const FP126: FieldParameters = FieldParameters {
-    p: 74769074762901517850839147140769382401, // 126-bit prime
-    p2: 149538149525803035701678294281538764802,
-    mu: 18446744073709551615,
-    r2: 27801541991839173768379182336352451464,
-    g: 63245316532470582112420298384754157617,
-    num_roots: 118,
-    bit_mask: 85070591730234615865843651857942052863,
-    roots: [
-        41206067869332392060018018868690681852,
-        33563006893569125790821128272078700549,
-        9969209968386869007425498928188874206,
-        26245577744033816872542400585149646017,
-        53536320213034809573447803273264211942,
-        27613962195776955012920796583378240442,
-        32365734403831264958530930421153577004,
-        13579354626561224539372784961933801433,
-        57316758837288076943811104544124917759,
-        70913423672054213072910590891105064074,
-        71265034959502540558500186666669444000,
-        34207722676470700263211551887273866594,
-        37340170148681921863826823402458410577,
-        35009585531414332540073382665488435215,
-        70329412074928482115163094157328536788,
-        39119429759852994810554872198104013087,
-        47573549675073661838420354629772140200,
-        77849817677037388106638164185970185092,
-        37853717993704464400736177978677308170,
-        83509620839139853788963077680031940984,
-        64573608437864873942981348294630891347,
-    ],
-};
+        // roots[l] = g^(2^(num_roots - l)) for each l <= MAX_ROOTS.
+        fp.g = fp.elem(g);
+        let mut x = fp.g;
+        let mut l = num_roots;
+        loop {
+            if l <= MAX_ROOTS {
+                fp.roots[l] = x;
+            }
+            if l == 0 {
+                break;
+            }
+            x = fp.mul(x, x);
+            l -= 1;
+        }
 
-// Compute the ceiling of the base-2 logarithm of `x`.
-pub(crate) fn log2(x: u128) -> u128 {
-    let y = (127 - x.leading_zeros()) as u128;
-    y + ((x > 1 << y) as u128)
-}
+        let one = fp.elem(1);
+        assert!(fp.roots[0] == one, "g^(2^num_roots) must be 1");
+        assert!(fp.roots[1] == fp.neg(one), "g must have order 2^num_roots");
+        fp
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use modinverse::modinverse;
-    use num_bigint::ToBigInt;
+    /// Checks that the parameters are consistent. See [`FieldParameters::check`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are inconsistent.
+    pub fn check(&self) {
+        assert!(
+            is_probable_prime(self.p as u128, |x, e| {
+                self.from_elem(self.pow(self.elem(x as u32), e as u32)) as u128
+            }),
+            "p is not prime"
+        );
+        assert_eq!(
+            self,
+            &Self::new(self.p, self.from_elem(self.g), self.num_roots),
+            "parameters do not match those computed from p, g, and num_roots"
+        );
+    }
 
-    #[test]
-    fn test_log2() {
-        assert_eq!(log2(1), 0);
-        assert_eq!(log2(2), 1);
-        assert_eq!(log2(3), 2);
-        assert_eq!(log2(4), 2);
-        assert_eq!(log2(15), 4);
-        assert_eq!(log2(16), 4);
-        assert_eq!(log2(30), 5);
-        assert_eq!(log2(32), 5);
-        assert_eq!(log2(1 << 127), 127);
-        assert_eq!(log2((1 << 127) + 13), 128);
+    /// Addition. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn add(&self, x: u32, y: u32) -> u32 {
+        let (z, carry) = x.overflowing_add(y);
+        let (s0, b0) = z.overflowing_sub(self.p);
+        let (_s1, b1) = (carry as u32).overflowing_sub(b0 as u32);
+        // if b1 == 1: return z
+        // else:       return s0
+        let m = 0u32.wrapping_sub(b1 as u32);
+        (z & m) | (s0 & !m)
     }
 
-    struct TestFieldParametersData {
-        fp: FieldParameters,  // The paramters being tested
-        expected_p: u128,     // Expected fp.p
-        expected_g: u128,     // Expected fp.from_elem(fp.g)
-        expected_order: u128, // Expect fp.from_elem(fp.pow(fp.g, expected_order)) == 1
+    /// Subtraction. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn sub(&self, x: u32, y: u32) -> u32 {
+        let (z0, b0) = x.overflowing_sub(y);
+        let m = 0u32.wrapping_sub(b0 as u32);
+        z0.wrapping_add(m & self.p)
     }
 
-    #[test]
-    fn test_fp() {
-        let test_fps = vec![
-            TestFieldParametersData {
-                fp: FP32,
-                expected_p: 4293918721,
-                expected_g: 3925978153,
-                expected_order: 1 << 20,
-            },
-            TestFieldParametersData {
-                fp: FP64,
-                expected_p: 15564440312192434177,
-                expected_g: 7450580596923828125,
-                expected_order: 1 << 59,
-            },
-            TestFieldParametersData {
-                fp: FP80,
-                expected_p: 779190469673491460259841,
-                expected_g: 41782115852031095118226,
-                expected_order: 1 << 72,
-            },
-            TestFieldParametersData {
-                fp: FP126,
-                expected_p: 74769074762901517850839147140769382401,
-                expected_g: 43421413544015439978138831414974882540,
-                expected_order: 1 << 118,
-            },
-        ];
+    /// Multiplication of field elements in the Montgomery domain. This uses the REDC algorithm
+    /// described
+    /// [here](https://www.ams.org/journals/mcom/1985-44-170/S0025-5718-1985-0777282-X/S0025-5718-1985-0777282-X.pdf).
+    /// The result will be in [0, p), so long as both x and y are as well.
+    pub const fn mul(&self, x: u32, y: u32) -> u32 {
+        let zz = (x as u64) * (y as u64);
 
-        for t in test_fps.into_iter() {
-            //  Check that the field parameters have been constructed properly.
-            t.fp.check(t.expected_p, t.expected_g, t.expected_order);
+        // Reduction
+        let w = self.mu.wrapping_mul(zz as u32);
+        let (t, carry) = zz.overflowing_add((w as u64) * (self.p as u64));
+        // The lower 32 bits of t are zero, so t / 2^32 is (carry, z).
+        let z = (t >> 32) as u32;
 
-            // Check that the generator has the correct order.
-            assert_eq!(t.fp.from_elem(t.fp.pow(t.fp.g, t.expected_order)), 1);
+        // Final subtraction
+        // If (carry, z) >= p, then z = z - p
+        let (s0, b0) = z.overflowing_sub(self.p);
+        let (_s1, b1) = (carry as u32).overflowing_sub(b0 as u32);
+        // if b1 == 1: return z
+        // else:       return s0
+        let m = 0u32.wrapping_sub(b1 as u32);
+        (z & m) | (s0 & !m)
+    }
 
-            // Test arithmetic using the field parameters.
-            arithmetic_test(&t.fp);
+    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the values of `x` or `exp`.
+    pub fn pow(&self, x: u32, exp: u32) -> u32 {
+        self.pow_bits(x, exp, 32)
+    }
+
+    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulus. The runtime of this
+    /// algorithm is linear in the bit length of `p` and does not depend on the value of `x`.
+    pub fn inv(&self, x: u32) -> u32 {
+        self.pow_bits(x, self.p - 2, 32 - self.p.leading_zeros())
+    }
+
+    // Computes `x^exp (mod p)`, where `exp < 2^bits`, in constant time.
+    fn pow_bits(&self, x: u32, exp: u32, bits: u32) -> u32 {
+        let mut t = self.elem(1);
+        for i in (0..bits).rev() {
+            t = self.mul(t, t);
+            let u = self.mul(t, x);
+            t = u32::conditional_select(&t, &u, Choice::from(((exp >> i) & 1) as u8));
         }
+        t
     }
 
-    fn arithmetic_test(fp: &FieldParameters) {
-        let mut rng = rand::thread_rng();
-        let big_p = &fp.p.to_bigint().unwrap();
+    /// Negation, i.e., `-x (mod p)` where `p` is the modulus.
+    pub const fn neg(&self, x: u32) -> u32 {
+        self.sub(0, x)
+    }
 
-        for _ in 0..100 {
-            let x = fp.rand_elem(&mut rng);
-            let y = fp.rand_elem(&mut rng);
-            let big_x = &fp.from_elem(x).to_bigint().unwrap();
-            let big_y = &fp.from_elem(y).to_bigint().unwrap();
+    /// Maps an integer to its internal representation in the Montgomery domain.
+    pub const fn elem(&self, x: u32) -> u32 {
+        self.mul(x, self.r2)
+    }
 
-            // Test addition.
-            let got = fp.add(x, y);
-            let want = (big_x + big_y) % big_p;
-            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+    /// Returns a random field element mapped.
+    #[cfg(test)]
+    pub(crate) fn rand_elem<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
+        let uniform = rand::distributions::Uniform::from(0..self.p);
+        self.elem(uniform.sample(rng))
+    }
 
-            // Test subtraction.
-            let got = fp.sub(x, y);
-            let want = if big_x >= big_y {
-                big_x - big_y
-            } else {
-                big_p - big_y + big_x
-            };
-            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+    /// Maps a field element to its representation as an integer.
+    #[allow(clippy::wrong_self_convention)]
+    pub const fn from_elem(&self, x: u32) -> u32 {
+        self.mul(x, 1)
+    }
 
-            // Test multiplication.
-            let got = fp.mul(x, y);
-            let want = (big_x * big_y) % big_p;
-            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+    /// Reduces a sum of field elements in their internal representation, i.e., maps `x` to the
+    /// field element whose internal representation is equal to `x (mod p)`.
+    pub fn reduce_wide(&self, x: u128) -> u32 {
+        // Write x = hi * 2^64 + lo, and then reduce each 64-bit half as in
+        // `FieldParameters64::reduce_wide()`. Multiplying by r2 twice maps hi to hi * R^2 (mod p).
+        let hi = self.reduce_u64((x >> 64) as u64);
+        let lo = self.reduce_u64(x as u64);
+        self.add(self.mul(self.mul(hi, self.r2), self.r2), lo)
+    }
 
-            // Test inversion.
-            let got = fp.inv(x);
-            let want = modinverse(fp.from_elem(x) as i128, fp.p as i128).unwrap();
-            assert_eq!(fp.from_elem(got) as i128, want);
-            assert_eq!(fp.from_elem(fp.mul(got, x)), 1);
+    // Returns `x (mod p)`. Write x = hi * 2^32 + lo. Since hi, lo < R, each product below is a
+    // valid input to REDC. The first outputs hi * R (mod p); the second and third map lo to
+    // lo (mod p).
+    fn reduce_u64(&self, x: u64) -> u32 {
+        let hi = self.mul((x >> 32) as u32, self.r2);
+        let lo = self.mul(self.mul(x as u32, 1), self.r2);
+        self.add(hi, lo)
+    }
 
-            // Test negation.
-            let got = fp.neg(x);
-            let want = (-(fp.from_elem(x) as i128)).rem_euclid(fp.p as i128);
-            assert_eq!(fp.from_elem(got) as i128, want);
-            assert_eq!(fp.from_elem(fp.add(got, x)), 0);
+    #[cfg(test)]
+    pub(crate) fn check_against(&self, p: u32, g: u32, order: u32) {
+        use modinverse::modinverse;
+
+        self.check();
+
+        assert_eq!(self.p, p, "p mismatch");
+
+        let mu = match modinverse((-(p as i64)).rem_euclid(1 << 32), 1 << 32) {
+            Some(mu) => mu as u32,
+            None => panic!("inverse of -p (mod 2^32) is undefined"),
+        };
+        assert_eq!(self.mu, mu, "mu mismatch");
+
+        let r = (1u64 << 32) % (p as u64);
+        let r2 = (r * r) % (p as u64);
+        assert_eq!(self.r2 as u64, r2, "r2 mismatch");
+
+        assert_eq!(self.g, self.elem(g), "g mismatch");
+        assert_eq!(
+            self.from_elem(self.pow(self.g, order)),
+            1,
+            "g order incorrect"
+        );
+
+        let num_roots = log2(order as u128) as usize;
+        assert_eq!(order, 1 << num_roots, "order not a power of 2");
+        assert_eq!(self.num_roots, num_roots, "num_roots mismatch");
+
+        let mut roots = vec![0; std::cmp::max(num_roots, MAX_ROOTS) + 1];
+        roots[num_roots] = self.elem(g);
+        for i in (0..num_roots).rev() {
+            roots[i] = self.mul(roots[i + 1], roots[i + 1]);
+        }
+        assert_eq!(&self.roots, &roots[..MAX_ROOTS + 1], "roots mismatch");
+        assert_eq!(self.from_elem(self.roots[0]), 1, "first root is not one");
+
+        let bit_mask: u32 = u32::MAX >> p.leading_zeros();
+        assert_eq!(self.bit_mask, bit_mask, "bit_mask mismatch");
+    }
+}
+
+/// The parameters of [`crate::field::Field32`].
+pub const FP32: FieldParameters32 = FieldParameters32::new(
+    4293918721, // 32-bit prime
+    3925978153, // generator
+    20,         // log2 of the order of the generator
+);
+
+/// This structure represents the parameters of a finite field GF(p) for which p < 2^64. It is
+/// the same as `FieldParameters`, except that elements are represented by a `u64` and are mapped
+/// to the Montgomery domain with `R = 2^64`. This allows multiplication to be carried out with a
+/// single 64x64->128 bit widening multiplication, rather than the four required for `u128`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FieldParameters64 {
+    /// The prime modulus `p`.
+    pub p: u64,
+    /// `mu = -p^(-1) mod 2^64`.
+    pub mu: u64,
+    /// `r2 = (2^64)^2 mod p`.
+    pub r2: u64,
+    /// The `2^num_roots`-th -principal root of unity. This element is used to generate the
+    /// elements of `roots`.
+    pub g: u64,
+    /// The number of principal roots of unity in `roots`.
+    pub num_roots: usize,
+    /// Equal to `2^b - 1`, where `b` is the length of `p` in bits.
+    pub bit_mask: u64,
+    /// `roots[l]` is the `2^l`-th principal root of unity, i.e., `roots[l]` has order `2^l` in the
+    /// multiplicative group. `root[l]` is equal to one by definition.
+    pub roots: [u64; MAX_ROOTS + 1],
+}
+
+impl FieldParameters64 {
+    /// Computes the parameters of `GF(p)` from the prime modulus `p` and a generator `g` of the
+    /// multiplicative subgroup of order `2^num_roots`. See [`FieldParameters::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is even, if `2^num_roots` does not divide `p - 1`, or if `g` does not have
+    /// order `2^num_roots`. Whether `p` is prime is not checked; use [`Self::check`] for that.
+    pub const fn new(p: u64, g: u64, num_roots: usize) -> Self {
+        assert!(p > 2 && p % 2 == 1, "p must be an odd prime");
+        assert!(g < p, "g must be smaller than p");
+        assert!(
+            num_roots > 0 && num_roots < 64 && (p - 1) & ((1 << num_roots) - 1) == 0,
+            "2^num_roots must divide p - 1"
+        );
+
+        let mut fp = Self {
+            p,
+            mu: neg_inv_mod_2_64(p),
+            r2: 0,
+            g: 0,
+            num_roots,
+            bit_mask: u64::MAX >> p.leading_zeros(),
+            roots: [0; MAX_ROOTS + 1],
+        };
+
+        // r2 = 2^128 (mod p), computed by doubling.
+        let mut r2 = 1;
+        let mut i = 0;
+        while i < 128 {
+            r2 = fp.add(r2, r2);
+            i += 1;
+        }
+        fp.r2 = r2;
+
+        // roots[l] = g^(2^(num_roots - l)) for each l <= MAX_ROOTS.
+        fp.g = fp.elem(g);
+        let mut x = fp.g;
+        let mut l = num_roots;
+        loop {
+            if l <= MAX_ROOTS {
+                fp.roots[l] = x;
+            }
+            if l == 0 {
+                break;
+            }
+            x = fp.mul(x, x);
+            l -= 1;
+        }
+
+        let one = fp.elem(1);
+        assert!(fp.roots[0] == one, "g^(2^num_roots) must be 1");
+        assert!(fp.roots[1] == fp.neg(one), "g must have order 2^num_roots");
+        fp
+    }
+
+    /// Checks that the parameters are consistent. See [`FieldParameters::check`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are inconsistent.
+    pub fn check(&self) {
+        assert!(
+            is_probable_prime(self.p as u128, |x, e| {
+                self.from_elem(self.pow(self.elem(x as u64), e as u64)) as u128
+            }),
+            "p is not prime"
+        );
+        assert_eq!(
+            self,
+            &Self::new(self.p, self.from_elem(self.g), self.num_roots),
+            "parameters do not match those computed from p, g, and num_roots"
+        );
+    }
+
+    /// Addition. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn add(&self, x: u64, y: u64) -> u64 {
+        let (z, carry) = x.overflowing_add(y);
+        let (s0, b0) = z.overflowing_sub(self.p);
+        let (_s1, b1) = (carry as u64).overflowing_sub(b0 as u64);
+        // if b1 == 1: return z
+        // else:       return s0
+        let m = 0u64.wrapping_sub(b1 as u64);
+        (z & m) | (s0 & !m)
+    }
+
+    /// Subtraction. The result will be in [0, p), so long as both x and y are as well.
+    pub const fn sub(&self, x: u64, y: u64) -> u64 {
+        let (z0, b0) = x.overflowing_sub(y);
+        let m = 0u64.wrapping_sub(b0 as u64);
+        z0.wrapping_add(m & self.p)
+    }
+
+    /// Multiplication of field elements in the Montgomery domain. This uses the REDC algorithm
+    /// described
+    /// [here](https://www.ams.org/journals/mcom/1985-44-170/S0025-5718-1985-0777282-X/S0025-5718-1985-0777282-X.pdf).
+    /// The result will be in [0, p), so long as both x and y are as well.
+    pub const fn mul(&self, x: u64, y: u64) -> u64 {
+        let zz = (x as u128) * (y as u128);
+
+        // Reduction
+        let w = self.mu.wrapping_mul(zz as u64);
+        let (t, carry) = zz.overflowing_add((w as u128) * (self.p as u128));
+        // The lower 64 bits of t are zero, so t / 2^64 is (carry, z).
+        let z = (t >> 64) as u64;
+
+        // Final subtraction
+        // If (carry, z) >= p, then z = z - p
+        let (s0, b0) = z.overflowing_sub(self.p);
+        let (_s1, b1) = (carry as u64).overflowing_sub(b0 as u64);
+        // if b1 == 1: return z
+        // else:       return s0
+        let m = 0u64.wrapping_sub(b1 as u64);
+        (z & m) | (s0 & !m)
+    }
+
+    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the values of `x` or `exp`.
+    pub fn pow(&self, x: u64, exp: u64) -> u64 {
+        self.pow_bits(x, exp, 64)
+    }
+
+    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulus. The runtime of this
+    /// algorithm is linear in the bit length of `p` and does not depend on the value of `x`.
+    pub fn inv(&self, x: u64) -> u64 {
+        self.pow_bits(x, self.p - 2, 64 - self.p.leading_zeros())
+    }
+
+    // Computes `x^exp (mod p)`, where `exp < 2^bits`, in constant time.
+    fn pow_bits(&self, x: u64, exp: u64, bits: u32) -> u64 {
+        let mut t = self.elem(1);
+        for i in (0..bits).rev() {
+            t = self.mul(t, t);
+            let u = self.mul(t, x);
+            t = u64::conditional_select(&t, &u, Choice::from(((exp >> i) & 1) as u8));
+        }
+        t
+    }
+
+    /// Negation, i.e., `-x (mod p)` where `p` is the modulus.
+    pub const fn neg(&self, x: u64) -> u64 {
+        self.sub(0, x)
+    }
+
+    /// Maps an integer to its internal representation in the Montgomery domain.
+    pub const fn elem(&self, x: u64) -> u64 {
+        self.mul(x, self.r2)
+    }
+
+    /// Returns a random field element mapped.
+    #[cfg(test)]
+    pub(crate) fn rand_elem<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let uniform = rand::distributions::Uniform::from(0..self.p);
+        self.elem(uniform.sample(rng))
+    }
+
+    /// Maps a field element to its representation as an integer.
+    #[allow(clippy::wrong_self_convention)]
+    pub const fn from_elem(&self, x: u64) -> u64 {
+        self.mul(x, 1)
+    }
+
+    /// Reduces a sum of field elements in their internal representation, i.e., maps `x` to the
+    /// field element whose internal representation is equal to `x (mod p)`.
+    pub fn reduce_wide(&self, x: u128) -> u64 {
+        // Write x = hi * 2^64 + lo. Since hi, lo < R, each product below is a valid input to
+        // REDC. The first outputs hi * R (mod p); the second and third map lo to lo (mod p).
+        let hi = self.mul((x >> 64) as u64, self.r2);
+        let lo = self.mul(self.mul(x as u64, 1), self.r2);
+        self.add(hi, lo)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check_against(&self, p: u64, g: u64, order: u64) {
+        use modinverse::modinverse;
+
+        self.check();
+
+        assert_eq!(self.p, p, "p mismatch");
+
+        let mu = match modinverse((-(p as i128)).rem_euclid(1 << 64), 1 << 64) {
+            Some(mu) => mu as u64,
+            None => panic!("inverse of -p (mod 2^64) is undefined"),
+        };
+        assert_eq!(self.mu, mu, "mu mismatch");
+
+        let r = (1u128 << 64) % (p as u128);
+        let r2 = (r * r) % (p as u128);
+        assert_eq!(self.r2 as u128, r2, "r2 mismatch");
+
+        assert_eq!(self.g, self.elem(g), "g mismatch");
+        assert_eq!(
+            self.from_elem(self.pow(self.g, order)),
+            1,
+            "g order incorrect"
+        );
+
+        let num_roots = log2(order as u128) as usize;
+        assert_eq!(order, 1 << num_roots, "order not a power of 2");
+        assert_eq!(self.num_roots, num_roots, "num_roots mismatch");
+
+        let mut roots = vec![0; std::cmp::max(num_roots, MAX_ROOTS) + 1];
+        roots[num_roots] = self.elem(g);
+        for i in (0..num_roots).rev() {
+            roots[i] = self.mul(roots[i + 1], roots[i + 1]);
+        }
+        assert_eq!(&self.roots, &roots[..MAX_ROOTS + 1], "roots mismatch");
+        assert_eq!(self.from_elem(self.roots[0]), 1, "first root is not one");
+
+        let bit_mask: u64 = u64::MAX >> p.leading_zeros();
+        assert_eq!(self.bit_mask, bit_mask, "bit_mask mismatch");
+    }
+}
+
+/// The parameters of [`crate::field::Field64`].
+pub const FP64: FieldParameters64 = FieldParameters64::new(
+    15564440312192434177, // 64-bit prime
+    7450580596923828125,  // generator
+    59,                   // log2 of the order of the generator
+);
+
+/// This structure represents the parameters of a finite field GF(p) for which `p = 2^61 - 1`, a
+/// Mersenne prime. Elements fit in a `u64` and are represented as integers in `[0, p)`: since
+/// `2^61 = 1 (mod p)`, a product can be reduced by adding its upper bits to its lower bits, which
+/// is considerably cheaper than Montgomery reduction.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FieldParametersMersenne61 {
+    /// The prime modulus `p`.
+    pub p: u64,
+    /// The `2^num_roots`-th -principal root of unity.
+    pub g: u64,
+    /// The number of principal roots of unity in `roots`.
+    pub num_roots: usize,
+    /// Equal to `2^b - 1`, where `b` is the length of `p` in bits.
+    pub bit_mask: u64,
+    /// `roots[l]` is the `2^l`-th principal root of unity. Since `p - 1 = 2 * (2^60 - 1)`, the
+    /// multiplicative group only has roots of unity of order 1 and 2.
+    pub roots: [u64; 2],
+}
+
+impl FieldParametersMersenne61 {
+    /// The bit length of `p`.
+    const BITS: u32 = 61;
+
+    /// Addition. The result will be in [0, p), so long as both x and y are as well.
+    pub fn add(&self, x: u64, y: u64) -> u64 {
+        // Since x, y < 2^61, the sum does not overflow.
+        self.reduce_once(x + y)
+    }
+
+    /// Subtraction. The result will be in [0, p), so long as both x and y are as well.
+    pub fn sub(&self, x: u64, y: u64) -> u64 {
+        // Since x + p >= y, the difference does not underflow.
+        self.reduce_once(x + self.p - y)
+    }
+
+    /// Multiplication. The result will be in [0, p), so long as both x and y are as well.
+    pub fn mul(&self, x: u64, y: u64) -> u64 {
+        let z = (x as u128) * (y as u128);
+        // Since 2^61 = 1 (mod p), z = lo + hi (mod p), where lo is the lower 61 bits of z and hi
+        // is the remaining bits. Both are less than 2^61, so their sum fits in a u64.
+        let lo = (z as u64) & self.p;
+        let hi = (z >> Self::BITS) as u64;
+        self.reduce_once(lo + hi)
+    }
+
+    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the values of `x` or `exp`.
+    pub fn pow(&self, x: u64, exp: u64) -> u64 {
+        self.pow_bits(x, exp, 64)
+    }
+
+    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulus. The runtime of this
+    /// algorithm is linear in the bit length of `p` and does not depend on the value of `x`.
+    pub fn inv(&self, x: u64) -> u64 {
+        self.pow_bits(x, self.p - 2, Self::BITS)
+    }
+
+    // Computes `x^exp (mod p)`, where `exp < 2^bits`, in constant time.
+    fn pow_bits(&self, x: u64, exp: u64, bits: u32) -> u64 {
+        let mut t = 1;
+        for i in (0..bits).rev() {
+            t = self.mul(t, t);
+            let u = self.mul(t, x);
+            t = u64::conditional_select(&t, &u, Choice::from(((exp >> i) & 1) as u8));
+        }
+        t
+    }
+
+    /// Negation, i.e., `-x (mod p)` where `p` is the modulus.
+    pub fn neg(&self, x: u64) -> u64 {
+        self.sub(0, x)
+    }
+
+    /// Maps an integer to its internal representation. Unlike `FieldParameters::elem()`, this
+    /// does not change the domain; the integer is merely reduced modulo `p`.
+    pub fn elem(&self, x: u64) -> u64 {
+        self.reduce_once((x & self.p) + (x >> Self::BITS))
+    }
+
+    /// Returns a random field element.
+    #[cfg(test)]
+    pub(crate) fn rand_elem<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let uniform = rand::distributions::Uniform::from(0..self.p);
+        uniform.sample(rng)
+    }
+
+    /// Maps a field element to its representation as an integer.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_elem(&self, x: u64) -> u64 {
+        x
+    }
+
+    /// Reduces a sum of field elements in their internal representation, i.e., maps `x` to the
+    /// field element whose internal representation is equal to `x (mod p)`.
+    pub fn reduce_wide(&self, x: u128) -> u64 {
+        // Since 2^61 = 1 (mod p), fold the upper bits of x into the lower bits until the result
+        // fits in a u64.
+        let x = (x & self.p as u128) + (x >> Self::BITS);
+        self.elem((x as u64 & self.p) + (x >> Self::BITS) as u64)
+    }
+
+    // Subtracts `p` from `x` if `x >= p`. The input must be less than `2p`.
+    fn reduce_once(&self, x: u64) -> u64 {
+        let (z, b) = x.overflowing_sub(self.p);
+        // if b == 1: return x
+        // else:      return z
+        let m = 0u64.wrapping_sub(b as u64);
+        (x & m) | (z & !m)
+    }
+}
+
+pub(crate) const FP61: FieldParametersMersenne61 = FieldParametersMersenne61 {
+    p: 2305843009213693951, // 61-bit prime, 2^61 - 1
+    g: 2305843009213693950,
+    num_roots: 1,
+    bit_mask: 2305843009213693951,
+    roots: [1, 2305843009213693950],
+};
+
+/// The number of 64-bit limbs used to represent an element of a multi-limb field.
+pub(crate) const LIMBS: usize = 4;
+
+/// This structure represents the parameters of a finite field GF(p) for which `p = 2^255 - c` for
+/// some small `c`. Such primes don't fit in a `u128`, so elements are represented by `LIMBS` 64-bit
+/// limbs in little-endian order. Unlike `FieldParameters`, elements are not mapped to the
+/// Montgomery domain: the special form of `p` allows products to be reduced by folding the upper
+/// half of the product into the lower half.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FieldParameters255 {
+    /// The prime modulus `p`.
+    pub p: [u64; LIMBS],
+    /// `c = 2^255 - p`.
+    pub c: u64,
+    /// The `2^num_roots`-th principal root of unity.
+    pub g: [u64; LIMBS],
+    /// The number of principal roots of unity in `roots`.
+    pub num_roots: usize,
+    /// `roots[l]` is the `2^l`-th principal root of unity.
+    pub roots: [[u64; LIMBS]; 3],
+}
+
+impl FieldParameters255 {
+    /// Addition. The result will be in [0, p), so long as both x and y are as well.
+    pub fn add(&self, x: &[u64; LIMBS], y: &[u64; LIMBS]) -> [u64; LIMBS] {
+        // Since p < 2^255, the sum fits in `LIMBS` limbs.
+        let mut z = [0; LIMBS];
+        let mut carry = 0;
+        for i in 0..LIMBS {
+            let (s, c) = adc(x[i], y[i], carry);
+            z[i] = s;
+            carry = c;
+        }
+        self.reduce_once(&z)
+    }
+
+    /// Subtraction. The result will be in [0, p), so long as both x and y are as well.
+    pub fn sub(&self, x: &[u64; LIMBS], y: &[u64; LIMBS]) -> [u64; LIMBS] {
+        let mut z = [0; LIMBS];
+        let mut borrow = 0;
+        for i in 0..LIMBS {
+            let (d, b) = sbb(x[i], y[i], borrow);
+            z[i] = d;
+            borrow = b;
+        }
+
+        // If the subtraction underflowed, then add p.
+        let m = 0u64.wrapping_sub(borrow);
+        let mut carry = 0;
+        for (limb, p) in z.iter_mut().zip(self.p.iter()) {
+            let (s, c) = adc(*limb, p & m, carry);
+            *limb = s;
+            carry = c;
+        }
+        z
+    }
+
+    /// Multiplication. The result will be in [0, p), so long as both x and y are as well.
+    pub fn mul(&self, x: &[u64; LIMBS], y: &[u64; LIMBS]) -> [u64; LIMBS] {
+        // Schoolbook multiplication.
+        let mut zz = [0; 2 * LIMBS];
+        for i in 0..LIMBS {
+            let mut carry = 0;
+            for j in 0..LIMBS {
+                let (lo, hi) = mac(zz[i + j], x[i], y[j], carry);
+                zz[i + j] = lo;
+                carry = hi;
+            }
+            zz[i + LIMBS] = carry;
+        }
+
+        // Reduction. Since `2^256 = 2*c (mod p)`, the upper half of the product is folded into the
+        // lower half by multiplying it by `2*c`.
+        let fold = 2 * self.c;
+        let mut z = [0; LIMBS];
+        let mut carry = 0;
+        for i in 0..LIMBS {
+            let (lo, hi) = mac(zz[i], zz[i + LIMBS], fold, carry);
+            z[i] = lo;
+            carry = hi;
+        }
+
+        // Fold the carry back in. The carry is less than `2*c + 1`, so the result fits in `LIMBS`
+        // limbs except with a single carry bit, which is folded in again.
+        let (lo, hi) = mac(z[0], carry, fold, 0);
+        z[0] = lo;
+        let mut carry = hi;
+        for limb in z.iter_mut().skip(1) {
+            let (s, c) = adc(*limb, 0, carry);
+            *limb = s;
+            carry = c;
+        }
+        let (s, _) = adc(z[0], carry * fold, 0);
+        z[0] = s;
+
+        self.reduce_once(&self.fold_top_bit(&z))
+    }
+
+    /// Modular exponentiation, i.e., `x^exp (mod p)` where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the values of `x` or `exp`.
+    pub fn pow(&self, x: &[u64; LIMBS], exp: &[u64; LIMBS]) -> [u64; LIMBS] {
+        let mut t = [1, 0, 0, 0];
+        for i in (0..64 * LIMBS).rev() {
+            t = self.mul(&t, &t);
+            let u = self.mul(&t, x);
+            let choice = Choice::from(((exp[i / 64] >> (i % 64)) & 1) as u8);
+            for (t_limb, u_limb) in t.iter_mut().zip(u.iter()) {
+                t_limb.conditional_assign(u_limb, choice);
+            }
+        }
+        t
+    }
+
+    /// Modular inversion, i.e., x^-1 (mod p) where `p` is the modulus. The runtime of this
+    /// algorithm does not depend on the value of `x`.
+    pub fn inv(&self, x: &[u64; LIMBS]) -> [u64; LIMBS] {
+        let mut exp = self.p;
+        // p is odd, so this does not borrow.
+        exp[0] -= 2;
+        self.pow(x, &exp)
+    }
+
+    /// Negation, i.e., `-x (mod p)` where `p` is the modulus.
+    pub fn neg(&self, x: &[u64; LIMBS]) -> [u64; LIMBS] {
+        self.sub(&[0; LIMBS], x)
+    }
+
+    /// Returns `true` if `x < p`.
+    pub fn is_reduced(&self, x: &[u64; LIMBS]) -> bool {
+        let mut borrow = 0;
+        for (limb, p) in x.iter().zip(self.p.iter()) {
+            let (_, b) = sbb(*limb, *p, borrow);
+            borrow = b;
+        }
+        borrow == 1
+    }
+
+    // Maps an integer `x < 2^256` to an integer `y < 2^255 + c` such that `x = y (mod p)`.
+    fn fold_top_bit(&self, x: &[u64; LIMBS]) -> [u64; LIMBS] {
+        let top = x[LIMBS - 1] >> 63;
+        let mut z = *x;
+        z[LIMBS - 1] &= u64::MAX >> 1;
+        let mut carry = top * self.c;
+        for limb in z.iter_mut() {
+            let (s, c) = adc(*limb, carry, 0);
+            *limb = s;
+            carry = c;
+        }
+        z
+    }
+
+    // Subtracts `p` from `x` if `x >= p`. The input must be less than `2p`.
+    fn reduce_once(&self, x: &[u64; LIMBS]) -> [u64; LIMBS] {
+        let mut z = [0; LIMBS];
+        let mut borrow = 0;
+        for i in 0..LIMBS {
+            let (d, b) = sbb(x[i], self.p[i], borrow);
+            z[i] = d;
+            borrow = b;
+        }
+
+        // if borrow == 1: return x
+        // else:           return z
+        let m = 0u64.wrapping_sub(borrow);
+        for i in 0..LIMBS {
+            z[i] = (x[i] & m) | (z[i] & !m);
+        }
+        z
+    }
+}
+
+// Returns `(x + y + carry) mod 2^64` and the carry bit.
+fn adc(x: u64, y: u64, carry: u64) -> (u64, u64) {
+    let t = (x as u128) + (y as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+// Returns `(x - y - borrow) mod 2^64` and the borrow bit.
+fn sbb(x: u64, y: u64, borrow: u64) -> (u64, u64) {
+    let t = (x as u128).wrapping_sub((y as u128) + (borrow as u128));
+    (t as u64, (t >> 127) as u64)
+}
+
+// Returns the low and high words of `z + x * y + carry`.
+fn mac(z: u64, x: u64, y: u64, carry: u64) -> (u64, u64) {
+    let t = (z as u128) + (x as u128) * (y as u128) + (carry as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+pub(crate) const FP255: FieldParameters255 = FieldParameters255 {
+    p: [
+        0xffffffffffffffed,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0x7fffffffffffffff,
+    ], // 255-bit prime, 2^255 - 19
+    c: 19,
+    g: [
+        0xc4ee1b274a0ea0b0,
+        0x2f431806ad2fe478,
+        0x2b4d00993dfbd7a7,
+        0x2b8324804fc1df0b,
+    ],
+    num_roots: 2,
+    roots: [
+        [1, 0, 0, 0],
+        [
+            0xffffffffffffffec,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0x7fffffffffffffff,
+        ],
+        [
+            0xc4ee1b274a0ea0b0,
+            0x2f431806ad2fe478,
+            0x2b4d00993dfbd7a7,
+            0x2b8324804fc1df0b,
+        ],
+    ],
+};
+
+// Compute the ceiling of the base-2 logarithm of `x`.
+pub(crate) fn log2(x: u128) -> u128 {
+    let y = (127 - x.leading_zeros()) as u128;
+    y + ((x > 1 << y) as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::ToBigInt;
+
+    #[test]
+    fn test_log2() {
+        assert_eq!(log2(1), 0);
+        assert_eq!(log2(2), 1);
+        assert_eq!(log2(3), 2);
+        assert_eq!(log2(4), 2);
+        assert_eq!(log2(15), 4);
+        assert_eq!(log2(16), 4);
+        assert_eq!(log2(30), 5);
+        assert_eq!(log2(32), 5);
+        assert_eq!(log2(1 << 127), 127);
+        assert_eq!(log2((1 << 127) + 13), 128);
+    }
+
+    struct TestFieldParametersData {
+        fp: FieldParameters,  // The paramters being tested
+        expected_p: u128,     // Expected fp.p
+        expected_g: u128,     // Expected fp.from_elem(fp.g)
+        expected_order: u128, // Expect fp.from_elem(fp.pow(fp.g, expected_order)) == 1
+    }
+
+    #[test]
+    fn test_fp() {
+        let test_fps = vec![
+            TestFieldParametersData {
+                fp: FP80,
+                expected_p: 779190469673491460259841,
+                expected_g: 41782115852031095118226,
+                expected_order: 1 << 72,
+            },
+            TestFieldParametersData {
+                fp: FP126,
+                expected_p: 74769074762901517850839147140769382401,
+                expected_g: 43421413544015439978138831414974882540,
+                expected_order: 1 << 118,
+            },
+            TestFieldParametersData {
+                fp: FP128,
+                expected_p: 340282366920938462946865773367900766209,
+                expected_g: 145091266659756586618791329697897684742,
+                expected_order: 1 << 66,
+            },
+        ];
+
+        for t in test_fps.into_iter() {
+            //  Check that the field parameters have been constructed properly.
+            t.fp.check_against(t.expected_p, t.expected_g, t.expected_order);
+
+            // Check that the generator has the correct order.
+            assert_eq!(t.fp.from_elem(t.fp.pow(t.fp.g, t.expected_order)), 1);
+
+            // Test arithmetic using the field parameters.
+            arithmetic_test(&t.fp);
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        FP32.check();
+        FP64.check();
+
+        // Parameters computed at runtime are the same as those computed at compile time.
+        assert_eq!(FieldParameters32::new(4293918721, 3925978153, 20), FP32);
+    }
+
+    #[test]
+    #[should_panic(expected = "p is not prime")]
+    fn test_check_composite() {
+        FieldParameters::new(15, 14, 1).check();
+    }
+
+    #[test]
+    #[should_panic(expected = "parameters do not match")]
+    fn test_check_inconsistent() {
+        let mut fp = FieldParameters64::new(15564440312192434177, 7450580596923828125, 59);
+        fp.roots[3] = fp.roots[2];
+        fp.check();
+    }
+
+    #[test]
+    #[should_panic(expected = "g must have order 2^num_roots")]
+    fn test_new_wrong_order() {
+        // The square of the generator has order 2^19.
+        FieldParameters::new(4293918721, 3394547854, 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "2^num_roots must divide p - 1")]
+    fn test_new_too_many_roots() {
+        FieldParameters::new(4293918721, 3925978153, 21);
+    }
+
+    fn arithmetic_test(fp: &FieldParameters) {
+        let mut rng = rand::thread_rng();
+        let big_p = &fp.p.to_bigint().unwrap();
+
+        for _ in 0..100 {
+            let x = fp.rand_elem(&mut rng);
+            let y = fp.rand_elem(&mut rng);
+            let big_x = &fp.from_elem(x).to_bigint().unwrap();
+            let big_y = &fp.from_elem(y).to_bigint().unwrap();
+
+            // Test addition.
+            let got = fp.add(x, y);
+            let want = (big_x + big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test subtraction.
+            let got = fp.sub(x, y);
+            let want = if big_x >= big_y {
+                big_x - big_y
+            } else {
+                big_p - big_y + big_x
+            };
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test multiplication.
+            let got = fp.mul(x, y);
+            let want = (big_x * big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test inversion.
+            let got = fp.inv(x);
+            let want = big_x.modpow(&(big_p - 2u128), big_p);
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.mul(got, x)), 1);
+
+            // Test negation.
+            let got = fp.neg(x);
+            let want = (big_p - big_x) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.add(got, x)), 0);
+        }
+    }
+
+    fn limbs_to_bigint(x: &[u64; LIMBS]) -> num_bigint::BigInt {
+        x.iter()
+            .rev()
+            .fold(0.to_bigint().unwrap(), |acc, limb| (acc << 64) + limb)
+    }
+
+    fn rand_limbs(rng: &mut impl rand::Rng) -> [u64; LIMBS] {
+        loop {
+            let mut x = [0; LIMBS];
+            rng.fill(&mut x);
+            x[LIMBS - 1] &= u64::MAX >> 1;
+            if FP255.is_reduced(&x) {
+                return x;
+            }
+        }
+    }
+
+    #[test]
+    fn test_fp255() {
+        let fp = &FP255;
+        let big_p = &((1.to_bigint().unwrap() << 255) - 19);
+        assert_eq!(&limbs_to_bigint(&fp.p), big_p);
+        assert_eq!(
+            &(limbs_to_bigint(&fp.p) + fp.c),
+            &(1.to_bigint().unwrap() << 255)
+        );
+
+        // Check that the roots of unity have the correct order.
+        assert_eq!(fp.g, fp.roots[fp.num_roots]);
+        for (l, root) in fp.roots.iter().enumerate() {
+            let mut x = *root;
+            for _ in 0..l {
+                assert_ne!(x, [1, 0, 0, 0]);
+                x = fp.mul(&x, &x);
+            }
+            assert_eq!(x, [1, 0, 0, 0]);
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut edge_cases = vec![[0; LIMBS], [1, 0, 0, 0], fp.roots[1]];
+        edge_cases.extend((0..100).map(|_| rand_limbs(&mut rng)));
+        for (i, x) in edge_cases.iter().enumerate() {
+            let y = &edge_cases[(i * 7 + 1) % edge_cases.len()];
+            let big_x = &limbs_to_bigint(x);
+            let big_y = &limbs_to_bigint(y);
+
+            // Test addition.
+            let got = fp.add(x, y);
+            let want = (big_x + big_y) % big_p;
+            assert_eq!(limbs_to_bigint(&got), want);
+
+            // Test subtraction.
+            let got = fp.sub(x, y);
+            let want = (big_p + big_x - big_y) % big_p;
+            assert_eq!(limbs_to_bigint(&got), want);
+
+            // Test multiplication.
+            let got = fp.mul(x, y);
+            let want = (big_x * big_y) % big_p;
+            assert_eq!(limbs_to_bigint(&got), want);
+
+            // Test negation.
+            let got = fp.neg(x);
+            let want = (big_p - big_x) % big_p;
+            assert_eq!(limbs_to_bigint(&got), want);
+            assert_eq!(fp.add(&got, x), [0; LIMBS]);
+
+            // Test inversion.
+            if *x != [0; LIMBS] {
+                let got = fp.inv(x);
+                let want = big_x.modpow(&(big_p - 2), big_p);
+                assert_eq!(limbs_to_bigint(&got), want);
+                assert_eq!(fp.mul(&got, x), [1, 0, 0, 0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fp64() {
+        let fp = &FP64;
+        fp.check_against(15564440312192434177, 7450580596923828125, 1 << 59);
+        assert_eq!(fp.from_elem(fp.pow(fp.g, 1 << 59)), 1);
+
+        let mut rng = rand::thread_rng();
+        let big_p = &fp.p.to_bigint().unwrap();
+        for _ in 0..100 {
+            let x = fp.rand_elem(&mut rng);
+            let y = fp.rand_elem(&mut rng);
+            let big_x = &fp.from_elem(x).to_bigint().unwrap();
+            let big_y = &fp.from_elem(y).to_bigint().unwrap();
+
+            // Test addition.
+            let got = fp.add(x, y);
+            let want = (big_x + big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test subtraction.
+            let got = fp.sub(x, y);
+            let want = (big_p + big_x - big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test multiplication.
+            let got = fp.mul(x, y);
+            let want = (big_x * big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test inversion.
+            let got = fp.inv(x);
+            let want = big_x.modpow(&(big_p - 2u64), big_p);
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.mul(got, x)), 1);
+
+            // Test negation.
+            let got = fp.neg(x);
+            let want = (big_p - big_x) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.add(got, x)), 0);
+        }
+    }
+
+    #[test]
+    fn test_fp32() {
+        let fp = &FP32;
+        fp.check_against(4293918721, 3925978153, 1 << 20);
+        assert_eq!(fp.from_elem(fp.pow(fp.g, 1 << 20)), 1);
+
+        // Sums of up to 2^128 - 1 are reduced correctly.
+        for x in [0, 1, fp.p as u128, u64::MAX as u128, u128::MAX] {
+            let got = fp.reduce_wide(x);
+            assert_eq!(got as u128, x % (fp.p as u128), "reduce_wide({})", x);
+        }
+
+        let mut rng = rand::thread_rng();
+        let big_p = &fp.p.to_bigint().unwrap();
+        for _ in 0..100 {
+            let x = fp.rand_elem(&mut rng);
+            let y = fp.rand_elem(&mut rng);
+            let big_x = &fp.from_elem(x).to_bigint().unwrap();
+            let big_y = &fp.from_elem(y).to_bigint().unwrap();
+
+            // Test addition.
+            let got = fp.add(x, y);
+            let want = (big_x + big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test subtraction.
+            let got = fp.sub(x, y);
+            let want = (big_p + big_x - big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test multiplication.
+            let got = fp.mul(x, y);
+            let want = (big_x * big_y) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+
+            // Test inversion.
+            let got = fp.inv(x);
+            let want = big_x.modpow(&(big_p - 2u32), big_p);
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.mul(got, x)), 1);
+
+            // Test negation.
+            let got = fp.neg(x);
+            let want = (big_p - big_x) % big_p;
+            assert_eq!(fp.from_elem(got).to_bigint().unwrap(), want);
+            assert_eq!(fp.from_elem(fp.add(got, x)), 0);
+        }
+    }
+
+    #[test]
+    fn test_fp61() {
+        let fp = &FP61;
+        assert_eq!(fp.p, (1 << 61) - 1);
+        assert_eq!(fp.bit_mask, u64::MAX >> fp.p.leading_zeros());
+        assert_eq!(fp.g, fp.roots[fp.num_roots]);
+        assert_eq!(fp.mul(fp.g, fp.g), 1);
+        assert_eq!(fp.roots[0], 1);
+        assert_eq!(fp.elem(fp.p), 0);
+        assert_eq!(fp.elem(u64::MAX), fp.elem(u64::MAX % fp.p));
+
+        let mut rng = rand::thread_rng();
+        let big_p = &fp.p.to_bigint().unwrap();
+        for _ in 0..100 {
+            let x = fp.rand_elem(&mut rng);
+            let y = fp.rand_elem(&mut rng);
+            let big_x = &x.to_bigint().unwrap();
+            let big_y = &y.to_bigint().unwrap();
+
+            // Test addition.
+            let got = fp.add(x, y);
+            let want = (big_x + big_y) % big_p;
+            assert_eq!(got.to_bigint().unwrap(), want);
+
+            // Test subtraction.
+            let got = fp.sub(x, y);
+            let want = (big_p + big_x - big_y) % big_p;
+            assert_eq!(got.to_bigint().unwrap(), want);
+
+            // Test multiplication.
+            let got = fp.mul(x, y);
+            let want = (big_x * big_y) % big_p;
+            assert_eq!(got.to_bigint().unwrap(), want);
+
+            // Test inversion.
+            let got = fp.inv(x);
+            let want = big_x.modpow(&(big_p - 2u64), big_p);
+            assert_eq!(got.to_bigint().unwrap(), want);
+            assert_eq!(fp.mul(got, x), 1);
+
+            // Test negation.
+            let got = fp.neg(x);
+            let want = (big_p - big_x) % big_p;
+            assert_eq!(got.to_bigint().unwrap(), want);
+            assert_eq!(fp.add(got, x), 0);
         }
     }
 }
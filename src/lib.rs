@@ -10,12 +10,102 @@
 
 pub mod benchmarked;
 pub mod client;
+pub mod codec;
 pub mod encrypt;
 pub mod fft;
 pub mod field;
-mod fp;
+pub mod fp;
 pub mod pcp;
-mod polynomial;
-mod prng;
+pub mod polynomial;
+pub mod prng;
+pub mod secret;
 pub mod server;
+#[cfg(feature = "test-vector")]
+pub mod test_vector;
 pub mod util;
+
+/// Errors from any part of the crate. Each module's error type converts into this type, so that
+/// applications can use `?` across the whole API.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Finite field operation error.
+    #[error("finite field operation error")]
+    Field(#[from] field::FieldError),
+    /// Shamir secret sharing error.
+    #[error("shamir secret sharing error")]
+    Shamir(#[from] field::ShamirError),
+    /// FFT error.
+    #[error("fft error")]
+    Fft(#[from] fft::FftError),
+    /// Polynomial error.
+    #[error("polynomial error")]
+    Polynomial(#[from] polynomial::PolynomialError),
+    /// PRNG error.
+    #[error("prng error")]
+    Prng(#[from] prng::PrngError),
+    /// Serialization/deserialization error.
+    #[error("serialization/deserialization error")]
+    Serialize(#[from] util::SerializeError),
+    /// Invalid proof dimension.
+    #[error("invalid dimension")]
+    Dimension(#[from] util::DimensionError),
+    /// Share reconstruction error.
+    #[error("share reconstruction error")]
+    Reconstruct(#[from] util::ReconstructError),
+    /// Encoding/decoding error.
+    #[error("codec error")]
+    Codec(#[from] codec::CodecError),
+    /// Encryption/decryption error.
+    #[error("encryption/decryption error")]
+    Encrypt(#[from] encrypt::EncryptError),
+    /// Client error.
+    #[error("client error")]
+    Client(#[from] client::ClientError),
+    /// Server error.
+    #[error("server error")]
+    Server(#[from] server::ServerError),
+    /// Proof system error.
+    #[error("proof system error")]
+    Pcp(#[from] pcp::PcpError),
+    /// Input type error.
+    #[error("input type error")]
+    Type(#[from] pcp::types::TypeError),
+    /// Test vector error.
+    #[cfg(feature = "test-vector")]
+    #[error("test vector error")]
+    TestVector(#[from] test_vector::TestVectorError),
+    /// Failure when calling getrandom().
+    #[error("getrandom: {0}")]
+    GetRandom(#[from] getrandom::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field32, FieldElementWithInteger};
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_error_conversions() {
+        fn decode_and_reconstruct(share1: &[u8], share2: &[u8]) -> Result<Vec<Field32>, Error> {
+            let share1 = util::deserialize::<Field32>(share1)?;
+            let share2 = util::deserialize::<Field32>(share2)?;
+            Ok(util::reconstruct_shares([share1, share2])?)
+        }
+
+        let share = util::serialize(&[Field32::from_reduced(1)]);
+        assert_eq!(
+            decode_and_reconstruct(&share, &share).unwrap(),
+            [Field32::from_reduced(2)]
+        );
+        assert_matches!(
+            decode_and_reconstruct(&share, &share[..3]),
+            Err(Error::Serialize(_))
+        );
+        let long_share = util::serialize(&[Field32::from_reduced(1); 2]);
+        assert_matches!(
+            decode_and_reconstruct(&share, &long_share),
+            Err(Error::Reconstruct(_))
+        );
+    }
+}
@@ -46,11 +46,11 @@
 //! ```
 //! use prio::pcp::types::Boolean;
 //! use prio::pcp::{decide, prove, query, Value};
-//! use prio::field::{rand, FieldElement, Field64};
+//! use prio::field::{rand, FieldElement, FieldElementWithInteger, Field64};
 //!
 //! use std::convert::TryFrom;
 //!
-//! let x = Boolean::try_from(((), vec![Field64::from(23)])).unwrap(); // Invalid input
+//! let x = Boolean::try_from(((), vec![Field64::from_reduced(23)])).unwrap(); // Invalid input
 //! let joint_rand = rand(x.valid_rand_len()).unwrap();
 //! let query_rand = rand(x.valid_gadget_len()).unwrap();
 //! let pf = prove(&x, &joint_rand).unwrap();
@@ -80,7 +80,7 @@
 //! let x_shares: Vec<Boolean<Field64>> = split(x.as_slice(), 2)
 //!     .unwrap()
 //!     .into_iter()
-//!     .map(|data| Boolean::try_from((x_par, data)).unwrap())
+//!     .map(|data| Boolean::try_from((x_par, data.into_vec())).unwrap())
 //!     .collect();
 //!
 //! let joint_rand = rand(x.valid_rand_len()).unwrap();
@@ -92,7 +92,7 @@
 //! let pf_shares: Vec<Proof<Field64>> = split(pf.as_slice(), 2)
 //!     .unwrap()
 //!     .into_iter()
-//!     .map(Proof::from)
+//!     .map(|share| Proof::from(share.into_vec()))
 //!     .collect();
 //!
 //! // Each verifier queries its shares of the input and proof and sends its
@@ -126,10 +126,11 @@ use std::any::Any;
 use std::convert::TryFrom;
 use std::fmt::Debug;
 
-use crate::fft::{discrete_fourier_transform, discrete_fourier_transform_inv_finish, FftError};
-use crate::field::{FieldElement, FieldError};
-use crate::fp::log2;
-use crate::polynomial::poly_eval;
+use crate::fft::{
+    discrete_fourier_transform_in_place, discrete_fourier_transform_inv_scaled, FftError,
+};
+use crate::field::{bulk, from_usize, FieldElement, FieldError};
+use crate::polynomial::{poly_eval, PolyEvaluator};
 use crate::prng::Prng;
 
 pub mod gadgets;
@@ -274,7 +275,7 @@ pub trait Value<F: FieldElement>:
     ///     .enumerate()
     ///     .map(|(i, data)| {
     ///         let mut share =
-    ///             MeanVarUnsignedVector::try_from((x.param(), data)).unwrap();
+    ///             MeanVarUnsignedVector::try_from((x.param(), data.into_vec())).unwrap();
     ///         share.set_leader(i == 0);
     ///         share
     ///     })
@@ -287,7 +288,7 @@ pub trait Value<F: FieldElement>:
     /// let pf_shares: Vec<Proof<Field64>> = split(pf.as_slice(), 2)
     ///     .unwrap()
     ///     .into_iter()
-    ///     .map(Proof::from)
+    ///     .map(|share| Proof::from(share.into_vec()))
     ///     .collect();
     ///
     /// let vf_shares = vec![
@@ -357,6 +358,8 @@ where
     // Fill the buffer with the proof. `proof_len` keeps track of the amount of data written to the
     // buffer so far.
     let mut proof_len = 0;
+    // Buffers for the intermediate proof polynomials, reused across gadgets.
+    let mut f: Vec<Vec<F>> = Vec::new();
     for idx in 0..shim.len() {
         let g = shim[idx]
             .as_any()
@@ -369,11 +372,11 @@ where
         // Interpolate the intermediate proof polynomials `f[0], ..., f[g_arity-1]` from the gadget
         // inputs.
         let m = (1 + g_calls[idx]).next_power_of_two();
-        let m_inv = F::from(F::Integer::try_from(m).unwrap()).inv();
-        let mut f = vec![vec![F::zero(); m]; g_arity];
+        let m_inv = from_usize::<F>(m).inv();
+        f.resize_with(g_arity, Vec::new);
         for wire in 0..g_arity {
-            discrete_fourier_transform(&mut f[wire], &g.f_vals[wire], m)?;
-            discrete_fourier_transform_inv_finish(&mut f[wire], m, m_inv);
+            f[wire].resize(m, F::zero());
+            discrete_fourier_transform_inv_scaled(&mut f[wire], &g.f_vals[wire], m, m_inv)?;
 
             // The first point on each intermediate polynomial is a random value chosen by the
             // prover. This point is stored in the proof so that the verifier can reconstruct the
@@ -409,7 +412,7 @@ impl<F: FieldElement> ProveShimGadget<F> {
         g_calls: usize,
     ) -> Result<Box<dyn Gadget<F>>, getrandom::Error> {
         let mut f_vals = vec![vec![F::zero(); 1 + g_calls]; inner.arity()];
-        let mut prng = Prng::new_with_length(f_vals.len())?;
+        let mut prng = Prng::<F>::new_with_length(f_vals.len())?;
 
         for wire in 0..f_vals.len() {
             // Choose a random field element as the first point on the intermediate proof
@@ -470,6 +473,13 @@ impl<F: FieldElement> From<Vec<F>> for Proof<F> {
     }
 }
 
+#[cfg(feature = "fuzz")]
+impl<'a, F: FieldElement + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for Proof<F> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::from(Vec::<F>::arbitrary(u)?))
+    }
+}
+
 /// Generate a verifier message for an input and proof (or the verifier share for an input share
 /// and proof share).
 ///
@@ -546,6 +556,7 @@ where
     data.push(v);
 
     // Fill the buffer with the verifier message.
+    let mut f = Vec::new();
     for idx in 0..shim.len() {
         let r = query_rand[idx];
         let g = shim[idx]
@@ -556,12 +567,12 @@ where
         // Reconstruct the intermediate proof polynomials `f[0], ..., f[g_arity-1]` and evaluate
         // each polynomial at input `r`.
         let m = (1 + g_calls[idx]).next_power_of_two();
-        let m_inv = F::from(F::Integer::try_from(m).unwrap()).inv();
-        let mut f = vec![F::zero(); m];
+        let m_inv = from_usize::<F>(m).inv();
+        f.resize(m, F::zero());
+        let mut evaluator = PolyEvaluator::with_capacity(r, m);
         for wire in 0..g.arity() {
-            discrete_fourier_transform(&mut f, &g.f_vals[wire], m)?;
-            discrete_fourier_transform_inv_finish(&mut f, m, m_inv);
-            data.push(poly_eval(&f, r));
+            discrete_fourier_transform_inv_scaled(&mut f, &g.f_vals[wire], m, m_inv)?;
+            data.push(evaluator.eval(&f));
         }
 
         // Add the value of the proof polynomial evaluated at `r`.
@@ -585,9 +596,6 @@ struct QueryShimGadget<F: FieldElement> {
     /// The proof polynomial evaluated on a random input `r`.
     p_at_r: F,
 
-    /// Used to compute an index into `p_val`.
-    step: usize,
-
     /// The number of times the gadget has been called so far.
     ct: usize,
 }
@@ -599,10 +607,8 @@ impl<F: FieldElement> QueryShimGadget<F> {
         proof_data: &[F],
         g_calls: usize,
     ) -> Result<Box<dyn Gadget<F>>, PcpError> {
-        let g_deg = inner.deg();
         let g_arity = inner.arity();
         let m = (1 + g_calls).next_power_of_two();
-        let p = m * g_deg;
 
         // Each call to this gadget records the values at which intermediate proof polynomials were
         // interpolated. The first point was a random value chosen by the prover and transmitted in
@@ -612,14 +618,14 @@ impl<F: FieldElement> QueryShimGadget<F> {
             f_vals[wire][0] = proof_data[wire];
         }
 
-        // Evaluate the proof polynomial at roots of unity.
-        let size = p.next_power_of_two();
-        let mut p_vals = vec![F::zero(); size];
-        discrete_fourier_transform(&mut p_vals, &proof_data[g_arity..], size)?;
-
-        // The step is used to compute the element of `p_val` that will be returned by a call to
-        // the gadget.
-        let step = (1 << (log2(p as u128) - log2(m as u128))) as usize;
+        // Evaluate the proof polynomial at the `m`-th roots of unity. Only these evaluations are
+        // used, so rather than transforming the whole polynomial, reduce it modulo `x^m - 1`, which
+        // agrees with it on these points, and transform the remainder.
+        let mut p_vals = vec![F::zero(); m];
+        for (j, coeff) in proof_data[g_arity..].iter().enumerate() {
+            p_vals[j & (m - 1)] += *coeff;
+        }
+        discrete_fourier_transform_in_place(&mut p_vals, m)?;
 
         // Evaluate the proof polynomial `p` at `r`.
         //
@@ -634,7 +640,6 @@ impl<F: FieldElement> QueryShimGadget<F> {
             f_vals,
             p_vals,
             p_at_r,
-            step,
             ct: 1,
         }))
     }
@@ -645,7 +650,7 @@ impl<F: FieldElement> Gadget<F> for QueryShimGadget<F> {
         for wire in 0..inp.len() {
             self.f_vals[wire][self.ct] = inp[wire];
         }
-        let outp = self.p_vals[self.ct * self.step];
+        let outp = self.p_vals[self.ct];
         self.ct += 1;
         Ok(outp)
     }
@@ -690,6 +695,13 @@ impl<F: FieldElement> From<Vec<F>> for Verifier<F> {
     }
 }
 
+#[cfg(feature = "fuzz")]
+impl<'a, F: FieldElement + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for Verifier<F> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::from(Vec::<F>::arbitrary(u)?))
+    }
+}
+
 impl<F: FieldElement> TryFrom<&[Verifier<F>]> for Verifier<F> {
     type Error = PcpError;
 
@@ -708,9 +720,7 @@ impl<F: FieldElement> TryFrom<&[Verifier<F>]> for Verifier<F> {
                 return Err(PcpError::CollectGadgetInLenMismatch);
             }
 
-            for j in 0..vf.data.len() {
-                vf.data[j] += vf_shares[i].data[j];
-            }
+            bulk::add_assign(&mut vf.data, &vf_shares[i].data);
         }
 
         Ok(vf)
@@ -760,7 +770,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::field::{rand, split, Field126};
+    use crate::field::{rand, split, Field126, FieldElementWithInteger};
     use crate::pcp::gadgets::{Mul, PolyEval};
     use crate::pcp::types::Boolean;
     use crate::polynomial::poly_range_check;
@@ -775,7 +785,7 @@ mod tests {
         type T = TestValue<F>;
         const NUM_SHARES: usize = 2;
 
-        let inp = F::from(3);
+        let inp = F::from_reduced(3);
         let x: T = TestValue::new(inp);
         let x_par = x.param();
         let x_shares: Vec<T> = split(x.as_slice(), NUM_SHARES)
@@ -783,7 +793,7 @@ mod tests {
             .into_iter()
             .enumerate()
             .map(|(i, data)| {
-                let mut share = T::try_from((x_par, data)).unwrap();
+                let mut share = T::try_from((x_par, data.into_vec())).unwrap();
                 share.set_leader(i == 0);
                 share
             })
@@ -794,7 +804,7 @@ mod tests {
         let pf_shares: Vec<Proof<F>> = split(pf.as_slice(), NUM_SHARES)
             .unwrap()
             .into_iter()
-            .map(Proof::from)
+            .map(|share| Proof::from(share.into_vec()))
             .collect();
 
         let query_rand = rand(2).unwrap(); // Length is the same as the length of the gadget
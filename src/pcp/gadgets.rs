@@ -2,13 +2,15 @@
 
 //! A collection of gadgets.
 
-use crate::fft::{discrete_fourier_transform, discrete_fourier_transform_inv_finish};
-use crate::field::FieldElement;
+use crate::fft::{
+    discrete_fourier_transform, discrete_fourier_transform_in_place,
+    discrete_fourier_transform_inv_finish, discrete_fourier_transform_inv_scaled,
+};
+use crate::field::{bulk, from_usize, FieldElement};
 use crate::pcp::{Gadget, PcpError};
-use crate::polynomial::{poly_deg, poly_eval, poly_mul, poly_range_check};
+use crate::polynomial::{poly_deg, poly_eval, poly_mul_direct_into, poly_range_check};
 
 use std::any::Any;
-use std::convert::TryFrom;
 
 /// For input polynomials larger than or equal to this threshold, gadgets will use FFT for
 /// polynomial multiplication. Otherwise, the gadget uses direct multiplication.
@@ -30,7 +32,7 @@ impl<F: FieldElement> Mul<F> {
         // degree of this gadget is `2`, so this is `2 * (1 + num_calls).next_power_of_two()`.
         // (We round up to the next power of two in order to make room for FFT.)
         let n = (2 * (1 + num_calls).next_power_of_two()).next_power_of_two();
-        let n_inv = F::from(F::Integer::try_from(n).unwrap()).inv();
+        let n_inv = from_usize::<F>(n).inv();
         Self { n, n_inv }
     }
 
@@ -40,10 +42,7 @@ impl<F: FieldElement> Mul<F> {
         outp: &mut [F],
         inp: &Vec<Vec<F>>,
     ) -> Result<(), PcpError> {
-        let v = poly_mul(&inp[0], &inp[1]);
-        for i in 0..v.len() {
-            outp[i] = v[i];
-        }
+        poly_mul_direct_into(outp, &inp[0], &inp[1]);
         Ok(())
     }
 
@@ -59,11 +58,9 @@ impl<F: FieldElement> Mul<F> {
         discrete_fourier_transform(&mut buf, &inp[0], n)?;
         discrete_fourier_transform(outp, &inp[1], n)?;
 
-        for i in 0..n {
-            buf[i] *= outp[i];
-        }
+        bulk::mul_assign(&mut outp[..n], &buf);
 
-        discrete_fourier_transform(outp, &buf, n)?;
+        discrete_fourier_transform_in_place(outp, n)?;
         discrete_fourier_transform_inv_finish(outp, n, self.n_inv);
         Ok(())
     }
@@ -111,7 +108,7 @@ impl<F: FieldElement> PolyEval<F> {
     /// this gadget is called by the validity circuit.
     pub fn new(poly: Vec<F>, num_calls: usize) -> Self {
         let n = (poly_deg(&poly) * (1 + num_calls).next_power_of_two()).next_power_of_two();
-        let n_inv = F::from(F::Integer::try_from(n).unwrap()).inv();
+        let n_inv = from_usize::<F>(n).inv();
         Self { poly, n, n_inv }
     }
 }
@@ -119,15 +116,23 @@ impl<F: FieldElement> PolyEval<F> {
 impl<F: FieldElement> PolyEval<F> {
     // Multiply input polynomials directly.
     fn call_poly_direct(&mut self, outp: &mut [F], inp: &Vec<Vec<F>>) -> Result<(), PcpError> {
+        let inp = &inp[0];
+        let buf_len = self.poly.len() * inp.len();
+        let mut x = vec![F::zero(); buf_len];
+        let mut tmp = vec![F::zero(); buf_len];
+        x[..inp.len()].clone_from_slice(inp);
+        let mut x_len = inp.len();
+
         outp[0] = self.poly[0];
-        let mut x = inp[0].to_vec();
         for i in 1..self.poly.len() {
-            for j in 0..x.len() {
-                outp[j] += self.poly[i] * x[j];
+            for (o, x_j) in outp.iter_mut().zip(x[..x_len].iter()) {
+                *o += self.poly[i] * *x_j;
             }
 
             if i < self.poly.len() - 1 {
-                x = poly_mul(&x, &inp[0]);
+                poly_mul_direct_into(&mut tmp, &x[..x_len], inp);
+                std::mem::swap(&mut x, &mut tmp);
+                x_len += inp.len() - 1;
             }
         }
         Ok(())
@@ -152,12 +157,9 @@ impl<F: FieldElement> PolyEval<F> {
             }
 
             if i < self.poly.len() - 1 {
-                for j in 0..n {
-                    x_vals[j] *= inp_vals[j];
-                }
+                bulk::mul_assign(&mut x_vals, &inp_vals);
 
-                discrete_fourier_transform(&mut x, &x_vals, n)?;
-                discrete_fourier_transform_inv_finish(&mut x, n, self.n_inv);
+                discrete_fourier_transform_inv_scaled(&mut x, &x_vals, n, self.n_inv)?;
             }
         }
         Ok(())
@@ -230,7 +232,7 @@ impl<F: FieldElement> MeanVarUnsigned<F> {
     pub fn new(bits: usize, num_calls: usize) -> Self {
         let poly: Vec<F> = poly_range_check(0, 2);
         let n = (3 * (1 + num_calls).next_power_of_two()).next_power_of_two();
-        let n_inv = F::from(F::Integer::try_from(n).unwrap()).inv();
+        let n_inv = from_usize::<F>(n).inv();
 
         Self {
             poly: [poly[0], poly[1], poly[2]],
@@ -250,23 +252,26 @@ impl<F: FieldElement> MeanVarUnsigned<F> {
         let x_vec = &inp[bits..2 * bits];
         let x = &inp[2 * bits];
 
-        let z = poly_mul(x, x);
-        for i in 0..z.len() {
-            outp[i] = z[i];
-        }
-        for i in z.len()..outp.len() {
-            outp[i] = F::zero();
+        let len = x.len();
+        poly_mul_direct_into(outp, x, x);
+        for o in outp[2 * len - 1..].iter_mut() {
+            *o = F::zero();
         }
 
+        let mut z = vec![F::zero(); 3 * len];
+        let mut tmp = vec![F::zero(); 3 * len];
         for l in 0..bits {
-            let mut z = r_vec[l].to_vec();
+            z[..len].clone_from_slice(&r_vec[l]);
+            let mut z_len = len;
             for i in 0..3 {
-                for j in 0..z.len() {
-                    outp[j] += self.poly[i] * z[j];
+                for (o, z_j) in outp.iter_mut().zip(z[..z_len].iter()) {
+                    *o += self.poly[i] * *z_j;
                 }
 
                 if i < 2 {
-                    z = poly_mul(&z, &x_vec[l]);
+                    poly_mul_direct_into(&mut tmp, &z[..z_len], &x_vec[l]);
+                    std::mem::swap(&mut z, &mut tmp);
+                    z_len += len - 1;
                 }
             }
         }
@@ -290,13 +295,12 @@ impl<F: FieldElement> MeanVarUnsigned<F> {
         let mut z = vec![F::zero(); n];
 
         let m = n / 2;
-        let m_inv = self.n_inv * F::from(F::Integer::try_from(2).unwrap());
+        let m_inv = self.n_inv * 2;
         discrete_fourier_transform(&mut x_vals, x, m)?;
         for j in 0..m {
             z_vals[j] = x_vals[j] * x_vals[j];
         }
-        discrete_fourier_transform(&mut z, &z_vals, m)?;
-        discrete_fourier_transform_inv_finish(&mut z, m, m_inv);
+        discrete_fourier_transform_inv_scaled(&mut z, &z_vals, m, m_inv)?;
         for i in 0..outp.len() {
             outp[i] = z[i];
         }
@@ -316,12 +320,9 @@ impl<F: FieldElement> MeanVarUnsigned<F> {
                 }
 
                 if i < 2 {
-                    for j in 0..n {
-                        z_vals[j] *= x_vals[j];
-                    }
+                    bulk::mul_assign(&mut z_vals, &x_vals);
 
-                    discrete_fourier_transform(&mut z, &z_vals, n)?;
-                    discrete_fourier_transform_inv_finish(&mut z, n, self.n_inv);
+                    discrete_fourier_transform_inv_scaled(&mut z, &z_vals, n, self.n_inv)?;
                     z_len += x.len();
                 }
             }
@@ -457,7 +458,7 @@ mod tests {
     // Test that calling g.call_poly() and evaluating the output at a given point is equivalent
     // to evaluating each of the inputs at the same point and applying g.call() on the results.
     fn gadget_test<F: FieldElement, G: Gadget<F>>(g: &mut G, num_calls: usize) {
-        let mut prng = Prng::new().unwrap();
+        let mut prng = Prng::<F>::new().unwrap();
         let mut inp = vec![F::zero(); g.arity()];
         let mut poly_outp = vec![F::zero(); (g.deg() * (1 + num_calls)).next_power_of_two()];
         let mut poly_inp = vec![vec![F::zero(); 1 + num_calls]; g.arity()];
@@ -2,7 +2,9 @@
 
 //! A collection of data types.
 
-use crate::field::FieldElement;
+use crate::field::{
+    encode_into_bitvector, from_usize, FieldElement, FieldElementWithInteger, FieldError,
+};
 use crate::pcp::gadgets::{MeanVarUnsigned, Mul, PolyEval};
 use crate::pcp::{Gadget, PcpError, Value};
 use crate::polynomial::poly_range_check;
@@ -203,7 +205,7 @@ pub struct MeanVarUnsignedVector<F: FieldElement> {
     is_leader: bool,
 }
 
-impl<F: FieldElement> MeanVarUnsignedVector<F> {
+impl<F: FieldElementWithInteger> MeanVarUnsignedVector<F> {
     /// Encodes `measurement` as an instance of the MeanVarUnsignedVector type. `bits` specifies
     /// the maximum length of each integer in bits.
     pub fn new(bits: usize, measurement: &[F::Integer]) -> Result<Self, TypeError> {
@@ -213,23 +215,20 @@ impl<F: FieldElement> MeanVarUnsignedVector<F> {
             ));
         }
 
-        let one = F::Integer::try_from(1).unwrap();
-        let max = F::Integer::try_from(1 << bits).unwrap();
         let mut data: Vec<F> = Vec::with_capacity((bits + 1) * measurement.len());
         for &int in measurement {
-            if int >= max {
-                return Err(TypeError::Instantiate(
-                    "MeanVarUnsignedVector: input overflow",
-                ));
-            }
-
-            for l in 0..bits {
-                let l = F::Integer::try_from(l).unwrap();
-                let w = F::from((int >> l) & one);
-                data.push(w);
-            }
-
-            let x = F::from(int);
+            data.extend(encode_into_bitvector::<F>(int, bits).map_err(|e| match e {
+                FieldError::IntegerTooLarge => {
+                    TypeError::Instantiate("MeanVarUnsignedVector: input overflow")
+                }
+                _ => TypeError::Instantiate(
+                    "MeanVarUnsignedVector: bits exceeds bit length of the field modulus",
+                ),
+            })?);
+
+            let x = F::try_from(int).map_err(|_| {
+                TypeError::Instantiate("MeanVarUnsignedVector: input exceeds field modulus")
+            })?;
             data.push(x * x);
         }
 
@@ -266,7 +265,7 @@ impl<F: FieldElement> Value<F> for MeanVarUnsignedVector<F> {
             // Sets `x` to the `bits`-bit integer encoded by `x_vec`.
             let mut x = F::zero();
             for l in 0..bits {
-                let w = F::from(F::Integer::try_from(1 << l).unwrap());
+                let w = from_usize::<F>(1 << l);
                 x += w * x_vec[l];
             }
 
@@ -383,7 +382,7 @@ mod tests {
         // Test PCP on invalid input.
         pcp_validity_test(
             &Boolean {
-                data: vec![TestField::from(1337)],
+                data: vec![TestField::from_reduced(1337)],
                 range: poly_range_check(0, 2),
             },
             &ValidityTestCase {
@@ -417,7 +416,7 @@ mod tests {
     fn test_poly_checked_vec() {
         let zero = TestField::zero();
         let one = TestField::one();
-        let nine = TestField::from(9);
+        let nine = TestField::from_reduced(9);
 
         // Test PCP on valid input.
         pcp_validity_test(
@@ -489,7 +488,7 @@ mod tests {
     fn test_mean_var_uint_vec() {
         let zero = TestField::zero();
         let one = TestField::one();
-        let nine = TestField::from(9);
+        let nine = TestField::from_reduced(9);
 
         // Can't encode an integer that is larger than 2^bits.
         assert!(MeanVarUnsignedVector::<TestField>::new(8, &[256]).is_err());
@@ -507,7 +506,7 @@ mod tests {
 
             let mut x = TestField::zero();
             for l in 0..bits {
-                x += TestField::from(1 << l) * x_vec[l];
+                x += TestField::from_reduced(1 << l) * x_vec[l];
             }
             assert_eq!(x * x, xx);
         }
@@ -631,7 +630,7 @@ mod tests {
             .into_iter()
             .enumerate()
             .map(|(i, data)| {
-                let mut share = V::try_from((x.param(), data)).unwrap();
+                let mut share = V::try_from((x.param(), data.into_vec())).unwrap();
                 share.set_leader(i == 0);
                 share
             })
@@ -640,7 +639,7 @@ mod tests {
         let pf_shares: Vec<Proof<F>> = split(pf.as_slice(), NUM_SHARES)
             .unwrap()
             .into_iter()
-            .map(Proof::from)
+            .map(|share| Proof::from(share.into_vec()))
             .collect();
 
         let mut vf_shares: Vec<Verifier<F>> = Vec::with_capacity(NUM_SHARES);
@@ -1,22 +1,28 @@
 // Copyright (c) 2020 Apple Inc.
 // SPDX-License-Identifier: MPL-2.0
 
-//! Functions for polynomial interpolation and evaluation
+//! Functions for polynomial interpolation and evaluation, and the [`Polynomial`] type.
 
-use crate::field::FieldElement;
+use crate::fft::{
+    discrete_fourier_transform_inv, poly_mul as poly_mul_fft, root_of_unity, FftError,
+};
+use crate::field::{batch_inv, from_usize, FieldElement, FieldElementWithInteger, FieldError};
 
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[cfg(test)]
 use std::convert::TryFrom;
 
 /// Temporary memory used for FFT
 #[derive(Debug)]
-pub struct PolyFFTTempMemory<F: FieldElement> {
+pub(crate) struct PolyFFTTempMemory<F: FieldElement> {
     fft_tmp: Vec<F>,
     fft_y_sub: Vec<F>,
     fft_roots_sub: Vec<F>,
 }
 
 impl<F: FieldElement> PolyFFTTempMemory<F> {
-    fn new(length: usize) -> Self {
+    pub(crate) fn new(length: usize) -> Self {
         PolyFFTTempMemory {
             fft_tmp: vec![F::zero(); length],
             fft_y_sub: vec![F::zero(); length],
@@ -27,28 +33,32 @@ impl<F: FieldElement> PolyFFTTempMemory<F> {
 
 /// Auxiliary memory for polynomial interpolation and evaluation
 #[derive(Debug)]
-pub struct PolyAuxMemory<F: FieldElement> {
-    pub roots_2n: Vec<F>,
-    pub roots_2n_inverted: Vec<F>,
-    pub roots_n: Vec<F>,
-    pub roots_n_inverted: Vec<F>,
-    pub coeffs: Vec<F>,
-    pub fft_memory: PolyFFTTempMemory<F>,
-}
-
-impl<F: FieldElement> PolyAuxMemory<F> {
-    pub fn new(n: usize) -> Self {
-        PolyAuxMemory {
-            roots_2n: fft_get_roots(2 * n, false),
-            roots_2n_inverted: fft_get_roots(2 * n, true),
-            roots_n: fft_get_roots(n, false),
-            roots_n_inverted: fft_get_roots(n, true),
-            coeffs: vec![F::zero(); 2 * n],
-            fft_memory: PolyFFTTempMemory::new(2 * n),
-        }
+pub(crate) struct PolyAuxMemory<F: FieldElement> {
+    pub(crate) roots_2n: Vec<F>,
+    pub(crate) roots_2n_inverted: Vec<F>,
+    pub(crate) roots_n: Vec<F>,
+    pub(crate) roots_n_inverted: Vec<F>,
+    pub(crate) coeffs: Vec<F>,
+    pub(crate) fft_memory: PolyFFTTempMemory<F>,
+}
+
+impl<F: FieldElementWithInteger> PolyAuxMemory<F> {
+    pub(crate) fn new(n: usize) -> Result<Self, FieldError> {
+        let two_n = n.checked_mul(2).ok_or(FieldError::RootsUnavailable)?;
+        Ok(PolyAuxMemory {
+            roots_2n: fft_get_roots(two_n, false)?,
+            roots_2n_inverted: fft_get_roots(two_n, true)?,
+            roots_n: fft_get_roots(n, false)?,
+            roots_n_inverted: fft_get_roots(n, true)?,
+            coeffs: vec![F::zero(); two_n],
+            fft_memory: PolyFFTTempMemory::new(two_n),
+        })
     }
 }
 
+// Sets `out[k]` to the sum of `ys[j] * roots[j * k mod n]` over each `j`, where `roots` holds the
+// powers of a root of unity of order `n` and `n` is of the form `2^a * 3^b`. Factors of 2 are
+// split off first.
 fn fft_recurse<F: FieldElement>(
     out: &mut [F],
     n: usize,
@@ -63,6 +73,11 @@ fn fft_recurse<F: FieldElement>(
         return;
     }
 
+    if !n.is_multiple_of(2) {
+        debug_assert!(n.is_multiple_of(3));
+        return fft_recurse_radix3(out, n, roots, ys, tmp, y_sub, roots_sub);
+    }
+
     let half_n = n / 2;
 
     let (mut tmp_first, mut tmp_second) = tmp.split_at_mut(half_n);
@@ -106,26 +121,66 @@ fn fft_recurse<F: FieldElement>(
     }
 }
 
-/// Calculate `count` number of roots of unity of order `count`
-fn fft_get_roots<F: FieldElement>(count: usize, invert: bool) -> Vec<F> {
-    let mut roots = vec![F::zero(); count];
-    let mut gen = F::generator();
+// Same as `fft_recurse()`, but splits off a factor of 3 from `n`. For `m = n / 3` and each `r < 3`,
+// `out[3 * i + r]` is the DFT of size `m` of the sequence `z_r[j] = (ys[j] + ys[j + m] * w^r +
+// ys[j + 2 * m] * w^(2 * r)) * roots[j * r]`, where `w = roots[m]` is a cube root of unity.
+fn fft_recurse_radix3<F: FieldElement>(
+    out: &mut [F],
+    n: usize,
+    roots: &[F],
+    ys: &[F],
+    tmp: &mut [F],
+    y_sub: &mut [F],
+    roots_sub: &mut [F],
+) {
+    let third_n = n / 3;
+    let w = [F::one(), roots[third_n], roots[2 * third_n]];
+
+    let (tmp_first, tmp_rest) = tmp.split_at_mut(third_n);
+    let (y_sub_first, y_sub_rest) = y_sub.split_at_mut(third_n);
+    let (roots_sub_first, roots_sub_rest) = roots_sub.split_at_mut(third_n);
+
+    for i in 0..third_n {
+        roots_sub_first[i] = roots[3 * i];
+    }
+    for r in 0..3 {
+        for j in 0..third_n {
+            y_sub_first[j] =
+                (ys[j] + ys[j + third_n] * w[r] + ys[j + 2 * third_n] * w[(2 * r) % 3])
+                    * roots[j * r];
+        }
+        fft_recurse(
+            tmp_first,
+            third_n,
+            roots_sub_first,
+            y_sub_first,
+            tmp_rest,
+            y_sub_rest,
+            roots_sub_rest,
+        );
+        for i in 0..third_n {
+            out[3 * i + r] = tmp_first[i];
+        }
+    }
+}
+
+/// Calculate `count` number of roots of unity of order `count`. An error is returned if `count` is
+/// not of the form `2^a * 3^b` or if the field has no root of unity of order `count`.
+fn fft_get_roots<F: FieldElementWithInteger>(
+    count: usize,
+    invert: bool,
+) -> Result<Vec<F>, FieldError> {
+    let mut gen = root_of_unity::<F>(count).map_err(|_| FieldError::RootsUnavailable)?;
     if invert {
         gen = gen.inv();
     }
 
-    roots[0] = F::one();
-    let step_size = F::generator_order() / F::Integer::try_from(count).unwrap();
-    // generator for subgroup of order count
-    gen = gen.pow(step_size);
-
-    roots[1] = gen;
-
-    for i in 2..count {
+    let mut roots = vec![F::one(); count];
+    for i in 1..count {
         roots[i] = gen * roots[i - 1];
     }
 
-    roots
+    Ok(roots)
 }
 
 fn fft_interpolate_raw<F: FieldElement>(
@@ -146,14 +201,14 @@ fn fft_interpolate_raw<F: FieldElement>(
         &mut mem.fft_roots_sub,
     );
     if invert {
-        let n_inverse = F::from(F::Integer::try_from(n_points).unwrap()).inv();
+        let n_inverse = from_usize::<F>(n_points).inv();
         for i in 0..n_points {
             out[i] *= n_inverse;
         }
     }
 }
 
-pub fn poly_fft<F: FieldElement>(
+pub(crate) fn poly_fft<F: FieldElement>(
     points_out: &mut [F],
     points_in: &[F],
     scaled_roots: &[F],
@@ -164,7 +219,8 @@ pub fn poly_fft<F: FieldElement>(
     fft_interpolate_raw(points_out, points_in, n_points, scaled_roots, invert, mem)
 }
 
-// Evaluate a polynomial using Horner's method.
+/// Evaluates the polynomial with coefficients `poly`, starting with the constant term, at `eval_at`
+/// using Horner's method.
 pub fn poly_eval<F: FieldElement>(poly: &[F], eval_at: F) -> F {
     if poly.len() == 0 {
         return F::zero();
@@ -179,7 +235,8 @@ pub fn poly_eval<F: FieldElement>(poly: &[F], eval_at: F) -> F {
     result
 }
 
-// Returns the degree of polynomial `p`.
+/// Returns the degree of the polynomial with coefficients `p`, starting with the constant term.
+/// Trailing zero coefficients are ignored. The degree of the zero polynomial is reported as 0.
 pub fn poly_deg<F: FieldElement>(p: &[F]) -> usize {
     let mut d = p.len();
     while d > 0 && p[d - 1] == F::zero() {
@@ -188,8 +245,55 @@ pub fn poly_deg<F: FieldElement>(p: &[F]) -> usize {
     d.saturating_sub(1)
 }
 
+/// Removes trailing zero coefficients from `p`, so that its length is one more than its degree. The
+/// zero polynomial is trimmed to a single zero coefficient.
+pub fn poly_trim<F: FieldElement>(p: &mut Vec<F>) {
+    p.truncate(poly_deg(p) + 1);
+    if p.is_empty() {
+        p.push(F::zero());
+    }
+}
+
+/// Returns the formal derivative of `p`, i.e., the polynomial with coefficients `i * p[i]` for each
+/// `i >= 1`. The output has one fewer coefficient than `p`, or one zero coefficient if `p` has at
+/// most one.
+pub fn poly_deriv<F: FieldElement>(p: &[F]) -> Vec<F> {
+    if p.len() <= 1 {
+        return vec![F::zero()];
+    }
+
+    let mut i = F::zero();
+    p[1..]
+        .iter()
+        .map(|c| {
+            i += F::one();
+            i * *c
+        })
+        .collect()
+}
+
+/// Returns the coefficients of `c * p(x)`.
+pub fn poly_scale<F: FieldElement>(p: &[F], c: F) -> Vec<F> {
+    p.iter().map(|x| c * *x).collect()
+}
+
+/// Returns the coefficients of `p(x + a)`, which has the same number of coefficients as `p`.
+pub fn poly_shift<F: FieldElement>(p: &[F], a: F) -> Vec<F> {
+    // Horner's method over polynomials. Before processing `p[k]`, the accumulated polynomial is
+    // stored in `out[k + 1..]`; multiplying it by `x + a` moves it to `out[k..]`.
+    let mut out = vec![F::zero(); p.len()];
+    for (k, c) in p.iter().enumerate().rev() {
+        for j in k..p.len() - 1 {
+            let next = out[j + 1];
+            out[j] += a * next;
+        }
+        out[k] += *c;
+    }
+    out
+}
+
 // Multiplies polynomials `p` and `q` and returns the result.
-pub fn poly_mul<F: FieldElement>(p: &[F], q: &[F]) -> Vec<F> {
+pub(crate) fn poly_mul<F: FieldElement>(p: &[F], q: &[F]) -> Vec<F> {
     let p_size = poly_deg(p) + 1;
     let q_size = poly_deg(q) + 1;
     let mut out = vec![F::zero(); p_size + q_size];
@@ -202,7 +306,7 @@ pub fn poly_mul<F: FieldElement>(p: &[F], q: &[F]) -> Vec<F> {
     out
 }
 
-pub fn poly_interpret_eval<F: FieldElement>(
+pub(crate) fn poly_interpret_eval<F: FieldElement>(
     points: &[F],
     roots: &[F],
     eval_at: F,
@@ -213,46 +317,599 @@ pub fn poly_interpret_eval<F: FieldElement>(
     poly_eval(&tmp_coeffs[..points.len()], eval_at)
 }
 
+// Sets the first `p.len() + q.len() - 1` elements of `outp` to the coefficients of `p * q`, computed
+// directly. Neither `p` nor `q` may be empty.
+pub(crate) fn poly_mul_direct_into<F: FieldElement>(outp: &mut [F], p: &[F], q: &[F]) {
+    let outp = &mut outp[..p.len() + q.len() - 1];
+    for x in outp.iter_mut() {
+        *x = F::zero();
+    }
+    for (i, p_i) in p.iter().enumerate() {
+        for (j, q_j) in q.iter().enumerate() {
+            outp[i + j] += *p_i * *q_j;
+        }
+    }
+}
+
 // Returns a polynomial that evaluates to `0` if the input is in range `[start, end)`. Otherwise,
 // the output is not `0`.
 pub(crate) fn poly_range_check<F: FieldElement>(start: usize, end: usize) -> Vec<F> {
-    let mut p = vec![F::one()];
-    let mut q = [F::zero(), F::one()];
+    let mut p = Polynomial::constant(F::one());
     for i in start..end {
-        q[0] = -F::from(F::Integer::try_from(i).unwrap());
-        p = poly_mul(&p, &q);
+        p = &p * &Polynomial::new(vec![-from_usize::<F>(i), F::one()]);
+    }
+    p.into_coeffs()
+}
+
+/// Batches of at most this many points are evaluated with Horner's method by [`poly_eval_batch`].
+/// Above this, the points are split into a product tree.
+const POLY_EVAL_BATCH_THRESHOLD: usize = 64;
+
+/// Evaluates the polynomial with coefficients `coeffs`, starting with the constant term, at each
+/// of `points` and returns the results in the same order.
+///
+/// For large sets of points, this uses multi-point evaluation: the polynomial is reduced modulo
+/// the products of `(x - point)` over halves of the points, recursively, until the remaining sets
+/// of points are small enough to evaluate directly. Products and quotients are computed with the
+/// FFT where the field supports it.
+pub fn poly_eval_batch<F: FieldElement>(coeffs: &[F], points: &[F]) -> Vec<F> {
+    let mut out = Vec::with_capacity(points.len());
+    if points.len() <= POLY_EVAL_BATCH_THRESHOLD || coeffs.len() <= POLY_EVAL_BATCH_THRESHOLD {
+        out.extend(points.iter().map(|x| poly_eval(coeffs, *x)));
+    } else {
+        let tree = ProductTree::new(points);
+        tree.eval(coeffs, points, &mut out);
+    }
+    out
+}
+
+// A node of the tree of products of `(x - point)` over subsets of the points.
+struct ProductTree<F: FieldElement> {
+    poly: Vec<F>,
+    children: Option<Box<(ProductTree<F>, ProductTree<F>)>>,
+}
+
+impl<F: FieldElement> ProductTree<F> {
+    fn new(points: &[F]) -> Self {
+        if points.len() <= POLY_EVAL_BATCH_THRESHOLD {
+            let mut poly = vec![F::one()];
+            for x in points {
+                poly = poly_mul(&poly, &[-*x, F::one()]);
+            }
+            return Self {
+                poly,
+                children: None,
+            };
+        }
+
+        let (left, right) = points.split_at(points.len() / 2);
+        let (left, right) = (Self::new(left), Self::new(right));
+        Self {
+            poly: poly_mul_fft(&left.poly, &right.poly),
+            children: Some(Box::new((left, right))),
+        }
+    }
+
+    // Appends the evaluations of `f` at `points`, the points from which this node was built.
+    fn eval(&self, f: &[F], points: &[F], out: &mut Vec<F>) {
+        let r = poly_rem(f, &self.poly);
+        match &self.children {
+            None => out.extend(points.iter().map(|x| poly_eval(&r, *x))),
+            Some(children) => {
+                let (left, right) = points.split_at(points.len() / 2);
+                children.0.eval(&r, left, out);
+                children.1.eval(&r, right, out);
+            }
+        }
+    }
+}
+
+// Returns the remainder of the division of `a` by the monic polynomial `b`.
+fn poly_rem<F: FieldElement>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.len() < b.len() {
+        return a.to_vec();
+    }
+    let m = b.len() - 1;
+    let k = a.len() - m;
+
+    if k <= POLY_EVAL_BATCH_THRESHOLD {
+        let mut r = a.to_vec();
+        for i in (m..a.len()).rev() {
+            let c = r[i];
+            for (j, b_j) in b.iter().enumerate() {
+                r[i - m + j] -= c * *b_j;
+            }
+        }
+        r.truncate(m);
+        return r;
+    }
+
+    // Compute the quotient from the reversed polynomials: if `a = q * b + r`, then
+    // `rev(a) = rev(q) * rev(b) mod x^k`, and `rev(b)` is invertible as a power series because its
+    // constant term is 1.
+    let a_rev: Vec<F> = a.iter().rev().copied().collect();
+    let b_rev: Vec<F> = b.iter().rev().copied().collect();
+    let mut q = poly_mul_fft(&a_rev[..k], &power_series_inv(&b_rev, k));
+    q.resize(k, F::zero());
+    q.reverse();
+
+    let qb = poly_mul_fft(&q, b);
+    (0..m)
+        .map(|i| a[i] - qb.get(i).copied().unwrap_or_else(F::zero))
+        .collect()
+}
+
+// Returns the inverse of the power series `h` modulo `x^k`, computed with Newton iteration. The
+// constant term of `h` must be 1.
+fn power_series_inv<F: FieldElement>(h: &[F], k: usize) -> Vec<F> {
+    let two = F::one() + F::one();
+    let mut g = vec![F::one()];
+    let mut precision = 1;
+    while precision < k {
+        precision = std::cmp::min(2 * precision, k);
+
+        // g <- g * (2 - h * g) mod x^precision
+        let mut e = poly_mul_fft(&h[..std::cmp::min(h.len(), precision)], &g);
+        e.resize(precision, F::zero());
+        for c in e.iter_mut() {
+            *c = -*c;
+        }
+        e[0] += two;
+        g = poly_mul_fft(&g, &e);
+        g.resize(precision, F::zero());
+    }
+    g.resize(k, F::zero());
+    g
+}
+
+/// Errors propagated by methods of [`Polynomial`].
+#[derive(Debug, thiserror::Error)]
+pub enum PolynomialError {
+    /// The number of points does not match the number of values.
+    #[error("number of points does not match number of values")]
+    InputSizeMismatch,
+    /// The same point was given more than once.
+    #[error("interpolation points are not distinct")]
+    DuplicatePoint,
+    /// FFT error.
+    #[error("FFT error")]
+    Fft(#[from] FftError),
+}
+
+/// A polynomial over a finite field, represented by its coefficients, starting with the constant
+/// term. The representation is normalized so that the leading coefficient is non-zero; the zero
+/// polynomial has no coefficients.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<F: FieldElement> {
+    coeffs: Vec<F>,
+}
+
+impl<F: FieldElement> Polynomial<F> {
+    /// Returns the polynomial with the given coefficients, starting with the constant term.
+    /// Trailing zero coefficients are dropped.
+    pub fn new(coeffs: Vec<F>) -> Self {
+        let mut p = Self { coeffs };
+        p.normalize();
+        p
+    }
+
+    // Drops trailing zero coefficients.
+    fn normalize(&mut self) {
+        while self.coeffs.last() == Some(&F::zero()) {
+            self.coeffs.pop();
+        }
+    }
+
+    /// Returns the zero polynomial.
+    pub fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+
+    /// Returns the constant polynomial `c`.
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// Returns the coefficients of the polynomial, starting with the constant term. The slice is
+    /// empty for the zero polynomial.
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// Consumes the polynomial and returns its coefficients.
+    pub fn into_coeffs(self) -> Vec<F> {
+        self.coeffs
+    }
+
+    /// Returns the degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Returns true if this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Evaluates the polynomial at `x`.
+    pub fn eval(&self, x: F) -> F {
+        poly_eval(&self.coeffs, x)
+    }
+
+    /// Returns the polynomial of degree less than `points.len()` that evaluates to `values[i]` at
+    /// `points[i]` for each `i`. The points must be distinct. If they are the powers of a root of
+    /// unity, in the order used by [`Self::interpolate_roots`], the inverse DFT is used instead.
+    pub fn interpolate(points: &[F], values: &[F]) -> Result<Self, PolynomialError> {
+        if points.len() != values.len() {
+            return Err(PolynomialError::InputSizeMismatch);
+        }
+        if is_roots_of_unity(points) {
+            return Self::interpolate_roots(values);
+        }
+
+        // Lagrange interpolation: accumulate `values[i] * weights[i] * l(x) / (x - points[i])`,
+        // where `l(x) = prod_j (x - points[j])` and `weights[i]` are the barycentric weights.
+        let domain = BarycentricDomain::new(points)?;
+        let mut l = Vec::with_capacity(points.len() + 1);
+        l.push(F::one());
+        for x_j in points.iter() {
+            // Multiply `l` by `x - x_j` in place.
+            l.push(F::zero());
+            for k in (1..l.len()).rev() {
+                l[k] = l[k - 1] - *x_j * l[k];
+            }
+            l[0] = -*x_j * l[0];
+        }
+
+        let mut out = vec![F::zero(); points.len()];
+        let mut basis = vec![F::zero(); points.len()];
+        for ((x_i, y_i), w_i) in points.iter().zip(values.iter()).zip(domain.weights()) {
+            // Divide `l` by `x - x_i` with synthetic division.
+            let mut carry = F::zero();
+            for (b, c) in basis.iter_mut().zip(l[1..].iter()).rev() {
+                carry = *c + *x_i * carry;
+                *b = carry;
+            }
+
+            let scale = *y_i * *w_i;
+            for (o, b) in out.iter_mut().zip(basis.iter()) {
+                *o += scale * *b;
+            }
+        }
+
+        Ok(Self::new(out))
+    }
+
+    /// Returns the polynomial of degree less than `values.len()` that evaluates to `values[i]` at
+    /// `r^i` for each `i`, where `r` is the principal root of unity of order `values.len()`. The
+    /// number of values must be a non-zero power of 2.
+    pub fn interpolate_roots(values: &[F]) -> Result<Self, PolynomialError> {
+        let mut p = Self::zero();
+        p.interpolate_roots_into(values)?;
+        Ok(p)
+    }
+
+    /// Same as [`Self::interpolate_roots`], except that the result is written to `self`, reusing
+    /// its allocation.
+    pub fn interpolate_roots_into(&mut self, values: &[F]) -> Result<(), PolynomialError> {
+        if values.is_empty() {
+            return Err(FftError::SizeInvalid.into());
+        }
+        self.coeffs.clear();
+        self.coeffs.resize(values.len(), F::zero());
+        discrete_fourier_transform_inv(&mut self.coeffs, values, values.len())?;
+        self.normalize();
+        Ok(())
+    }
+}
+
+// Returns true if `points` is `r^0, r^1, ..., r^(n-1)`, where `n` is a power of 2 and `r` is the
+// principal root of unity of order `n`.
+fn is_roots_of_unity<F: FieldElement>(points: &[F]) -> bool {
+    if !points.len().is_power_of_two() {
+        return false;
+    }
+    let r = match F::root(points.len().trailing_zeros() as usize) {
+        Some(r) => r,
+        None => return false,
+    };
+    let mut x = F::one();
+    for p in points.iter() {
+        if *p != x {
+            return false;
+        }
+        x *= r;
+    }
+    true
+}
+
+impl<F: FieldElement> From<Vec<F>> for Polynomial<F> {
+    fn from(coeffs: Vec<F>) -> Self {
+        Self::new(coeffs)
+    }
+}
+
+impl<F: FieldElement> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn add(self, rhs: Self) -> Polynomial<F> {
+        let (long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut coeffs = long.coeffs.clone();
+        for (c, x) in coeffs.iter_mut().zip(short.coeffs.iter()) {
+            *c += *x;
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+impl<F: FieldElement> Add for Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<F: FieldElement> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn sub(self, rhs: Self) -> Polynomial<F> {
+        self + &-rhs
+    }
+}
+
+impl<F: FieldElement> Sub for Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<F: FieldElement> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn mul(self, rhs: Self) -> Polynomial<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+        Polynomial::new(poly_mul_fft(&self.coeffs, &rhs.coeffs))
+    }
+}
+
+impl<F: FieldElement> Mul for Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl<F: FieldElement> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn neg(self) -> Polynomial<F> {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|c| -*c).collect(),
+        }
+    }
+}
+
+impl<F: FieldElement> Neg for Polynomial<F> {
+    type Output = Polynomial<F>;
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+/// Evaluates polynomials at a fixed point `x`. The powers of `x` are computed once and reused, so
+/// evaluating many polynomials at the same point, e.g., at a verifier's challenge, costs one
+/// multiplication per coefficient and no additional powers. Unlike Horner's method, the products
+/// are independent of each other.
+#[derive(Clone, Debug)]
+pub struct PolyEvaluator<F: FieldElement> {
+    // powers[i] = x^i
+    powers: Vec<F>,
+}
+
+impl<F: FieldElement> PolyEvaluator<F> {
+    /// Returns an evaluator for the point `x`. Powers are computed as needed.
+    pub fn new(x: F) -> Self {
+        Self::with_capacity(x, 2)
+    }
+
+    /// Returns an evaluator for the point `x` with the powers needed for polynomials with up to
+    /// `len` coefficients precomputed.
+    pub fn with_capacity(x: F, len: usize) -> Self {
+        let mut powers = Vec::with_capacity(std::cmp::max(len, 2));
+        powers.push(F::one());
+        powers.push(x);
+        let mut evaluator = Self { powers };
+        evaluator.extend(len);
+        evaluator
+    }
+
+    /// Returns the evaluation point.
+    pub fn point(&self) -> F {
+        self.powers[1]
+    }
+
+    /// Evaluates the polynomial with coefficients `coeffs`, starting with the constant term.
+    pub fn eval(&mut self, coeffs: &[F]) -> F {
+        self.extend(coeffs.len());
+        coeffs
+            .iter()
+            .zip(self.powers.iter())
+            .map(|(c, p)| *c * *p)
+            .sum()
+    }
+
+    // Ensures that at least `len` powers are cached.
+    fn extend(&mut self, len: usize) {
+        let x = self.powers[1];
+        while self.powers.len() < len {
+            let last = self.powers[self.powers.len() - 1];
+            self.powers.push(last * x);
+        }
+    }
+}
+
+/// A fixed set of distinct interpolation points together with their barycentric weights. Once the
+/// weights are computed, the polynomial of degree less than the number of points that takes given
+/// values on the points can be evaluated anywhere in time linear in the number of points, without
+/// computing its coefficients.
+#[derive(Clone, Debug)]
+pub struct BarycentricDomain<F: FieldElement> {
+    points: Vec<F>,
+    // weights[i] = 1 / prod_{j != i} (points[i] - points[j])
+    weights: Vec<F>,
+}
+
+impl<F: FieldElement> BarycentricDomain<F> {
+    /// Computes the weights for the given points. This takes time quadratic in the number of
+    /// points. The points must be distinct.
+    pub fn new(points: &[F]) -> Result<Self, PolynomialError> {
+        let mut weights = vec![F::one(); points.len()];
+        for (i, x_i) in points.iter().enumerate() {
+            for (j, x_j) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                if x_i == x_j {
+                    return Err(PolynomialError::DuplicatePoint);
+                }
+                weights[i] *= *x_i - *x_j;
+            }
+        }
+        batch_inv(&mut weights);
+        Ok(Self {
+            points: points.to_vec(),
+            weights,
+        })
+    }
+
+    /// Returns the domain consisting of the powers `r^0, r^1, ..., r^(size-1)` of the principal
+    /// root of unity `r` of order `size`. The weights have a closed form, `r^i / size`, so this
+    /// takes time linear in `size`.
+    pub fn roots_of_unity(size: usize) -> Result<Self, PolynomialError> {
+        if !size.is_power_of_two() {
+            return Err(FftError::SizeInvalid.into());
+        }
+        let r = F::root(size.trailing_zeros() as usize).ok_or(FftError::SizeTooLarge)?;
+        let size_inv = from_usize::<F>(size).inv();
+
+        let mut points = Vec::with_capacity(size);
+        let mut weights = Vec::with_capacity(size);
+        let mut x = F::one();
+        for _ in 0..size {
+            points.push(x);
+            weights.push(x * size_inv);
+            x *= r;
+        }
+        Ok(Self { points, weights })
+    }
+
+    /// Returns the interpolation points.
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// Returns the barycentric weights of the points.
+    pub fn weights(&self) -> &[F] {
+        &self.weights
+    }
+
+    /// Evaluates at `x` the polynomial of degree less than the number of points that evaluates to
+    /// `values[i]` at the `i`-th point.
+    pub fn eval(&self, values: &[F], x: F) -> Result<F, PolynomialError> {
+        if values.len() != self.points.len() {
+            return Err(PolynomialError::InputSizeMismatch);
+        }
+
+        // If `x` is one of the points, the formula below divides by zero.
+        let mut diffs = Vec::with_capacity(self.points.len());
+        for (x_i, v_i) in self.points.iter().zip(values.iter()) {
+            if *x_i == x {
+                return Ok(*v_i);
+            }
+            diffs.push(x - *x_i);
+        }
+
+        // p(x) = l(x) * sum_i weights[i] * values[i] / (x - points[i]), where
+        // l(x) = prod_i (x - points[i]).
+        let l: F = diffs.iter().product();
+        batch_inv(&mut diffs);
+        let sum: F = diffs
+            .iter()
+            .zip(self.weights.iter().zip(values.iter()))
+            .map(|(d_inv, (w_i, v_i))| *d_inv * *w_i * *v_i)
+            .sum();
+        Ok(l * sum)
     }
-    p
 }
 
 #[test]
 fn test_roots() {
     use crate::field::Field32;
 
-    let count = 128;
-    let roots = fft_get_roots::<Field32>(count, false);
-    let roots_inv = fft_get_roots::<Field32>(count, true);
+    for count in [128, 72, 9].iter().copied() {
+        let roots = fft_get_roots::<Field32>(count, false).unwrap();
+        let roots_inv = fft_get_roots::<Field32>(count, true).unwrap();
 
-    for i in 0..count {
-        assert_eq!(roots[i] * roots_inv[i], 1);
-        assert_eq!(roots[i].pow(u32::try_from(count).unwrap()), 1);
-        assert_eq!(roots_inv[i].pow(u32::try_from(count).unwrap()), 1);
+        for i in 0..count {
+            assert_eq!(roots[i] * roots_inv[i], 1);
+            assert_eq!(roots[i].pow(u32::try_from(count).unwrap()), 1);
+            assert_eq!(roots_inv[i].pow(u32::try_from(count).unwrap()), 1);
+        }
+        // The generator has order exactly `count`.
+        assert!(roots[1..].iter().all(|x| *x != 1));
     }
 }
 
+#[test]
+fn test_roots_unavailable() {
+    use crate::field::Field32;
+
+    // Field32 has roots of unity of order 2^20 * 3^2.
+    assert!(fft_get_roots::<Field32>(1 << 20, false).is_ok());
+    assert!(fft_get_roots::<Field32>(9 << 20, false).is_ok());
+    assert_eq!(
+        fft_get_roots::<Field32>(1 << 21, false),
+        Err(FieldError::RootsUnavailable)
+    );
+    assert_eq!(
+        fft_get_roots::<Field32>(27, false),
+        Err(FieldError::RootsUnavailable)
+    );
+    assert_eq!(
+        fft_get_roots::<Field32>(10, false),
+        Err(FieldError::RootsUnavailable)
+    );
+    assert_eq!(
+        PolyAuxMemory::<Field32>::new(1 << 20).unwrap_err(),
+        FieldError::RootsUnavailable
+    );
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(
+        fft_get_roots::<Field32>(1 << 40, false),
+        Err(FieldError::RootsUnavailable)
+    );
+    assert_eq!(
+        PolyAuxMemory::<Field32>::new(usize::MAX).unwrap_err(),
+        FieldError::RootsUnavailable
+    );
+}
+
 #[test]
 fn test_eval() {
     use crate::field::Field32;
 
-    let mut poly = vec![Field32::from(0); 4];
-    poly[0] = 2.into();
-    poly[1] = 1.into();
-    poly[2] = 5.into();
+    let mut poly = vec![Field32::from_reduced(0); 4];
+    poly[0] = Field32::from_reduced(2);
+    poly[1] = Field32::from_reduced(1);
+    poly[2] = Field32::from_reduced(5);
     // 5*3^2 + 3 + 2 = 50
-    assert_eq!(poly_eval(&poly[..3], 3.into()), 50);
-    poly[3] = 4.into();
+    assert_eq!(poly_eval(&poly[..3], Field32::from_reduced(3)), 50);
+    poly[3] = Field32::from_reduced(4);
     // 4*3^3 + 5*3^2 + 3 + 2 = 158
-    assert_eq!(poly_eval(&poly[..4], 3.into()), 158);
+    assert_eq!(poly_eval(&poly[..4], Field32::from_reduced(3)), 158);
 }
 
 #[test]
@@ -276,21 +933,21 @@ fn test_poly_mul() {
     use crate::field::Field64;
 
     let p = [
-        Field64::from(u64::try_from(2).unwrap()),
-        Field64::from(u64::try_from(3).unwrap()),
+        Field64::from_reduced(u64::try_from(2).unwrap()),
+        Field64::from_reduced(u64::try_from(3).unwrap()),
     ];
 
     let q = [
         Field64::one(),
         Field64::zero(),
-        Field64::from(u64::try_from(5).unwrap()),
+        Field64::from_reduced(u64::try_from(5).unwrap()),
     ];
 
     let want = [
-        Field64::from(u64::try_from(2).unwrap()),
-        Field64::from(u64::try_from(3).unwrap()),
-        Field64::from(u64::try_from(10).unwrap()),
-        Field64::from(u64::try_from(15).unwrap()),
+        Field64::from_reduced(u64::try_from(2).unwrap()),
+        Field64::from_reduced(u64::try_from(3).unwrap()),
+        Field64::from_reduced(u64::try_from(10).unwrap()),
+        Field64::from_reduced(u64::try_from(15).unwrap()),
     ];
 
     let got = poly_mul(&p, &q);
@@ -307,18 +964,18 @@ fn test_poly_range_check() {
 
     // Check each number in the range.
     for i in start..end {
-        let x = Field64::from(i as u64);
+        let x = Field64::from_reduced(i as u64);
         let y = poly_eval(&p, x);
         assert_eq!(y, Field64::zero(), "range check failed for {}", i);
     }
 
     // Check the number below the range.
-    let x = Field64::from((start - 1) as u64);
+    let x = Field64::from_reduced((start - 1) as u64);
     let y = poly_eval(&p, x);
     assert_ne!(y, Field64::zero());
 
     // Check a number above the range.
-    let x = Field64::from(end as u64);
+    let x = Field64::from_reduced(end as u64);
     let y = poly_eval(&p, x);
     assert_ne!(y, Field64::zero());
 }
@@ -330,51 +987,268 @@ fn test_fft() {
     use rand::prelude::*;
     use std::convert::TryFrom;
 
-    let count = 128;
-    let mut mem = PolyAuxMemory::new(count / 2);
-
-    let mut poly = vec![Field32::from(0); count];
-    let mut points2 = vec![Field32::from(0); count];
-
-    let points = (0..count)
-        .into_iter()
-        .map(|_| Field32::from(random::<u32>()))
-        .collect::<Vec<Field32>>();
-
-    // From points to coeffs and back
-    poly_fft(
-        &mut poly,
-        &points,
-        &mem.roots_2n,
-        count,
-        false,
-        &mut mem.fft_memory,
+    // Sizes with factors of 3 use the mixed-radix FFT.
+    for count in [128, 72, 36].iter().copied() {
+        let mut mem = PolyAuxMemory::new(count / 2).unwrap();
+
+        let mut poly = vec![Field32::from_reduced(0); count];
+        let mut points2 = vec![Field32::from_reduced(0); count];
+
+        let points = (0..count)
+            .into_iter()
+            .map(|_| Field32::from_reduced(random::<u32>()))
+            .collect::<Vec<Field32>>();
+
+        // From points to coeffs and back
+        poly_fft(
+            &mut poly,
+            &points,
+            &mem.roots_2n,
+            count,
+            false,
+            &mut mem.fft_memory,
+        );
+        poly_fft(
+            &mut points2,
+            &poly,
+            &mem.roots_2n_inverted,
+            count,
+            true,
+            &mut mem.fft_memory,
+        );
+
+        assert_eq!(points, points2);
+
+        // interpolation
+        poly_fft(
+            &mut poly,
+            &points,
+            &mem.roots_2n,
+            count,
+            false,
+            &mut mem.fft_memory,
+        );
+        for i in 0..count {
+            let mut should_be = Field32::from_reduced(0);
+            for j in 0..count {
+                should_be = mem.roots_2n[i].pow(u32::try_from(j).unwrap()) * points[j] + should_be;
+            }
+            assert_eq!(should_be, poly[i]);
+        }
+    }
+}
+
+#[test]
+fn test_polynomial() {
+    use crate::field::{rand, Field64};
+
+    let zero = Polynomial::<Field64>::zero();
+    let one = Polynomial::constant(Field64::one());
+    assert_eq!(zero.degree(), None);
+    assert_eq!(one.degree(), Some(0));
+    assert_eq!(Polynomial::new(vec![Field64::one(), Field64::zero()]), one);
+
+    let p = Polynomial::new(rand::<Field64>(5).unwrap());
+    let q = Polynomial::new(rand::<Field64>(40).unwrap());
+    let x = rand::<Field64>(1).unwrap()[0];
+
+    assert_eq!((&p + &q).eval(x), p.eval(x) + q.eval(x));
+    assert_eq!((&p - &q).eval(x), p.eval(x) - q.eval(x));
+    assert_eq!((&p * &q).eval(x), p.eval(x) * q.eval(x));
+    assert_eq!((-&p).eval(x), -p.eval(x));
+    assert_eq!((&p * &q).degree(), Some(43));
+    assert!((&p - &p).is_zero());
+    assert_eq!(&p * &zero, zero);
+    assert_eq!(&p * &one, p);
+    assert_eq!(p.clone() + q.clone(), q.clone() + p.clone());
+}
+
+#[test]
+fn test_polynomial_interpolate() {
+    use crate::field::{rand, Field64};
+
+    let p = Polynomial::new(rand::<Field64>(8).unwrap());
+    let points = rand::<Field64>(8).unwrap();
+    let values: Vec<Field64> = points.iter().map(|x| p.eval(*x)).collect();
+    assert_eq!(Polynomial::interpolate(&points, &values).unwrap(), p);
+
+    let root = Field64::root(3).unwrap();
+    let values: Vec<Field64> = (0..8u64).map(|i| p.eval(root.pow(i))).collect();
+    assert_eq!(Polynomial::interpolate_roots(&values).unwrap(), p);
+    let mut q = Polynomial::new(rand::<Field64>(20).unwrap());
+    q.interpolate_roots_into(&values).unwrap();
+    assert_eq!(q, p);
+
+    let points: Vec<Field64> = (0..8u64).map(|i| root.pow(i)).collect();
+    assert_eq!(Polynomial::interpolate(&points, &values).unwrap(), p);
+    // Roots of unity out of order take the general path.
+    let mut shuffled = points.clone();
+    shuffled.swap(1, 2);
+    let mut shuffled_values = values.clone();
+    shuffled_values.swap(1, 2);
+    assert_eq!(
+        Polynomial::interpolate(&shuffled, &shuffled_values).unwrap(),
+        p
+    );
+
+    assert!(Polynomial::<Field64>::interpolate(&[], &[])
+        .unwrap()
+        .is_zero());
+    assert_matches::assert_matches!(
+        Polynomial::interpolate(&points[..2], &values[..3]),
+        Err(PolynomialError::InputSizeMismatch)
+    );
+    assert_matches::assert_matches!(
+        Polynomial::interpolate(&[points[0], points[0]], &values[..2]),
+        Err(PolynomialError::DuplicatePoint)
     );
-    poly_fft(
-        &mut points2,
-        &poly,
-        &mem.roots_2n_inverted,
-        count,
-        true,
-        &mut mem.fft_memory,
+    assert_matches::assert_matches!(
+        Polynomial::interpolate_roots(&values[..3]),
+        Err(PolynomialError::Fft(FftError::SizeInvalid))
     );
+    assert_matches::assert_matches!(
+        Polynomial::<Field64>::interpolate_roots(&[]),
+        Err(PolynomialError::Fft(FftError::SizeInvalid))
+    );
+}
 
-    assert_eq!(points, points2);
+#[test]
+fn test_poly_eval_batch() {
+    use crate::field::{rand, Field61, Field64};
 
-    // interpolation
-    poly_fft(
-        &mut poly,
-        &points,
-        &mem.roots_2n,
-        count,
-        false,
-        &mut mem.fft_memory,
-    );
-    for i in 0..count {
-        let mut should_be = Field32::from(0);
-        for j in 0..count {
-            should_be = mem.roots_2n[i].pow(u32::try_from(j).unwrap()) * points[j] + should_be;
+    for (deg, count) in [(0, 10), (10, 1000), (500, 1000), (3000, 300)].iter() {
+        let coeffs = rand::<Field64>(*deg + 1).unwrap();
+        let points = rand::<Field64>(*count).unwrap();
+        let want: Vec<Field64> = points.iter().map(|x| poly_eval(&coeffs, *x)).collect();
+        assert_eq!(poly_eval_batch(&coeffs, &points), want);
+    }
+
+    // Field61 does not have the roots of unity needed for the FFT.
+    let coeffs = rand::<Field61>(200).unwrap();
+    let points = rand::<Field61>(200).unwrap();
+    let want: Vec<Field61> = points.iter().map(|x| poly_eval(&coeffs, *x)).collect();
+    assert_eq!(poly_eval_batch(&coeffs, &points), want);
+
+    assert!(poly_eval_batch::<Field64>(&[], &[]).is_empty());
+}
+
+#[test]
+fn test_poly_rem() {
+    use crate::field::{rand, Field64};
+
+    for (a_len, b_len) in [(10, 3), (300, 20), (1000, 500), (2, 5)].iter() {
+        let a = rand::<Field64>(*a_len).unwrap();
+        let mut b = rand::<Field64>(*b_len).unwrap();
+        b[*b_len - 1] = Field64::one();
+
+        // Long division.
+        let mut want = a.clone();
+        for i in (b.len() - 1..a.len()).rev() {
+            let c = want[i];
+            for j in 0..b.len() {
+                want[i + 1 - b.len() + j] -= c * b[j];
+            }
         }
-        assert_eq!(should_be, poly[i]);
+        want.truncate(std::cmp::min(a.len(), b.len() - 1));
+
+        assert_eq!(poly_rem(&a, &b), want);
+    }
+}
+
+#[test]
+fn test_barycentric() {
+    use crate::field::{rand, Field64};
+
+    let p = Polynomial::new(rand::<Field64>(10).unwrap());
+    let points = rand::<Field64>(10).unwrap();
+    let values: Vec<Field64> = points.iter().map(|x| p.eval(*x)).collect();
+    let domain = BarycentricDomain::new(&points).unwrap();
+    for x in rand::<Field64>(5).unwrap().iter().chain(points.iter()) {
+        assert_eq!(domain.eval(&values, *x).unwrap(), p.eval(*x));
+    }
+
+    let domain = BarycentricDomain::<Field64>::roots_of_unity(16).unwrap();
+    let values: Vec<Field64> = domain.points().iter().map(|x| p.eval(*x)).collect();
+    let x = rand::<Field64>(1).unwrap()[0];
+    assert_eq!(domain.eval(&values, x).unwrap(), p.eval(x));
+    // The closed-form weights match the general ones.
+    assert_eq!(
+        BarycentricDomain::new(domain.points()).unwrap().weights(),
+        domain.weights()
+    );
+
+    assert_matches::assert_matches!(
+        BarycentricDomain::new(&[points[0], points[1], points[0]]),
+        Err(PolynomialError::DuplicatePoint)
+    );
+    assert_matches::assert_matches!(
+        domain.eval(&values[..3], x),
+        Err(PolynomialError::InputSizeMismatch)
+    );
+    assert_matches::assert_matches!(
+        BarycentricDomain::<Field64>::roots_of_unity(12),
+        Err(PolynomialError::Fft(FftError::SizeInvalid))
+    );
+}
+
+#[test]
+fn test_poly_evaluator() {
+    use crate::field::{rand, Field64};
+
+    let x = rand::<Field64>(1).unwrap()[0];
+    let mut evaluator = PolyEvaluator::new(x);
+    assert_eq!(evaluator.point(), x);
+    for len in [0, 1, 5, 100, 3].iter() {
+        let coeffs = rand::<Field64>(*len).unwrap();
+        assert_eq!(evaluator.eval(&coeffs), poly_eval(&coeffs, x));
     }
+
+    let coeffs = rand::<Field64>(10).unwrap();
+    assert_eq!(
+        PolyEvaluator::with_capacity(x, 10).eval(&coeffs),
+        poly_eval(&coeffs, x)
+    );
+}
+
+#[test]
+fn test_poly_utils() {
+    use crate::field::{rand, Field64};
+
+    let one = Field64::one();
+    let zero = Field64::zero();
+    let two = one + one;
+
+    let mut p = vec![one, two, zero, zero];
+    poly_trim(&mut p);
+    assert_eq!(p, vec![one, two]);
+    let mut p = vec![zero, zero];
+    poly_trim(&mut p);
+    assert_eq!(p, vec![zero]);
+    let mut p = Vec::new();
+    poly_trim::<Field64>(&mut p);
+    assert_eq!(p, vec![zero]);
+
+    // d/dx (1 + 2x + x^2 + 2x^3) = 2 + 2x + 6x^2
+    assert_eq!(
+        poly_deriv(&[one, two, one, two]),
+        vec![two, two, Field64::from(6)]
+    );
+    assert_eq!(poly_deriv(&[two]), vec![zero]);
+    assert_eq!(poly_deriv::<Field64>(&[]), vec![zero]);
+
+    let p = rand::<Field64>(10).unwrap();
+    let c = rand::<Field64>(1).unwrap()[0];
+    let a = rand::<Field64>(1).unwrap()[0];
+    let x = rand::<Field64>(1).unwrap()[0];
+    assert_eq!(poly_eval(&poly_scale(&p, c), x), c * poly_eval(&p, x));
+    assert_eq!(poly_eval(&poly_shift(&p, a), x), poly_eval(&p, x + a));
+    assert_eq!(poly_shift(&p, zero), p);
+    assert!(poly_shift::<Field64>(&[], a).is_empty());
+
+    // The derivative satisfies the product rule.
+    let q = rand::<Field64>(7).unwrap();
+    let lhs = poly_eval(&poly_deriv(&poly_mul(&p, &q)), x);
+    let rhs = poly_eval(&poly_deriv(&p), x) * poly_eval(&q, x)
+        + poly_eval(&p, x) * poly_eval(&poly_deriv(&q), x);
+    assert_eq!(lhs, rhs);
 }
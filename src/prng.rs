@@ -1,106 +1,645 @@
 // Copyright (c) 2020 Apple Inc.
 // SPDX-License-Identifier: MPL-2.0
 
+//! A pseudorandom generator of field elements. The output is derived from a short seed by a
+//! [`SeedStream`], which is a type parameter of [`Prng`] so that applications can choose the
+//! construction mandated by their deployment.
+
+use super::codec::{CodecError, Decode, Encode};
 use super::field::{FieldElement, FieldError};
 use aes::{
-    cipher::{generic_array::GenericArray, FromBlockCipher, NewBlockCipher, StreamCipher},
+    cipher::{
+        generic_array::GenericArray, FromBlockCipher, NewBlockCipher, NewCipher, StreamCipher,
+    },
     Aes128, Aes128Ctr,
 };
+use chacha20::ChaCha20Legacy;
 use getrandom::getrandom;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake128, Shake128Reader,
+};
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Cursor, Read, Write};
 use std::marker::PhantomData;
 
 const BLOCK_SIZE: usize = 16;
 const DEFAULT_BUFFER_SIZE_IN_ELEMENTS: usize = 128;
 const MAXIMUM_BUFFER_SIZE_IN_ELEMENTS: usize = 4096;
+/// The length of a seed in bytes.
 pub const SEED_LENGTH: usize = 2 * BLOCK_SIZE;
 
-pub(crate) fn secret_share<F: FieldElement>(share1: &mut [F]) -> Result<Vec<u8>, getrandom::Error> {
-    let mut seed = vec![0; SEED_LENGTH];
-    getrandom(seed.as_mut_slice())?;
-
-    // get prng array
-    let data: Vec<F> = Prng::new_with_seed_and_optional_length(&seed, Some(share1.len())).collect();
-
-    // secret share
-    for (s1, d) in share1.iter_mut().zip(data.iter()) {
-        *s1 -= *d;
-    }
-
-    Ok(seed)
-}
-
+#[cfg(test)]
 pub(crate) fn extract_share_from_seed<F: FieldElement>(
     length: usize,
     seed: &[u8],
 ) -> Result<Vec<F>, PrngError> {
-    if seed.len() != 2 * BLOCK_SIZE {
-        return Err(PrngError::SeedLen);
+    Ok(Prng::<F>::new_with_seed_and_optional_length(seed, Some(length))?.collect())
+}
+
+/// The length of a key for [`Prng::from_key_and_nonce`] in bytes.
+pub const KEY_LENGTH: usize = 16;
+
+// Returns HMAC-SHA256 keyed by `key` over the length of `dst` as a 64-bit big-endian integer,
+// followed by `dst` and `binder`.
+fn derive_seed(key: &[u8], dst: &[u8], binder: &[u8]) -> Seed<SEED_LENGTH> {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key);
+    let mut ctx = ring::hmac::Context::with_key(&key);
+    // Prefix the tag with its length so that (dst, binder) pairs are encoded unambiguously.
+    ctx.update(&(dst.len() as u64).to_be_bytes());
+    ctx.update(dst);
+    ctx.update(binder);
+    Seed::read_from(ctx.sign().as_ref()).unwrap()
+}
+
+/// The number of elements generated from each substream by [`expand_partitioned`]. This is part of
+/// the definition of the output and must not change.
+pub const PARTITION_LEN: usize = 1 << 16;
+
+/// Expands `seed` into `out`, generating each block of [`PARTITION_LEN`] elements from its own
+/// substream. If the `multithreaded` feature is enabled, the blocks are generated in parallel on
+/// the rayon thread pool.
+///
+/// The substream for block `i` is [`Prng::derive`] applied to `seed` with a fixed tag and the
+/// binder `i` as a 64-bit big-endian integer. The output therefore only depends on the seed and
+/// the seed stream `S`, not on the number of threads, but it differs from the sequence generated
+/// by [`Prng::from_seed`] for the same seed.
+pub fn expand_partitioned<F: FieldElement, S: SeedStream>(seed: &Seed<SEED_LENGTH>, out: &mut [F]) {
+    let expand = |(i, block): (usize, &mut [F])| {
+        Prng::<F, S>::derive(seed, b"prio expand partitioned", &(i as u64).to_be_bytes())
+            .fill(block);
+    };
+
+    #[cfg(feature = "multithreaded")]
+    {
+        use rayon::prelude::*;
+
+        out.par_chunks_mut(PARTITION_LEN)
+            .enumerate()
+            .for_each(expand);
     }
 
-    Ok(Prng::new_with_seed_and_optional_length(seed, Some(length)).collect())
+    #[cfg(not(feature = "multithreaded"))]
+    out.chunks_mut(PARTITION_LEN).enumerate().for_each(expand);
 }
 
 /// Errors propagated by methods in this module.
 #[derive(Debug, PartialEq, thiserror::Error)]
-pub(crate) enum PrngError {
+pub enum PrngError {
+    /// The seed does not have the expected length.
     #[error("invalid seed length")]
     SeedLen,
 }
 
-/// This type implements an iterator that generates a pseudorandom sequence of field elements. The
-/// sequence is derived from the key stream of AES-128 in CTR mode with a random IV.
+/// A secret seed of `L` bytes, from which a pseudorandom sequence is derived.
+///
+/// Seeds are compared in constant time. If the `zeroize` feature is enabled, a seed is erased when
+/// it is dropped.
+#[derive(Clone)]
+pub struct Seed<const L: usize>([u8; L]);
+
+impl<const L: usize> Seed<L> {
+    /// Generates a seed using the system's randomness.
+    pub fn generate() -> Result<Self, getrandom::Error> {
+        let mut seed = [0; L];
+        getrandom(&mut seed)?;
+        Ok(Self(seed))
+    }
+
+    /// Draws a seed from `rng`.
+    pub fn from_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self, rand_core::Error> {
+        let mut seed = [0; L];
+        rng.try_fill_bytes(&mut seed)?;
+        Ok(Self(seed))
+    }
+
+    /// Returns the bytes of the seed.
+    pub fn as_bytes(&self) -> &[u8; L] {
+        &self.0
+    }
+
+    /// Writes the seed to the end of `bytes`. Exactly `L` bytes are written.
+    pub fn append_to(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.0);
+    }
+
+    /// Decodes a seed from `bytes`, which must be exactly `L` bytes long.
+    pub fn read_from(bytes: &[u8]) -> Result<Self, PrngError> {
+        if bytes.len() != L {
+            return Err(PrngError::SeedLen);
+        }
+        let mut seed = [0; L];
+        seed.copy_from_slice(bytes);
+        Ok(Self(seed))
+    }
+
+    /// Writes the seed to `writer`.
+    pub fn encode_into<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.0)
+    }
+
+    /// Reads the next `L` bytes from `reader` as a seed.
+    pub fn decode_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut seed = [0; L];
+        reader.read_exact(&mut seed)?;
+        Ok(Self(seed))
+    }
+}
+
+impl<const L: usize> Encode for Seed<L> {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.0);
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(L)
+    }
+}
+
+impl<const L: usize> Decode for Seed<L> {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        Ok(Self::decode_from(bytes)?)
+    }
+}
+
+impl<const L: usize> From<[u8; L]> for Seed<L> {
+    fn from(seed: [u8; L]) -> Self {
+        Self(seed)
+    }
+}
+
+impl<const L: usize> AsRef<[u8]> for Seed<L> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const L: usize> ConstantTimeEq for Seed<L> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl<const L: usize> PartialEq for Seed<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<const L: usize> Eq for Seed<L> {}
+
+impl<const L: usize> Debug for Seed<L> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Don't print the seed, which is secret.
+        f.debug_tuple("Seed").finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const L: usize> Zeroize for Seed<L> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const L: usize> Drop for Seed<L> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const L: usize> ZeroizeOnDrop for Seed<L> {}
+
+/// How a [`Prng`] maps the output of its seed stream to field elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sampling {
+    /// Each candidate element is read from `F::BYTES` bytes of the stream and rejected if it is not
+    /// less than the modulus. The output is exactly uniform, but the number of bytes consumed per
+    /// element varies. This is the default.
+    Rejection,
+    /// Each element is read from `2 * F::BYTES` bytes of the stream and reduced modulo `p`, as by
+    /// [`FieldElement::from_random_rejection_free`]. Element `i` is always derived from the same
+    /// bytes of the stream, and the work per element is constant. The output is statistically
+    /// close to uniform.
+    WideReduction,
+}
+
+/// A deterministic stream of pseudorandom bytes derived from a seed. A [`Prng`] maps the stream to
+/// field elements by rejection sampling, so the choice of stream determines the sequence of field
+/// elements generated from a seed.
+pub trait SeedStream {
+    /// Constructs the stream derived from `seed`.
+    fn from_seed(seed: &Seed<SEED_LENGTH>) -> Self;
+
+    /// Overwrites `buf` with the next `buf.len()` bytes of the stream.
+    fn fill(&mut self, buf: &mut [u8]);
+}
+
+/// The key stream of AES-128 in CTR mode, keyed by the first half of the seed, with the second half
+/// of the seed as the initial counter block. This is the stream used by Prio v2 and the default for
+/// [`Prng`].
 #[derive(Debug)]
-pub(crate) struct Prng<F: FieldElement> {
+pub struct SeedStreamAes128(Aes128Ctr);
+
+impl SeedStream for SeedStreamAes128 {
+    fn from_seed(seed: &Seed<SEED_LENGTH>) -> Self {
+        let key = GenericArray::from_slice(&seed.as_bytes()[..BLOCK_SIZE]);
+        let iv = GenericArray::from_slice(&seed.as_bytes()[BLOCK_SIZE..]);
+        Self(Aes128Ctr::from_block_cipher(Aes128::new(key), iv))
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for b in buf.iter_mut() {
+            *b = 0;
+        }
+        self.0.apply_keystream(buf);
+    }
+}
+
+/// The implementation of AES used by [`SeedStreamAes128`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesBackend {
+    /// The AES-NI instructions of x86 processors.
+    AesNi,
+    /// The portable, constant-time bitsliced implementation.
+    Software,
+}
+
+/// Returns the implementation of AES that [`SeedStreamAes128`] uses on this machine. This is
+/// intended for diagnostics.
+///
+/// The `aes` crate detects hardware support at runtime rather than at compile time, so the same
+/// binary uses AES-NI on processors that support it and falls back to the software implementation
+/// on those that don't. The output of the stream is the same either way.
+pub fn aes_backend() -> AesBackend {
+    // Mirrors the check performed by `Aes128Ctr`.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("aes") && is_x86_feature_detected!("ssse3") {
+            return AesBackend::AesNi;
+        }
+    }
+    AesBackend::Software
+}
+
+/// The output of the SHAKE128 extendable-output function on input of the seed.
+#[derive(Clone)]
+pub struct SeedStreamShake128(Shake128Reader);
+
+impl SeedStream for SeedStreamShake128 {
+    fn from_seed(seed: &Seed<SEED_LENGTH>) -> Self {
+        Self::from_input(seed.as_bytes())
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        XofReader::read(&mut self.0, buf);
+    }
+}
+
+impl SeedStreamShake128 {
+    // Constructs the output of SHAKE128 on input of `input`, which, unlike a seed, may have any
+    // length.
+    pub(crate) fn from_input(input: &[u8]) -> Self {
+        let mut xof = Shake128::default();
+        xof.update(input);
+        Self(xof.finalize_xof())
+    }
+}
+
+impl Debug for SeedStreamShake128 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Don't print the XOF state, from which the rest of the stream can be computed.
+        f.debug_tuple("SeedStreamShake128").finish()
+    }
+}
+
+/// The key stream of ChaCha20 keyed by the seed, with an all-zero nonce. This is faster than
+/// [`SeedStreamAes128`] on platforms without hardware support for AES, such as older ARM cores and
+/// WebAssembly runtimes.
+///
+/// The stream uses the original construction of ChaCha20 with a 64-bit block counter, so it does
+/// not end after 2^32 blocks. Up to that point it is the same as the key stream of the RFC 8439
+/// construction with an all-zero nonce.
+pub struct SeedStreamChaCha20(ChaCha20Legacy);
+
+impl SeedStream for SeedStreamChaCha20 {
+    fn from_seed(seed: &Seed<SEED_LENGTH>) -> Self {
+        let key = GenericArray::from_slice(seed.as_bytes());
+        Self(ChaCha20Legacy::new(key, &GenericArray::default()))
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for b in buf.iter_mut() {
+            *b = 0;
+        }
+        self.0.apply_keystream(buf);
+    }
+}
+
+impl Debug for SeedStreamChaCha20 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Don't print the key.
+        f.debug_tuple("SeedStreamChaCha20").finish()
+    }
+}
+
+/// This type implements an iterator that generates a pseudorandom sequence of field elements. The
+/// sequence is derived from a seed using the seed stream `S`, which by default is the key stream of
+/// AES-128 in CTR mode.
+pub struct Prng<F: FieldElement, S: SeedStream = SeedStreamAes128> {
     phantom: PhantomData<F>,
-    cipher: Aes128Ctr,
+    seed: Seed<SEED_LENGTH>,
+    stream: S,
     length: Option<usize>,
     buffer: Vec<u8>,
+    // The size of the buffer in bytes, a multiple of `F::BYTES`.
+    buffer_len: usize,
+    sampling: Sampling,
     buffer_index: usize,
     output_written: usize,
 }
 
-impl<F: FieldElement> Prng<F> {
+impl<F: FieldElement, S: SeedStream> Debug for Prng<F, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Don't print the state of the stream or the buffered output, which are secret.
+        f.debug_struct("Prng")
+            .field("length", &self.length)
+            .field("sampling", &self.sampling)
+            .field("output_written", &self.output_written)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FieldElement, S: SeedStream> Prng<F, S> {
     /// Generates a seed and constructs an iterator over an infinite sequence of pseudorandom field
     /// elements.
-    pub(crate) fn new() -> Result<Self, getrandom::Error> {
-        let mut seed = [0; SEED_LENGTH];
-        getrandom(&mut seed)?;
-        Ok(Self::new_with_seed_and_optional_length(&seed, None))
+    pub fn new() -> Result<Self, getrandom::Error> {
+        Ok(Self::from_seed_with_optional_length(
+            Seed::generate()?,
+            None,
+        ))
     }
 
     /// Generates a seed and constructs an iterator over a pseudorandom sequence of field elements
-    /// of length `length`.
-    pub(crate) fn new_with_length(length: usize) -> Result<Self, getrandom::Error> {
-        let mut seed = [0; SEED_LENGTH];
-        getrandom(&mut seed)?;
-        Ok(Self::new_with_seed_and_optional_length(&seed, Some(length)))
+    /// of length `length`. Unlike [`Prng`], the returned iterator implements
+    /// [`ExactSizeIterator`].
+    pub fn new_with_length(length: usize) -> Result<BoundedPrng<F, S>, getrandom::Error> {
+        Ok(BoundedPrng {
+            prng: Self::from_seed_with_optional_length(Seed::generate()?, Some(length)),
+            remaining: length,
+        })
+    }
+
+    /// Draws a seed from `rng` and constructs an iterator over a pseudorandom sequence of field
+    /// elements. If `length` is `None`, the sequence is infinite.
+    pub fn from_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        length: Option<usize>,
+    ) -> Result<Self, rand_core::Error> {
+        Ok(Self::from_seed_with_optional_length(
+            Seed::from_rng(rng)?,
+            length,
+        ))
+    }
+
+    /// Constructs an iterator over an infinite sequence of pseudorandom field elements derived from
+    /// `seed`. The sequence only depends on the seed, so two parties holding the same seed can
+    /// expand it into the same vector, e.g., a secret share.
+    pub fn from_seed(seed: &Seed<SEED_LENGTH>) -> Self {
+        Self::from_seed_with_optional_length(seed.clone(), None)
+    }
+
+    /// Derives a sequence from `seed` that is specific to the domain-separation tag `dst` and the
+    /// context string `binder`. Sequences derived from the same seed with distinct tags or binders
+    /// are independent, so a single seed can supply the randomness for several purposes, e.g.,
+    /// proof randomness, joint randomness and share expansion, without any of them reusing the
+    /// output of another.
+    ///
+    /// The derived seed is HMAC-SHA256 keyed by `seed` over the length of `dst` as a 64-bit
+    /// big-endian integer, followed by `dst` and `binder`. [`Prng::seed`] returns the derived
+    /// seed.
+    pub fn derive(seed: &Seed<SEED_LENGTH>, dst: &[u8], binder: &[u8]) -> Self {
+        Self::from_seed_with_optional_length(derive_seed(seed.as_bytes(), dst, binder), None)
     }
 
-    fn new_with_seed_and_optional_length(seed: &[u8], length: Option<usize>) -> Self {
-        let key = GenericArray::from_slice(&seed[..BLOCK_SIZE]);
-        let iv = GenericArray::from_slice(&seed[BLOCK_SIZE..]);
-        let mut cipher = Aes128Ctr::from_block_cipher(Aes128::new(&key), &iv);
+    /// Constructs an iterator over an infinite sequence of pseudorandom field elements derived from
+    /// a long-term `key` and a `nonce`, e.g., a per-report nonce. The same key can be used with
+    /// many nonces: the sequences for distinct nonces are independent, so unlike reusing a seed,
+    /// this never repeats the output of the seed stream.
+    ///
+    /// The seed of the sequence is HMAC-SHA256 keyed by `key` over a fixed tag and the nonce, as in
+    /// [`Prng::derive`].
+    pub fn from_key_and_nonce(key: &Seed<KEY_LENGTH>, nonce: &[u8]) -> Self {
+        Self::from_seed_with_optional_length(
+            derive_seed(key.as_bytes(), b"prio key and nonce", nonce),
+            None,
+        )
+    }
+
+    /// Returns the seed from which the sequence is derived. For a generator constructed with
+    /// [`Prng::new`] or one of its variants, this is the seed drawn from the system's randomness.
+    pub fn seed(&self) -> &Seed<SEED_LENGTH> {
+        &self.seed
+    }
+
+    /// Constructs an iterator over a pseudorandom sequence of field elements derived from `seed`,
+    /// which must be `SEED_LENGTH` bytes long. If `length` is `None`, the sequence is infinite.
+    pub fn new_with_seed_and_optional_length(
+        seed: &[u8],
+        length: Option<usize>,
+    ) -> Result<Self, PrngError> {
+        Ok(Self::from_seed_with_optional_length(
+            Seed::read_from(seed)?,
+            length,
+        ))
+    }
+
+    fn from_seed_with_optional_length(seed: Seed<SEED_LENGTH>, length: Option<usize>) -> Self {
+        let mut stream = S::from_seed(&seed);
 
         let buf_len_in_elems = match length {
             Some(length) => std::cmp::min(length + 1, MAXIMUM_BUFFER_SIZE_IN_ELEMENTS),
             None => DEFAULT_BUFFER_SIZE_IN_ELEMENTS,
         };
-        let mut buffer = vec![0; buf_len_in_elems * F::BYTES];
-        cipher.apply_keystream(&mut buffer);
+        let buffer_len = buf_len_in_elems * F::BYTES;
+        let mut buffer = vec![0; buffer_len];
+        stream.fill(&mut buffer);
 
         Self {
             phantom: PhantomData::<F>,
-            cipher,
+            seed,
+            stream,
             length,
             buffer,
+            buffer_len,
+            sampling: Sampling::Rejection,
             buffer_index: 0,
             output_written: 0,
         }
     }
+
+    /// Sets the size of the internal buffer to `len` elements, or to one element if `len` is 0. The
+    /// buffer is refilled from the seed stream whenever it has been used up, so a small buffer
+    /// saves memory on constrained devices and a large one speeds up the expansion of long
+    /// sequences. The output does not depend on the size of the buffer.
+    ///
+    /// By default, the buffer holds one more element than the length of the sequence, up to 4096
+    /// elements, or 128 elements if the sequence is infinite.
+    pub fn set_buffer_len(&mut self, len: usize) {
+        self.buffer_len = std::cmp::max(len, 1) * F::BYTES;
+
+        // Move the unused output into a buffer of the new size.
+        let unused = self.buffer.len() - self.buffer_index;
+        let mut buffer = vec![0; std::cmp::max(self.buffer_len, unused)];
+        buffer[..unused].copy_from_slice(&self.buffer[self.buffer_index..]);
+        self.stream.fill(&mut buffer[unused..]);
+        #[cfg(feature = "zeroize")]
+        self.buffer.zeroize();
+        self.buffer = buffer;
+        self.buffer_index = 0;
+    }
+
+    /// Sets how the output of the seed stream is mapped to field elements. The mode applies to the
+    /// elements generated after this call.
+    pub fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+    }
+
+    // Refills the buffer with the next output of the seed stream, making it at least `min_len`
+    // bytes long. Output that has not been used yet, such as the first few bytes of an element
+    // after a call to `fill_bytes()`, is moved to the front of the buffer; there must be fewer
+    // than `min_len` such bytes.
+    fn refill(&mut self, min_len: usize) {
+        let unused = self.buffer.len() - self.buffer_index;
+        self.buffer.copy_within(self.buffer_index.., 0);
+        self.buffer
+            .resize(std::cmp::max(self.buffer_len, min_len), 0);
+        self.stream.fill(&mut self.buffer[unused..]);
+        self.buffer_index = 0;
+    }
+
+    /// Returns the number of elements remaining in the sequence, or `None` if the sequence is
+    /// infinite.
+    pub fn remaining(&self) -> Option<usize> {
+        self.length
+            .map(|length| length.saturating_sub(self.output_written))
+    }
+
+    /// Overwrites `out` with the next `out.len()` elements of the sequence and returns the number
+    /// of elements written. This is less than `out.len()` only if the sequence has a length and
+    /// fewer elements remain, in which case the rest of `out` is left unchanged. Unlike collecting
+    /// the iterator, this does not allocate.
+    pub fn fill(&mut self, out: &mut [F]) -> usize {
+        for (i, x) in out.iter_mut().enumerate() {
+            match self.next() {
+                Some(y) => *x = y,
+                None => return i,
+            }
+        }
+        out.len()
+    }
+
+    /// Overwrites `out` with the next `out.len()` bytes of the seed stream. The bytes are consumed
+    /// from the same position as field elements, so subsequent elements are derived from the
+    /// output following these bytes. Bytes do not count towards the length of the sequence.
+    pub fn fill_bytes(&mut self, out: &mut [u8]) {
+        let mut written = 0;
+        while written < out.len() {
+            if self.buffer_index == self.buffer.len() {
+                self.refill(1);
+            }
+            let n = std::cmp::min(out.len() - written, self.buffer.len() - self.buffer_index);
+            out[written..written + n]
+                .copy_from_slice(&self.buffer[self.buffer_index..self.buffer_index + n]);
+            self.buffer_index += n;
+            written += n;
+        }
+    }
+
+    /// Derives a child sequence labeled by `label`. This consumes `SEED_LENGTH` bytes of the seed
+    /// stream, so repeated forks with the same label yield independent children, and the rest of
+    /// this sequence is independent of the child. Since forking is deterministic, a tree of
+    /// sequences, e.g., one per round and per aggregator, can be derived from a single seed.
+    ///
+    /// The child's seed is HMAC-SHA256 keyed by the consumed bytes over a fixed tag and the label,
+    /// as in [`Prng::derive`].
+    pub fn fork(&mut self, label: &[u8]) -> Self {
+        let mut key = [0; SEED_LENGTH];
+        self.fill_bytes(&mut key);
+        let child =
+            Self::from_seed_with_optional_length(derive_seed(&key, b"prio fork", label), None);
+        #[cfg(feature = "zeroize")]
+        key.zeroize();
+        child
+    }
+
+    /// Returns the next `len` bytes of the seed stream, e.g., for use as a nonce or a symmetric key
+    /// derived from the same seed as the field elements. See [`Prng::fill_bytes`].
+    pub fn next_bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut out = vec![0; len];
+        self.fill_bytes(&mut out);
+        out
+    }
+}
+
+// A `Prng` can also be used as a source of random bytes for generic code, e.g., for shuffling or
+// sampling from other distributions. The bytes are read from the seed stream as by
+// `Prng::fill_bytes()`.
+impl<F: FieldElement, S: SeedStream> RngCore for Prng<F, S> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+        Prng::fill_bytes(self, &mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+        Prng::fill_bytes(self, &mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Prng::fill_bytes(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        Prng::fill_bytes(self, dest);
+        Ok(())
+    }
+}
+
+impl<F: FieldElement, S: SeedStream> CryptoRng for Prng<F, S> {}
+
+impl<F: FieldElement, S: SeedStream> SeedableRng for Prng<F, S> {
+    type Seed = [u8; SEED_LENGTH];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Prng::from_seed(&Seed::from(seed))
+    }
+}
+
+// The seed and the buffer, which holds output of the seed stream that has not yet been used, would
+// allow an attacker to recover elements of a secret share. Note that the expanded AES key held by
+// `SeedStreamAes128` is not erased, as the `aes` crate does not support this.
+#[cfg(feature = "zeroize")]
+impl<F: FieldElement, S: SeedStream> Drop for Prng<F, S> {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+        self.buffer.zeroize();
+    }
 }
 
-impl<F: FieldElement> Iterator for Prng<F> {
+#[cfg(feature = "zeroize")]
+impl<F: FieldElement, S: SeedStream> ZeroizeOnDrop for Prng<F, S> {}
+
+impl<F: FieldElement, S: SeedStream> Iterator for Prng<F, S> {
     type Item = F;
 
     fn next(&mut self) -> Option<F> {
@@ -110,48 +649,114 @@ impl<F: FieldElement> Iterator for Prng<F> {
             }
         }
 
+        let chunk_len = match self.sampling {
+            Sampling::Rejection => F::BYTES,
+            Sampling::WideReduction => 2 * F::BYTES,
+        };
         loop {
             // Seek to the next chunk of the buffer that encodes an element of F.
-            for i in (self.buffer_index..self.buffer.len()).step_by(F::BYTES) {
-                let j = i + F::BYTES;
-                if let Some(x) = match F::try_from_random(&self.buffer[i..j]) {
-                    Ok(x) => Some(x),
-                    Err(FieldError::FromBytesModulusOverflow) => None, // reject this sample
+            while self.buffer_index + chunk_len <= self.buffer.len() {
+                let i = self.buffer_index;
+                self.buffer_index += chunk_len;
+                let chunk = &self.buffer[i..self.buffer_index];
+                let result = match self.sampling {
+                    Sampling::Rejection => F::try_from_random(chunk),
+                    Sampling::WideReduction => F::from_random_rejection_free(chunk),
+                };
+                match result {
+                    Ok(x) => {
+                        self.output_written += 1;
+                        return Some(x);
+                    }
+                    Err(FieldError::FromBytesModulusOverflow) => (), // reject this sample
                     Err(err) => panic!("unexpected error: {}", err),
-                } {
-                    // Set the buffer index to the next chunk.
-                    self.buffer_index = j;
-                    self.output_written += 1;
-                    return Some(x);
                 }
             }
 
             // Refresh buffer with the next chunk of PRG output.
-            for b in &mut self.buffer {
-                *b = 0;
-            }
-            self.cipher.apply_keystream(&mut self.buffer);
-            self.buffer_index = 0;
+            self.refill(chunk_len);
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining() {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// An iterator over a pseudorandom sequence of field elements of a fixed length, constructed with
+/// [`Prng::new_with_length`]. Since the number of remaining elements is always known, this
+/// implements [`ExactSizeIterator`].
+pub struct BoundedPrng<F: FieldElement, S: SeedStream = SeedStreamAes128> {
+    prng: Prng<F, S>,
+    remaining: usize,
 }
 
+impl<F: FieldElement, S: SeedStream> Debug for BoundedPrng<F, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedPrng")
+            .field("prng", &self.prng)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl<F: FieldElement, S: SeedStream> BoundedPrng<F, S> {
+    /// Returns the number of elements remaining in the sequence.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the seed from which the sequence is derived. See [`Prng::seed`].
+    pub fn seed(&self) -> &Seed<SEED_LENGTH> {
+        self.prng.seed()
+    }
+
+    /// Overwrites `out` with the next `out.len()` elements of the sequence and returns the number
+    /// of elements written. This is less than `out.len()` only if fewer elements remain, in which
+    /// case the rest of `out` is left unchanged. See [`Prng::fill`].
+    pub fn fill(&mut self, out: &mut [F]) -> usize {
+        let written = self.prng.fill(out);
+        self.remaining -= written;
+        written
+    }
+}
+
+impl<F: FieldElement, S: SeedStream> Iterator for BoundedPrng<F, S> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let x = self.prng.next()?;
+        self.remaining -= 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<F: FieldElement, S: SeedStream> ExactSizeIterator for BoundedPrng<F, S> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::field::Field32;
+    use crate::field::{Field32, Field64, FieldElementWithInteger};
+    use crate::util::tests::from_hex;
 
     #[test]
     fn secret_sharing() {
-        let mut data = vec![Field32::from(0); 123];
-        data[3] = 23.into();
+        let mut data = vec![Field32::from_reduced(0); 123];
+        data[3] = Field32::from_reduced(23);
 
         let data_clone = data.clone();
 
-        let seed = secret_share(&mut data).unwrap();
+        let seeds = crate::field::split_compressed_in_place(&mut data, 1).unwrap();
         assert_ne!(data, data_clone);
 
-        let share2 = extract_share_from_seed(data.len(), &seed).unwrap();
+        let share2 = extract_share_from_seed(data.len(), seeds[0].as_bytes()).unwrap();
 
         assert_eq!(data.len(), share2.len());
 
@@ -163,6 +768,438 @@ mod tests {
         assert_eq!(data, data_clone);
     }
 
+    #[test]
+    fn seed() {
+        let seed = Seed::<SEED_LENGTH>::generate().unwrap();
+        assert_ne!(seed, Seed::generate().unwrap());
+        assert_eq!(seed, seed.clone());
+        assert!(bool::from(seed.ct_eq(&seed.clone())));
+
+        let mut bytes = Vec::new();
+        seed.append_to(&mut bytes);
+        assert_eq!(bytes, seed.as_bytes());
+        assert_eq!(Seed::read_from(&bytes).unwrap(), seed);
+        assert_eq!(
+            Seed::<SEED_LENGTH>::read_from(&bytes[1..]),
+            Err(PrngError::SeedLen)
+        );
+
+        let mut encoded = Vec::new();
+        seed.encode_into(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+        assert_eq!(
+            Seed::<SEED_LENGTH>::decode_from(&mut &encoded[..]).unwrap(),
+            seed
+        );
+        assert!(Seed::<SEED_LENGTH>::decode_from(&mut &encoded[1..]).is_err());
+
+        // The seed is not printed.
+        assert_eq!(format!("{:?}", seed), "Seed");
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn seed_zeroize() {
+        let mut seed = Seed::from([1u8; 16]);
+        seed.zeroize();
+        assert_eq!(seed.as_bytes(), &[0; 16]);
+    }
+
+    #[test]
+    fn seed_export() {
+        let mut prng = Prng::<Field32>::new().unwrap();
+        let seed = prng.seed().clone();
+        let want: Vec<Field32> = prng.by_ref().take(100).collect();
+        assert_eq!(prng.seed(), &seed);
+
+        let got: Vec<Field32> = Prng::<Field32>::from_seed(&seed).take(100).collect();
+        assert_eq!(got, want);
+        assert_eq!(
+            got,
+            extract_share_from_seed::<Field32>(100, seed.as_bytes()).unwrap()
+        );
+
+        let mut other_seed = *seed.as_bytes();
+        other_seed[0] ^= 1;
+        let other: Vec<Field32> = Prng::<Field32>::from_seed(&Seed::from(other_seed))
+            .take(100)
+            .collect();
+        assert_ne!(other, want);
+    }
+
+    #[test]
+    fn seed_codec() {
+        let seed = Seed::<SEED_LENGTH>::generate().unwrap();
+        let encoded = seed.get_encoded();
+        assert_eq!(encoded, seed.as_bytes());
+        assert_eq!(Seed::<SEED_LENGTH>::get_decoded(&encoded).unwrap(), seed);
+        assert!(Seed::<SEED_LENGTH>::get_decoded(&encoded[1..]).is_err());
+    }
+
+    #[test]
+    fn test_aes_backend() {
+        let backend = aes_backend();
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(
+            backend == AesBackend::AesNi,
+            is_x86_feature_detected!("aes") && is_x86_feature_detected!("ssse3")
+        );
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        assert_eq!(backend, AesBackend::Software);
+    }
+
+    #[test]
+    fn seed_stream_shake128() {
+        let seed = Seed::from([7; SEED_LENGTH]);
+
+        // The stream is SHAKE128 applied to the seed. (Computed with Python's `hashlib`.)
+        let mut stream = SeedStreamShake128::from_seed(&seed);
+        let mut buf = [0; 8];
+        stream.fill(&mut buf);
+        assert_eq!(buf, [0xac, 0x27, 0x00, 0x81, 0xc3, 0x00, 0xba, 0xb1]);
+
+        // The choice of stream determines the output.
+        let shake: Vec<Field64> = Prng::<Field64, SeedStreamShake128>::from_seed(&seed)
+            .take(100)
+            .collect();
+        let aes: Vec<Field64> = Prng::<Field64>::from_seed(&seed).take(100).collect();
+        assert_ne!(shake, aes);
+
+        // The output is independent of the size of the buffer.
+        let shake_with_length: Vec<Field64> =
+            Prng::<Field64, SeedStreamShake128>::new_with_seed_and_optional_length(
+                seed.as_bytes(),
+                Some(100),
+            )
+            .unwrap()
+            .collect();
+        assert_eq!(shake_with_length, shake);
+
+        // Test vector from NIST.
+        let mut buf = [0; 32];
+        SeedStreamShake128::from_input(b"abc").fill(&mut buf);
+        assert_eq!(
+            buf[..],
+            from_hex("5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8")[..]
+        );
+    }
+
+    #[test]
+    fn seed_stream_chacha20() {
+        let seed = Seed::from([7; SEED_LENGTH]);
+
+        // The stream is the ChaCha20 key stream. (Computed with the Python `cryptography`
+        // package.)
+        let mut stream = SeedStreamChaCha20::from_seed(&seed);
+        let mut buf = vec![0; 160];
+        for chunk in buf.chunks_mut(23) {
+            stream.fill(chunk);
+        }
+        assert_eq!(buf[..8], from_hex("f400927857aaf641")[..]);
+        assert_eq!(
+            buf[120..136],
+            from_hex("bd7460315e81182ac2be6756184ff134")[..]
+        );
+
+        let chacha: Vec<Field64> = Prng::<Field64, SeedStreamChaCha20>::from_seed(&seed)
+            .take(100)
+            .collect();
+        let aes: Vec<Field64> = Prng::<Field64>::from_seed(&seed).take(100).collect();
+        assert_ne!(chacha, aes);
+    }
+
+    #[test]
+    fn derive() {
+        let seed = Seed::from([9; SEED_LENGTH]);
+        let sample = |dst: &[u8], binder: &[u8]| -> Vec<Field64> {
+            Prng::<Field64>::derive(&seed, dst, binder)
+                .take(10)
+                .collect()
+        };
+
+        let proof = sample(b"proof", b"");
+        assert_eq!(proof, sample(b"proof", b""));
+        assert_ne!(proof, sample(b"joint", b""));
+        assert_ne!(proof, sample(b"proof", b"nonce"));
+        assert_ne!(
+            proof,
+            Prng::<Field64>::from_seed(&seed)
+                .take(10)
+                .collect::<Vec<_>>()
+        );
+
+        // The boundary between the tag and the binder is unambiguous.
+        assert_ne!(sample(b"ab", b"c"), sample(b"a", b"bc"));
+
+        // The derivation does not depend on the seed stream, but the stream does.
+        let prng = Prng::<Field64, SeedStreamShake128>::derive(&seed, b"proof", b"");
+        assert_eq!(
+            prng.seed(),
+            Prng::<Field64>::derive(&seed, b"proof", b"").seed()
+        );
+        assert_ne!(prng.take(10).collect::<Vec<_>>(), proof);
+    }
+
+    #[test]
+    fn rng_core() {
+        use rand::seq::SliceRandom;
+
+        let seed = [5; SEED_LENGTH];
+        let mut want = vec![0; 12];
+        SeedStreamAes128::from_seed(&Seed::from(seed)).fill(&mut want);
+
+        let mut rng = <Prng<Field32> as SeedableRng>::from_seed(seed);
+        assert_eq!(rng.next_u32().to_le_bytes(), want[..4]);
+        assert_eq!(rng.next_u64().to_le_bytes(), want[4..12]);
+
+        // Generic code driven by the same seed makes the same choices.
+        let shuffle = |seed| {
+            let mut v: Vec<usize> = (0..50).collect();
+            v.shuffle(&mut <Prng<Field32, SeedStreamChaCha20> as SeedableRng>::from_seed(seed));
+            v
+        };
+        assert_eq!(shuffle(seed), shuffle(seed));
+        assert_ne!(shuffle(seed), (0..50).collect::<Vec<_>>());
+
+        let mut rng = Prng::<Field64>::seed_from_u64(1);
+        let mut bytes = [0; 16];
+        rng.try_fill_bytes(&mut bytes).unwrap();
+        assert_ne!(bytes, [0; 16]);
+    }
+
+    #[test]
+    fn remaining() {
+        let mut prng = Prng::<Field32>::new_with_length(5).unwrap();
+        assert_eq!(prng.remaining(), 5);
+        assert_eq!(prng.len(), 5);
+        prng.next().unwrap();
+        assert_eq!(prng.remaining(), 4);
+        assert_eq!(prng.len(), 4);
+        assert_eq!(prng.size_hint(), (4, Some(4)));
+
+        // Collecting preallocates and yields exactly the remaining elements.
+        let rest: Vec<Field32> = prng.by_ref().collect();
+        assert_eq!(rest.len(), 4);
+        assert_eq!(prng.len(), 0);
+        assert_eq!(prng.next(), None);
+        assert_eq!(prng.len(), 0);
+
+        // A short fill reports how many elements were written.
+        let mut prng = Prng::<Field32>::new_with_length(3).unwrap();
+        let mut out = [Field32::zero(); 5];
+        assert_eq!(prng.fill(&mut out[..2]), 2);
+        assert_eq!(prng.len(), 1);
+        assert_eq!(prng.fill(&mut out), 1);
+        assert_eq!(prng.len(), 0);
+        assert_eq!(out[2..], [Field32::zero(); 3]);
+
+        // The bounded sequence is a prefix of the unbounded one with the same seed.
+        let prng = Prng::<Field32>::new_with_length(10).unwrap();
+        let want: Vec<Field32> = Prng::<Field32>::from_seed(prng.seed()).take(10).collect();
+        assert_eq!(prng.collect::<Vec<_>>(), want);
+
+        let prng = Prng::<Field32>::new().unwrap();
+        assert_eq!(prng.remaining(), None);
+        assert_eq!(prng.size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn test_expand_partitioned() {
+        let seed = Seed::from([4; SEED_LENGTH]);
+        let len = 2 * PARTITION_LEN + 10;
+        let mut got = vec![Field32::zero(); len];
+        expand_partitioned::<Field32, SeedStreamAes128>(&seed, &mut got);
+
+        // Each block is the prefix of its own substream.
+        for (i, block) in got.chunks(PARTITION_LEN).enumerate() {
+            let mut prng = Prng::<Field32>::derive(
+                &seed,
+                b"prio expand partitioned",
+                &(i as u64).to_be_bytes(),
+            );
+            let want: Vec<Field32> = prng.by_ref().take(block.len()).collect();
+            assert_eq!(block, &want[..]);
+        }
+
+        // A shorter output is a prefix of a longer one.
+        let mut short = vec![Field32::zero(); PARTITION_LEN + 1];
+        expand_partitioned::<Field32, SeedStreamAes128>(&seed, &mut short);
+        assert_eq!(short, got[..PARTITION_LEN + 1]);
+
+        let mut other = vec![Field32::zero(); 10];
+        expand_partitioned::<Field32, SeedStreamChaCha20>(&seed, &mut other);
+        assert_ne!(other, got[..10]);
+    }
+
+    #[test]
+    fn buffer_len() {
+        let seed = Seed::from([6; SEED_LENGTH]);
+        let want: Vec<Field64> = Prng::<Field64>::from_seed(&seed).take(1000).collect();
+
+        for len in [0, 1, 3, 100, 5000].iter() {
+            let mut prng = Prng::<Field64>::from_seed(&seed);
+            prng.set_buffer_len(*len);
+            let got: Vec<Field64> = prng.by_ref().take(500).collect();
+            assert_eq!(got, want[..500]);
+            assert_eq!(prng.buffer.len(), std::cmp::max(*len, 1) * Field64::BYTES);
+
+            // The buffer can be resized part way through.
+            prng.set_buffer_len(len + 7);
+            let got: Vec<Field64> = prng.take(500).collect();
+            assert_eq!(got, want[500..]);
+        }
+    }
+
+    #[test]
+    fn bytes_then_elements() {
+        // Elements that straddle the end of the buffer after reading bytes are decoded correctly.
+        let seed = Seed::from([8; SEED_LENGTH]);
+        let mut want = vec![0; 5000];
+        SeedStreamAes128::from_seed(&seed).fill(&mut want);
+
+        let mut prng = Prng::<Field32>::from_seed(&seed);
+        prng.set_buffer_len(4);
+        prng.fill_bytes(&mut [0; 3]);
+        let mut pos = 3;
+        for _ in 0..1000 {
+            let x = prng.next().unwrap();
+            // Skip rejected samples.
+            while Field32::try_from_random(&want[pos..pos + 4]).is_err() {
+                pos += 4;
+            }
+            assert_eq!(x, Field32::try_from_random(&want[pos..pos + 4]).unwrap());
+            pos += 4;
+        }
+    }
+
+    #[test]
+    fn wide_reduction() {
+        fn run<F: FieldElement>() {
+            let seed = Seed::from([2; SEED_LENGTH]);
+            let chunk = 2 * F::BYTES;
+            let mut stream = vec![0; 3 + 50 * chunk];
+            SeedStreamAes128::from_seed(&seed).fill(&mut stream);
+
+            // Element `i` is derived from a fixed range of the stream, regardless of the buffer
+            // size.
+            for buffer_len in [1, 7, 64].iter() {
+                let mut prng = Prng::<F>::from_seed(&seed);
+                prng.set_buffer_len(*buffer_len);
+                prng.set_sampling(Sampling::WideReduction);
+                prng.fill_bytes(&mut [0; 3]);
+                for i in 0..50 {
+                    let want =
+                        F::from_random_rejection_free(&stream[3 + i * chunk..3 + (i + 1) * chunk])
+                            .unwrap();
+                    assert_eq!(prng.next().unwrap(), want);
+                }
+            }
+
+            let rejection: Vec<F> = Prng::<F>::from_seed(&seed).take(10).collect();
+            let mut prng = Prng::<F>::from_seed(&seed);
+            prng.set_sampling(Sampling::WideReduction);
+            assert_ne!(prng.take(10).collect::<Vec<F>>(), rejection);
+        }
+
+        run::<Field32>();
+        run::<Field64>();
+        run::<crate::field::Field128>();
+        run::<crate::field::Field64x2>();
+    }
+
+    #[test]
+    fn key_and_nonce() {
+        let key = Seed::<KEY_LENGTH>::from([1; KEY_LENGTH]);
+        let sample = |key: &Seed<KEY_LENGTH>, nonce: &[u8]| -> Vec<Field64> {
+            Prng::<Field64>::from_key_and_nonce(key, nonce)
+                .take(10)
+                .collect()
+        };
+
+        let report0 = sample(&key, b"report 0");
+        assert_eq!(report0, sample(&key, b"report 0"));
+        assert_ne!(report0, sample(&key, b"report 1"));
+        assert_ne!(report0, sample(&Seed::from([2; KEY_LENGTH]), b"report 0"));
+    }
+
+    #[test]
+    fn fork() {
+        let seed = Seed::from([10; SEED_LENGTH]);
+        let sample = |prng: &mut Prng<Field64>| -> Vec<Field64> { prng.take(10).collect() };
+
+        let mut parent = Prng::<Field64>::from_seed(&seed);
+        let mut round0 = parent.fork(b"round");
+        let mut round1 = parent.fork(b"round");
+        let rest = sample(&mut parent);
+
+        // Forking is deterministic.
+        let mut again = Prng::<Field64>::from_seed(&seed);
+        assert_eq!(sample(&mut again.fork(b"round")), sample(&mut round0));
+        assert_eq!(sample(&mut again.fork(b"round")), sample(&mut round1));
+        assert_eq!(sample(&mut again), rest);
+
+        // Children depend on the label and on their position.
+        let mut round0 = Prng::<Field64>::from_seed(&seed).fork(b"round");
+        let mut other = Prng::<Field64>::from_seed(&seed).fork(b"other");
+        let round0 = sample(&mut round0);
+        assert_ne!(round0, sample(&mut other));
+        assert_ne!(round0, sample(&mut round1));
+
+        // A fork consumes `SEED_LENGTH` bytes of the parent.
+        let mut parent = Prng::<Field64>::from_seed(&seed);
+        parent.fork(b"round");
+        let mut skipped = Prng::<Field64>::from_seed(&seed);
+        skipped.fill_bytes(&mut [0; SEED_LENGTH]);
+        assert_eq!(sample(&mut parent), sample(&mut skipped));
+    }
+
+    #[test]
+    fn fill() {
+        let seed = Seed::from([3; SEED_LENGTH]);
+        let want: Vec<Field64> = Prng::<Field64>::from_seed(&seed).take(300).collect();
+
+        let mut prng = Prng::<Field64>::from_seed(&seed);
+        let mut got = vec![Field64::zero(); 300];
+        assert_eq!(prng.fill(&mut got[..100]), 100);
+        assert_eq!(prng.fill(&mut got[100..]), 200);
+        assert_eq!(got, want);
+
+        // A finite sequence stops at its length.
+        let mut prng =
+            Prng::<Field64>::new_with_seed_and_optional_length(seed.as_bytes(), Some(10)).unwrap();
+        let mut got = vec![Field64::one(); 20];
+        assert_eq!(prng.fill(&mut got), 10);
+        assert_eq!(got[..10], want[..10]);
+        assert!(got[10..].iter().all(|x| *x == Field64::one()));
+        assert_eq!(prng.fill(&mut got), 0);
+    }
+
+    #[test]
+    fn fill_bytes() {
+        let seed = Seed::from([3; SEED_LENGTH]);
+        let mut want = vec![0; 5000];
+        SeedStreamAes128::from_seed(&seed).fill(&mut want);
+
+        // Bytes are read from the same position in the stream as field elements.
+        let mut prng = Prng::<Field32>::from_seed(&seed);
+        let mut got = vec![0; 5000];
+        prng.fill_bytes(&mut got[..3]);
+        prng.fill_bytes(&mut got[3..]);
+        assert_eq!(got, want);
+
+        let mut prng = Prng::<Field32>::from_seed(&seed);
+        let x = prng.next().unwrap();
+        assert_eq!(
+            x,
+            Field32::try_from_random(&want[..Field32::BYTES]).unwrap()
+        );
+        let mut got = vec![0; 100];
+        prng.fill_bytes(&mut got);
+        assert_eq!(got, want[Field32::BYTES..Field32::BYTES + 100]);
+        let got = prng.next_bytes(50);
+        assert_eq!(got, want[Field32::BYTES + 100..Field32::BYTES + 150]);
+    }
+
     #[test]
     fn secret_sharing_interop() {
         let seed = [
@@ -189,7 +1226,7 @@ mod tests {
         let seed = base64::decode(seed_base64).unwrap();
         let random_data = extract_share_from_seed::<Field32>(len, &seed).unwrap();
 
-        let random_bytes = crate::util::serialize(&random_data);
+        let random_bytes = Field32::slice_into_byte_vec(&random_data);
 
         let digest = ring::digest::digest(&ring::digest::SHA256, &random_bytes);
         assert_eq!(base64::encode(digest), hash_base64);
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Containers for secret material, such as private keys and input shares.
+//!
+//! [`SecretBytes`] and [`SecretVec`] do not print their contents with `{:?}`, so that secrets
+//! can't end up in logs by accident, and they are compared in constant time. If the `zeroize`
+//! feature is enabled, their contents are erased when they are dropped. [`Seed`](crate::prng::Seed)
+//! provides the same guarantees for PRNG seeds.
+//!
+//! Erasure on drop does not cover copies that were made before, e.g., by `clone()` or by a `Vec`
+//! reallocating its buffer as it grows. Reserve the final capacity up front to avoid the latter.
+
+use crate::field::FieldElement;
+use crate::util::ct_eq_slice;
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Deref;
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A secret byte string.
+#[derive(Clone, Default)]
+pub struct SecretBytes(Vec<u8>);
+
+impl SecretBytes {
+    /// Takes ownership of `bytes`.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for SecretBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl ConstantTimeEq for SecretBytes {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[..].ct_eq(&other.0[..])
+    }
+}
+
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretBytes {}
+
+impl Debug for SecretBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretBytes").finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for SecretBytes {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for SecretBytes {}
+
+/// A secret vector of field elements, such as a share of an input.
+#[derive(Clone)]
+pub struct SecretVec<F: FieldElement>(Vec<F>);
+
+impl<F: FieldElement> SecretVec<F> {
+    /// Takes ownership of `data`.
+    pub fn new(data: Vec<F>) -> Self {
+        Self(data)
+    }
+
+    /// Returns the underlying vector, e.g., to fill it in place. The contents of any buffer that
+    /// the vector reallocates away from are not erased.
+    pub fn as_mut_vec(&mut self) -> &mut Vec<F> {
+        &mut self.0
+    }
+
+    /// Returns the underlying vector, e.g., to pass a share to a constructor that takes a `Vec`.
+    /// The vector is moved out without copying its contents, which are no longer erased on drop.
+    pub fn into_vec(mut self) -> Vec<F> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl<F: FieldElement> Default for SecretVec<F> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<F: FieldElement> From<Vec<F>> for SecretVec<F> {
+    fn from(data: Vec<F>) -> Self {
+        Self(data)
+    }
+}
+
+impl<F: FieldElement> AsRef<[F]> for SecretVec<F> {
+    fn as_ref(&self) -> &[F] {
+        &self.0
+    }
+}
+
+impl<F: FieldElement> Deref for SecretVec<F> {
+    type Target = [F];
+
+    fn deref(&self) -> &[F] {
+        &self.0
+    }
+}
+
+impl<F: FieldElement> ConstantTimeEq for SecretVec<F> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from(ct_eq_slice(&self.0, &other.0) as u8)
+    }
+}
+
+impl<F: FieldElement> PartialEq for SecretVec<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<F: FieldElement> Eq for SecretVec<F> {}
+
+impl<F: FieldElement> Debug for SecretVec<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretVec").finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: FieldElement> Zeroize for SecretVec<F> {
+    fn zeroize(&mut self) {
+        // `FieldElement` does not require `Zeroize`, so the elements are overwritten the way
+        // `zeroize` does it: with volatile writes that the compiler may not elide.
+        for x in self.0.iter_mut() {
+            // Safety: `x` is a valid, aligned pointer to an initialized element, and `F: Copy`, so
+            // overwriting it skips no destructor.
+            unsafe { std::ptr::write_volatile(x, F::zero()) };
+        }
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+        self.0.clear();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: FieldElement> Drop for SecretVec<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: FieldElement> ZeroizeOnDrop for SecretVec<F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field64, FieldElementWithInteger};
+
+    #[test]
+    fn test_no_debug_leak() {
+        let bytes = SecretBytes::from(vec![0x42; 4]);
+        assert_eq!(format!("{:?}", bytes), "SecretBytes");
+        assert_eq!(bytes.as_bytes(), [0x42; 4]);
+
+        let data = SecretVec::new(vec![Field64::from_reduced(1337)]);
+        assert_eq!(format!("{:?}", data), "SecretVec");
+        assert!(!format!("{:?}", Some(&data)).contains("1337"));
+        assert_eq!(data.into_vec(), [Field64::from_reduced(1337)]);
+    }
+
+    #[test]
+    fn test_eq() {
+        let a = SecretBytes::new(vec![1, 2, 3]);
+        assert_eq!(a, a.clone());
+        assert_ne!(a, SecretBytes::new(vec![1, 2, 4]));
+        assert_ne!(a, SecretBytes::new(vec![1, 2]));
+
+        let x = SecretVec::new(vec![Field64::from_reduced(1), Field64::from_reduced(2)]);
+        assert_eq!(x, x.clone());
+        assert_ne!(x, SecretVec::new(vec![Field64::from_reduced(1)]));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        let mut bytes = SecretBytes::new(vec![0x42; 4]);
+        bytes.zeroize();
+        assert!(bytes.is_empty());
+
+        let mut data = SecretVec::new(vec![Field64::from_reduced(1337); 3]);
+        data.zeroize();
+        assert!(data.is_empty());
+    }
+}
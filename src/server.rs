@@ -3,13 +3,23 @@
 
 //! The Prio server. Only 0 / 1 vectors are supported for now.
 use crate::{
+    codec::{CodecError, Decode, Encode},
     encrypt::{decrypt_share, EncryptError, PrivateKey},
-    field::{merge_vector, FieldElement, FieldError},
+    field::{
+        expand_share_into, merge_vector_with, BulkFieldOps, CpuBulkFieldOps, FieldElement,
+        FieldElementWithInteger, FieldError,
+    },
     polynomial::{poly_interpret_eval, PolyAuxMemory},
-    prng::{extract_share_from_seed, Prng, PrngError},
-    util::{deserialize, proof_length, unpack_proof, SerializeError},
+    prng::{Prng, PrngError, Seed},
+    secret::SecretVec,
+    util::{
+        ct_eq, deserialize_with_header_into, read_header, DimensionError, ProofDimensions,
+        ProofLayout, SerializeError,
+    },
 };
 use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use subtle::{Choice, ConstantTimeEq};
 
 /// Possible errors from server operations
 #[derive(Debug, thiserror::Error)]
@@ -23,12 +33,18 @@ pub enum ServerError {
     /// Serialization/deserialization error
     #[error("serialization/deserialization error")]
     Serialize(#[from] SerializeError),
+    /// The dimension is not supported by the field
+    #[error("invalid dimension")]
+    Dimension(#[from] DimensionError),
     /// Failure when calling getrandom().
     #[error("getrandom: {0}")]
     GetRandom(#[from] getrandom::Error),
     /// Decrypted a share seed of the incorrect length.
     #[error("invalid seed length")]
     SeedLen,
+    /// An encrypted share was passed to a server constructed without a private key.
+    #[error("server has no private key")]
+    NoPrivateKey,
 }
 
 impl From<PrngError> for ServerError {
@@ -43,68 +59,133 @@ impl From<PrngError> for ServerError {
 /// [`VerificationMessage`](struct.VerificationMessage.html)
 #[derive(Debug)]
 pub struct ValidationMemory<F: FieldElement> {
+    layout: ProofLayout,
     points_f: Vec<F>,
     points_g: Vec<F>,
     points_h: Vec<F>,
     poly_mem: PolyAuxMemory<F>,
 }
 
-impl<F: FieldElement> ValidationMemory<F> {
+impl<F: FieldElementWithInteger> ValidationMemory<F> {
     /// Construct a new ValidationMemory object for validating proof shares of
     /// length `dimension`.
-    pub fn new(dimension: usize) -> Self {
-        let n: usize = (dimension + 1).next_power_of_two();
-        ValidationMemory {
+    ///
+    /// # Errors
+    ///
+    /// Fails if the field does not have enough roots of unity for the given dimension.
+    pub fn new(dimension: usize) -> Result<Self, FieldError> {
+        let layout = ProofDimensions::<F>::new(dimension)
+            .map_err(|_| FieldError::RootsUnavailable)?
+            .layout();
+        let n = layout.num_points();
+        let poly_mem = PolyAuxMemory::new(n)?;
+        Ok(ValidationMemory {
+            layout,
             points_f: vec![F::zero(); n],
             points_g: vec![F::zero(); n],
             points_h: vec![F::zero(); 2 * n],
-            poly_mem: PolyAuxMemory::new(n),
-        }
+            poly_mem,
+        })
     }
 }
 
 /// Main workhorse of the server.
 #[derive(Debug)]
-pub struct Server<F: FieldElement> {
+pub struct Server<F: FieldElementWithInteger> {
     prng: Prng<F>,
     dimension: usize,
+    dims: ProofDimensions<F>,
     is_first_server: bool,
     accumulator: Vec<F>,
     validation_mem: ValidationMemory<F>,
-    private_key: PrivateKey,
+    // Holds the most recently decoded share, so that its allocation is reused across reports.
+    share: SecretVec<F>,
+    private_key: Option<PrivateKey>,
+    backend: Box<dyn BulkFieldOps<F>>,
 }
 
-impl<F: FieldElement> Server<F> {
+impl<F: FieldElementWithInteger> Server<F> {
     /// Construct a new server instance
     ///
     /// Params:
     ///  * `dimension`: the number of elements in the aggregation vector.
-    ///  * `is_first_server`: only one of the servers should have this true.
+    ///  * `is_first_server`: only one of the servers should have this true. With more than two
+    ///    servers, this is the server that receives the first share output by
+    ///    [`Client::encode_shares`](crate::client::Client::encode_shares).
     ///  * `private_key`: the private key for decrypting the share of the proof.
     pub fn new(
         dimension: usize,
         is_first_server: bool,
         private_key: PrivateKey,
     ) -> Result<Server<F>, ServerError> {
+        Self::with_optional_key(dimension, is_first_server, Some(private_key))
+    }
+
+    /// Construct a new server instance for shares that are decrypted before they reach the server,
+    /// e.g., by a transport layer that provides authenticated encryption. Such a server only
+    /// accepts plaintext shares, through
+    /// [`generate_verification_message_from_share`](#method.generate_verification_message_from_share)
+    /// and [`aggregate_share`](#method.aggregate_share); the methods that take encrypted shares
+    /// return [`ServerError::NoPrivateKey`].
+    pub fn without_encryption(
+        dimension: usize,
+        is_first_server: bool,
+    ) -> Result<Server<F>, ServerError> {
+        Self::with_optional_key(dimension, is_first_server, None)
+    }
+
+    fn with_optional_key(
+        dimension: usize,
+        is_first_server: bool,
+        private_key: Option<PrivateKey>,
+    ) -> Result<Server<F>, ServerError> {
+        let dims = ProofDimensions::new(dimension)?;
         Ok(Server {
             prng: Prng::new()?,
             dimension,
+            dims,
             is_first_server,
             accumulator: vec![F::zero(); dimension],
-            validation_mem: ValidationMemory::new(dimension),
+            validation_mem: ValidationMemory::new(dimension)?,
+            share: SecretVec::default(),
             private_key,
+            backend: Box::new(CpuBulkFieldOps),
         })
     }
 
-    /// Decrypt and deserialize
-    fn deserialize_share(&self, encrypted_share: &[u8]) -> Result<Vec<F>, ServerError> {
-        let share = decrypt_share(encrypted_share, &self.private_key)?;
-        Ok(if self.is_first_server {
-            deserialize(&share)?
+    /// Sets the backend used to add shares into the accumulator. By default, the accumulator is
+    /// computed on the CPU.
+    pub fn set_backend(&mut self, backend: Box<dyn BulkFieldOps<F>>) {
+        self.backend = backend;
+    }
+
+    /// Decrypt and deserialize into `self.share`
+    fn deserialize_share(&mut self, encrypted_share: &[u8]) -> Result<(), ServerError> {
+        let private_key = self.private_key.as_ref().ok_or(ServerError::NoPrivateKey)?;
+        let share = decrypt_share(encrypted_share, private_key)?;
+        let mut out = std::mem::take(&mut self.share);
+        let result = self.decode_share_into(&share, out.as_mut_vec());
+        self.share = out;
+        result
+    }
+
+    /// Decodes a decrypted share, as encoded by the client before encryption, into this server's
+    /// share of the proof. The share of the first server holds the proof share in full, whereas
+    /// the share of any other server holds the seed from which the proof share is expanded.
+    pub fn decode_share(&self, share: &[u8]) -> Result<SecretVec<F>, ServerError> {
+        let mut out = SecretVec::default();
+        self.decode_share_into(share, out.as_mut_vec())?;
+        Ok(out)
+    }
+
+    fn decode_share_into(&self, share: &[u8], out: &mut Vec<F>) -> Result<(), ServerError> {
+        if self.is_first_server {
+            deserialize_with_header_into(self.dimension, share, out)?;
         } else {
-            let len = proof_length(self.dimension);
-            extract_share_from_seed(len, &share)?
-        })
+            let seed = Seed::read_from(read_header::<F>(share, self.dimension)?)?;
+            expand_share_into(&seed, self.dims.proof_length(), out);
+        }
+        Ok(())
     }
 
     /// Generate verification message from an encrypted share
@@ -118,11 +199,31 @@ impl<F: FieldElement> Server<F> {
         eval_at: F,
         share: &[u8],
     ) -> Result<VerificationMessage<F>, ServerError> {
-        let share_field = self.deserialize_share(share)?;
+        self.deserialize_share(share)?;
         generate_verification_message(
             self.dimension,
             eval_at,
-            &share_field,
+            &self.share,
+            self.is_first_server,
+            &mut self.validation_mem,
+        )
+    }
+
+    /// Like `generate_verification_message()`, but for a share of the proof that was already
+    /// decrypted and decoded, e.g., by `decode_share()`.
+    pub fn generate_verification_message_from_share(
+        &mut self,
+        eval_at: F,
+        share: &[F],
+    ) -> Result<VerificationMessage<F>, ServerError> {
+        if share.len() != self.dims.proof_length() {
+            return Err(SerializeError::UnpackInputSizeMismatch.into());
+        }
+
+        generate_verification_message(
+            self.dimension,
+            eval_at,
+            share,
             self.is_first_server,
             &mut self.validation_mem,
         )
@@ -138,13 +239,47 @@ impl<F: FieldElement> Server<F> {
         v1: &VerificationMessage<F>,
         v2: &VerificationMessage<F>,
     ) -> Result<bool, ServerError> {
-        let share_field = self.deserialize_share(share)?;
-        let is_valid = is_valid_share(v1, v2);
+        self.aggregate_with_messages(share, [v1, v2])
+    }
+
+    /// Like `aggregate()`, but for any number of servers: the share is only added to the
+    /// accumulator if the verification messages of all of the servers, including this one,
+    /// indicate that it passed validation.
+    pub fn aggregate_with_messages<'a, I>(
+        &mut self,
+        share: &[u8],
+        messages: I,
+    ) -> Result<bool, ServerError>
+    where
+        I: IntoIterator<Item = &'a VerificationMessage<F>>,
+    {
+        self.deserialize_share(share)?;
+        let share = std::mem::take(&mut self.share);
+        let result = self.aggregate_share(&share, messages);
+        self.share = share;
+        result
+    }
+
+    /// Like `aggregate_with_messages()`, but for a share of the proof that was already decrypted
+    /// and decoded, e.g., by `decode_share()`.
+    pub fn aggregate_share<'a, I>(&mut self, share: &[F], messages: I) -> Result<bool, ServerError>
+    where
+        I: IntoIterator<Item = &'a VerificationMessage<F>>,
+    {
+        if share.len() != self.dims.proof_length() {
+            return Err(SerializeError::UnpackInputSizeMismatch.into());
+        }
+
+        let is_valid = is_valid_shares(messages);
         if is_valid {
-            // Add to the accumulator. share_field also includes the proof
+            // Add to the accumulator. The share also includes the proof
             // encoding, so we slice off the first dimension fields, which are
             // the actual data share.
-            merge_vector(&mut self.accumulator, &share_field[..self.dimension])?;
+            merge_vector_with(
+                &*self.backend,
+                &mut self.accumulator,
+                &share[..self.dimension],
+            )?;
         }
 
         Ok(is_valid)
@@ -167,7 +302,11 @@ impl<F: FieldElement> Server<F> {
     /// Returns an error if `other_total_shares.len()` is not equal to this
     //// server's `dimension`.
     pub fn merge_total_shares(&mut self, other_total_shares: &[F]) -> Result<(), ServerError> {
-        Ok(merge_vector(&mut self.accumulator, other_total_shares)?)
+        Ok(merge_vector_with(
+            &*self.backend,
+            &mut self.accumulator,
+            other_total_shares,
+        )?)
     }
 
     /// Choose a random point for polynomial evaluation
@@ -195,6 +334,34 @@ pub struct VerificationMessage<F: FieldElement> {
     pub h_r: F,
 }
 
+impl<F: FieldElement> ConstantTimeEq for VerificationMessage<F> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.f_r.ct_eq(&other.f_r) & self.g_r.ct_eq(&other.g_r) & self.h_r.ct_eq(&other.h_r)
+    }
+}
+
+impl<F: FieldElement> Encode for VerificationMessage<F> {
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        self.f_r.encode(bytes);
+        self.g_r.encode(bytes);
+        self.h_r.encode(bytes);
+    }
+
+    fn encoded_len(&self) -> Option<usize> {
+        Some(3 * F::ENCODED_LEN)
+    }
+}
+
+impl<F: FieldElement> Decode for VerificationMessage<F> {
+    fn decode(bytes: &mut Cursor<&[u8]>) -> Result<Self, CodecError> {
+        Ok(Self {
+            f_r: F::decode(bytes)?,
+            g_r: F::decode(bytes)?,
+            h_r: F::decode(bytes)?,
+        })
+    }
+}
+
 /// Given a proof and evaluation point, this constructs the verification
 /// message.
 pub fn generate_verification_message<F: FieldElement>(
@@ -204,8 +371,11 @@ pub fn generate_verification_message<F: FieldElement>(
     is_first_server: bool,
     mem: &mut ValidationMemory<F>,
 ) -> Result<VerificationMessage<F>, ServerError> {
-    let unpacked = unpack_proof(proof, dimension)?;
-    let proof_length = 2 * (dimension + 1).next_power_of_two();
+    // The memory determines the layout, which depends on the field as well as the dimension.
+    if mem.layout.dimension() != dimension {
+        return Err(SerializeError::UnpackInputSizeMismatch.into());
+    }
+    let unpacked = mem.layout.unpack(proof)?;
 
     // set zero terms
     mem.points_f[0] = *unpacked.f0;
@@ -225,12 +395,8 @@ pub fn generate_verification_message<F: FieldElement>(
     }
 
     // set points_h, skipping over elements that should be zero
-    let mut i = 1;
-    let mut j = 0;
-    while i < proof_length {
-        mem.points_h[i] = unpacked.points_h_packed[j];
-        j += 1;
-        i += 2;
+    for (j, x) in unpacked.points_h_packed.iter().enumerate() {
+        mem.points_h[2 * j + 1] = *x;
     }
 
     // evaluate polynomials at random point
@@ -264,12 +430,27 @@ pub fn is_valid_share<F: FieldElement>(
     v1: &VerificationMessage<F>,
     v2: &VerificationMessage<F>,
 ) -> bool {
+    is_valid_shares([v1, v2])
+}
+
+/// Decides if the distributed proof is valid, given the verification messages of all of the
+/// servers. Returns `false` if fewer than two messages are given.
+pub fn is_valid_shares<'a, F, I>(messages: I) -> bool
+where
+    F: FieldElement,
+    I: IntoIterator<Item = &'a VerificationMessage<F>>,
+{
     // reconstruct f_r, g_r, h_r
-    let f_r = v1.f_r + v2.f_r;
-    let g_r = v1.g_r + v2.g_r;
-    let h_r = v1.h_r + v2.h_r;
-    // validity check
-    f_r * g_r == h_r
+    let mut count = 0;
+    let (mut f_r, mut g_r, mut h_r) = (F::zero(), F::zero(), F::zero());
+    for v in messages {
+        f_r += v.f_r;
+        g_r += v.g_r;
+        h_r += v.h_r;
+        count += 1;
+    }
+    // validity check, in constant time so that the timing does not reveal the values
+    count >= 2 && ct_eq(&(f_r * g_r), &h_r)
 }
 
 #[cfg(test)]
@@ -277,44 +458,154 @@ mod tests {
     use super::*;
     use crate::field::Field32;
     use crate::util;
+    use assert_matches::assert_matches;
     use serde_json;
 
+    #[test]
+    fn test_debug_hides_secrets() {
+        let priv_key = PrivateKey::from_base64(
+            "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9RqZx3XMkdEHBHfNuCSMpOwofVSq3TfyKwn0NrftKisKKVSaTOt5seJ67P5QL4hxgPWvxw==",
+        )
+        .unwrap();
+        let mut server = Server::<Field32>::new(3, true, priv_key).unwrap();
+        let share = crate::encrypt::encrypt_share(
+            &util::serialize_with_header(
+                3,
+                &vec![Field32::from(1337); util::proof_length::<Field32>(3)],
+            ),
+            &crate::encrypt::PublicKey::from(server.private_key.as_ref().unwrap()),
+        )
+        .unwrap();
+        server.deserialize_share(&share).unwrap();
+
+        let debug = format!("{:?}", server);
+        assert!(debug.contains("SecretVec"));
+        assert!(debug.contains("SecretBytes"));
+        assert!(!debug.contains("1337"));
+    }
+
+    #[test]
+    fn test_validation_memory_too_large() {
+        // Field32 has roots of unity of order 2^20 * 3^2.
+        assert!(ValidationMemory::<Field32>::new((9 << 19) - 1).is_ok());
+        assert_eq!(
+            ValidationMemory::<Field32>::new(9 << 19).unwrap_err(),
+            FieldError::RootsUnavailable
+        );
+        assert_eq!(
+            ValidationMemory::<Field32>::new(usize::MAX).unwrap_err(),
+            FieldError::RootsUnavailable
+        );
+    }
+
     #[test]
     fn test_validation() {
         let dim = 8;
         let proof_u32: Vec<u32> = vec![
-            1, 0, 0, 0, 0, 0, 0, 0, 2052337230, 3217065186, 1886032198, 2533724497, 397524722,
-            3820138372, 1535223968, 4291254640, 3565670552, 2447741959, 163741941, 335831680,
-            2567182742, 3542857140, 124017604, 4201373647, 431621210, 1618555683, 267689149,
+            1, 0, 0, 0, 0, 0, 0, 0, 3111779405, 3975057473, 301357002, 2084239163, 1507413659,
+            2960061992, 3224998767, 650461382, 1572046000, 4000995183, 1892910550, 3201695133,
         ];
 
-        let mut proof: Vec<Field32> = proof_u32.iter().map(|x| Field32::from(*x)).collect();
+        let mut proof: Vec<Field32> = proof_u32
+            .iter()
+            .map(|x| Field32::from_reduced(*x))
+            .collect();
         let share2 = util::tests::secret_share(&mut proof);
-        let eval_at = Field32::from(12313);
+        let eval_at = Field32::from_reduced(12313);
 
-        let mut validation_mem = ValidationMemory::new(dim);
+        let mut validation_mem = ValidationMemory::new(dim).unwrap();
 
         let v1 =
             generate_verification_message(dim, eval_at, &proof, true, &mut validation_mem).unwrap();
         let v2 = generate_verification_message(dim, eval_at, &share2, false, &mut validation_mem)
             .unwrap();
         assert_eq!(is_valid_share(&v1, &v2), true);
+
+        // The memory is for a different dimension.
+        assert_matches!(
+            generate_verification_message(dim - 1, eval_at, &proof, true, &mut validation_mem),
+            Err(ServerError::Serialize(
+                SerializeError::UnpackInputSizeMismatch
+            ))
+        );
+    }
+
+    #[test]
+    fn test_plaintext_shares() {
+        let dim = 8;
+        let proof_u32: Vec<u32> = vec![
+            1, 0, 0, 0, 0, 0, 0, 0, 3111779405, 3975057473, 301357002, 2084239163, 1507413659,
+            2960061992, 3224998767, 650461382, 1572046000, 4000995183, 1892910550, 3201695133,
+        ];
+
+        let mut proof: Vec<Field32> = proof_u32
+            .iter()
+            .map(|x| Field32::from_reduced(*x))
+            .collect();
+        let seeds = crate::field::split_compressed_in_place(&mut proof, 1).unwrap();
+        let mut encoded_share2 = Vec::new();
+        util::write_header::<Field32>(&mut encoded_share2, dim);
+        seeds[0].append_to(&mut encoded_share2);
+
+        let mut server1 = Server::<Field32>::without_encryption(dim, true).unwrap();
+        let mut server2 = Server::<Field32>::without_encryption(dim, false).unwrap();
+        let share1 = server1
+            .decode_share(&util::serialize_with_header(dim, &proof))
+            .unwrap();
+        let share2 = server2.decode_share(&encoded_share2).unwrap();
+        assert_eq!(share1[..], proof[..]);
+
+        let eval_at = server1.choose_eval_at();
+        let v1 = server1
+            .generate_verification_message_from_share(eval_at, &share1)
+            .unwrap();
+        let v2 = server2
+            .generate_verification_message_from_share(eval_at, &share2)
+            .unwrap();
+        assert!(server1.aggregate_share(&share1, [&v1, &v2]).unwrap());
+        assert!(server2.aggregate_share(&share2, [&v1, &v2]).unwrap());
+        assert_eq!(
+            util::reconstruct_shares([server1.total_shares(), server2.total_shares()]).unwrap(),
+            &proof_u32[..dim]
+                .iter()
+                .map(|x| Field32::from_reduced(*x))
+                .collect::<Vec<_>>()[..]
+        );
+
+        assert!(matches!(
+            server1.aggregate_share(&share1[..dim], [&v1, &v2]),
+            Err(ServerError::Serialize(
+                SerializeError::UnpackInputSizeMismatch
+            ))
+        ));
+        assert!(matches!(
+            server1.generate_verification_message_from_share(eval_at, &share1[..dim]),
+            Err(ServerError::Serialize(
+                SerializeError::UnpackInputSizeMismatch
+            ))
+        ));
+        assert!(matches!(
+            server1.generate_verification_message(eval_at, &[0; 64]),
+            Err(ServerError::NoPrivateKey)
+        ));
     }
 
     #[test]
     fn test_verification_message_serde() {
         let dim = 8;
         let proof_u32: Vec<u32> = vec![
-            1, 0, 0, 0, 0, 0, 0, 0, 2052337230, 3217065186, 1886032198, 2533724497, 397524722,
-            3820138372, 1535223968, 4291254640, 3565670552, 2447741959, 163741941, 335831680,
-            2567182742, 3542857140, 124017604, 4201373647, 431621210, 1618555683, 267689149,
+            1, 0, 0, 0, 0, 0, 0, 0, 3111779405, 3975057473, 301357002, 2084239163, 1507413659,
+            2960061992, 3224998767, 650461382, 1572046000, 4000995183, 1892910550, 3201695133,
         ];
 
-        let mut proof: Vec<Field32> = proof_u32.iter().map(|x| Field32::from(*x)).collect();
+        let mut proof: Vec<Field32> = proof_u32
+            .iter()
+            .map(|x| Field32::from_reduced(*x))
+            .collect();
         let share2 = util::tests::secret_share(&mut proof);
-        let eval_at = Field32::from(12313);
+        let eval_at = Field32::from_reduced(12313);
 
-        let mut validation_mem = ValidationMemory::new(dim);
+        let mut validation_mem = ValidationMemory::new(dim).unwrap();
 
         let v1 =
             generate_verification_message(dim, eval_at, &proof, true, &mut validation_mem).unwrap();
@@ -326,5 +617,14 @@ mod tests {
         let deserialized: VerificationMessage<Field32> = serde_json::from_str(&serialized).unwrap();
 
         assert_eq!(is_valid_share(&deserialized, &v2), true);
+
+        // encode and decode the first verification message
+        let encoded = v1.get_encoded();
+        assert_eq!(encoded.len(), 3 * Field32::ENCODED_LEN);
+        let decoded = VerificationMessage::<Field32>::get_decoded(&encoded).unwrap();
+        assert!(bool::from(decoded.ct_eq(&v1)));
+        assert!(!bool::from(decoded.ct_eq(&v2)));
+
+        assert_eq!(is_valid_share(&decoded, &v2), true);
     }
 }
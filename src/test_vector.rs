@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Known-answer test vectors for checking other implementations against this crate.
+//!
+//! A [`TestVector`] records a list of inputs, the seed from which each input was split into
+//! shares with [`split_with_seed`], the resulting shares, the sums of the shares held by each
+//! server and the aggregate. Field elements are encoded as by
+//! [`FieldElement::slice_into_byte_vec`] and byte strings are written to JSON as lowercase hex, so
+//! that another implementation can compare its output byte-for-byte:
+//!
+//! ```
+//! use prio::field::{Field64, FieldElementWithInteger};
+//! use prio::prng::Seed;
+//! use prio::test_vector::TestVector;
+//!
+//! let inputs = vec![vec![Field64::from_reduced(1), Field64::from_reduced(0)]];
+//! let seeds = vec![Seed::from([7; 32])];
+//! let vector = TestVector::generate(&inputs, &seeds, 2);
+//!
+//! let json = vector.to_json().unwrap();
+//! TestVector::from_json(&json).unwrap().verify::<Field64>().unwrap();
+//! ```
+
+use crate::field::{merge_vector, split_with_seed, FieldElement, FieldError};
+use crate::prng::{Seed, SEED_LENGTH};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::io::Read;
+
+/// Errors from loading or verifying test vectors.
+#[derive(Debug, thiserror::Error)]
+pub enum TestVectorError {
+    /// The test vector is not valid JSON or does not have the expected structure.
+    #[error("json error")]
+    Json(#[from] serde_json::Error),
+    /// The test vector is for a different field than the one it is verified with.
+    #[error("field ID mismatch: test vector has {0}, expected {1}")]
+    FieldIdMismatch(u8, u8),
+    /// A seed in the test vector has the wrong length.
+    #[error("seed {0} has the wrong length")]
+    SeedLen(usize),
+    /// A byte string in the test vector does not encode a vector of field elements.
+    #[error("finite field operation error")]
+    Field(#[from] FieldError),
+    /// The output of this crate differs from the expected value in the test vector.
+    #[error("mismatch in {0}")]
+    Mismatch(&'static str),
+}
+
+/// A byte string, written to JSON as lowercase hex.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexBytes(pub Vec<u8>);
+
+impl Serialize for HexBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut hex = String::with_capacity(2 * self.0.len());
+        for b in self.0.iter() {
+            hex.push_str(&format!("{:02x}", b));
+        }
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+struct HexVisitor;
+
+impl<'de> de::Visitor<'de> for HexVisitor {
+    type Value = HexBytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<HexBytes, E> {
+        let digit = |c: &u8| (*c as char).to_digit(16).map(|d| d as u8);
+        v.as_bytes()
+            .chunks(2)
+            .map(|pair| match pair {
+                [hi, lo] => Some(digit(hi)? << 4 | digit(lo)?),
+                _ => None,
+            })
+            .collect::<Option<Vec<u8>>>()
+            .map(HexBytes)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// The sharing of one input of a [`TestVector`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareTestCase {
+    /// The encoded input.
+    pub input: HexBytes,
+    /// The seed passed to [`split_with_seed`].
+    pub seed: HexBytes,
+    /// The encoded shares output by [`split_with_seed`], one per server.
+    pub shares: Vec<HexBytes>,
+}
+
+/// A known-answer test for splitting inputs into shares and aggregating them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// The [`FieldElement::ID`], cast to `u8`, of the field the inputs are encoded in.
+    pub field_id: u8,
+    /// The number of shares each input is split into.
+    pub num_shares: usize,
+    /// The sharing of each input.
+    pub cases: Vec<ShareTestCase>,
+    /// The encoded sum of the shares held by each server.
+    pub aggregate_shares: Vec<HexBytes>,
+    /// The encoded sum of the inputs.
+    pub aggregate: HexBytes,
+}
+
+impl TestVector {
+    /// Generates a test vector by splitting `inputs[i]` into `num_shares` shares with
+    /// `seeds[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inputs` and `seeds` differ in length, if the inputs differ in length, or if
+    /// `num_shares` is 0.
+    pub fn generate<F: FieldElement>(
+        inputs: &[Vec<F>],
+        seeds: &[Seed<SEED_LENGTH>],
+        num_shares: usize,
+    ) -> Self {
+        assert_eq!(inputs.len(), seeds.len(), "need one seed per input");
+        assert!(num_shares > 0, "need at least one share");
+
+        let len = inputs.first().map_or(0, Vec::len);
+        let mut aggregate = vec![F::zero(); len];
+        let mut aggregate_shares = vec![vec![F::zero(); len]; num_shares];
+        let cases = inputs
+            .iter()
+            .zip(seeds.iter())
+            .map(|(input, seed)| {
+                let shares = split_with_seed(seed, input, num_shares);
+                merge_vector(&mut aggregate, input).expect("inputs differ in length");
+                for (total, share) in aggregate_shares.iter_mut().zip(shares.iter()) {
+                    merge_vector(total, share).unwrap();
+                }
+                ShareTestCase {
+                    input: encode(input),
+                    seed: HexBytes(seed.as_bytes().to_vec()),
+                    shares: shares.iter().map(|share| encode(share)).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            field_id: F::ID as u8,
+            num_shares,
+            cases,
+            aggregate_shares: aggregate_shares.iter().map(|share| encode(share)).collect(),
+            aggregate: encode(&aggregate),
+        }
+    }
+
+    /// Parses a test vector from JSON.
+    pub fn from_json(json: &str) -> Result<Self, TestVectorError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Reads a test vector in JSON from `reader`, e.g., a file.
+    pub fn load<R: Read>(reader: R) -> Result<Self, TestVectorError> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Writes the test vector to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, TestVectorError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Checks that this crate reproduces the test vector exactly: the shares are recomputed from
+    /// the inputs and seeds, the aggregate shares from the shares, and the aggregate from the
+    /// inputs, and each is compared with the encoding in the test vector.
+    pub fn verify<F: FieldElement>(&self) -> Result<(), TestVectorError> {
+        if self.field_id != F::ID as u8 {
+            return Err(TestVectorError::FieldIdMismatch(self.field_id, F::ID as u8));
+        }
+
+        let mut inputs = Vec::with_capacity(self.cases.len());
+        let mut seeds = Vec::with_capacity(self.cases.len());
+        for (i, case) in self.cases.iter().enumerate() {
+            inputs.push(F::byte_slice_into_vec(&case.input.0)?);
+            seeds.push(Seed::read_from(&case.seed.0).map_err(|_| TestVectorError::SeedLen(i))?);
+        }
+        if inputs.iter().any(|input| input.len() != inputs[0].len()) {
+            return Err(TestVectorError::Field(FieldError::InputSizeMismatch));
+        }
+        if self.num_shares == 0 {
+            return Err(TestVectorError::Mismatch("num_shares"));
+        }
+
+        let expected = Self::generate(&inputs, &seeds, self.num_shares);
+        for (case, expected) in self.cases.iter().zip(expected.cases.iter()) {
+            if case.shares != expected.shares {
+                return Err(TestVectorError::Mismatch("shares"));
+            }
+        }
+        if self.aggregate_shares != expected.aggregate_shares {
+            return Err(TestVectorError::Mismatch("aggregate_shares"));
+        }
+        if self.aggregate != expected.aggregate {
+            return Err(TestVectorError::Mismatch("aggregate"));
+        }
+        Ok(())
+    }
+}
+
+fn encode<F: FieldElement>(values: &[F]) -> HexBytes {
+    HexBytes(F::slice_into_byte_vec(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Field32, Field64, FieldElementWithInteger};
+    use assert_matches::assert_matches;
+
+    fn test_vector() -> TestVector {
+        let inputs: Vec<Vec<Field64>> = (0..3)
+            .map(|i| (0..4).map(|j| Field64::from_reduced(i * j)).collect())
+            .collect();
+        let seeds: Vec<_> = (0..3u8).map(|i| Seed::from([i; SEED_LENGTH])).collect();
+        TestVector::generate(&inputs, &seeds, 3)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let vector = test_vector();
+        assert_eq!(vector.cases.len(), 3);
+        assert_eq!(vector.aggregate_shares.len(), 3);
+        assert_eq!(
+            Field64::byte_slice_into_vec(&vector.aggregate.0).unwrap(),
+            [0, 3, 6, 9]
+                .iter()
+                .map(|x| Field64::from_reduced(*x))
+                .collect::<Vec<_>>()
+        );
+
+        let json = vector.to_json().unwrap();
+        assert_eq!(TestVector::from_json(&json).unwrap(), vector);
+        assert_eq!(TestVector::load(json.as_bytes()).unwrap(), vector);
+        vector.verify::<Field64>().unwrap();
+    }
+
+    #[test]
+    fn test_hex() {
+        let json = serde_json::to_string(&HexBytes(vec![0x00, 0xab, 0x7f])).unwrap();
+        assert_eq!(json, "\"00ab7f\"");
+        assert_eq!(
+            serde_json::from_str::<HexBytes>("\"00AB7f\"").unwrap(),
+            HexBytes(vec![0x00, 0xab, 0x7f])
+        );
+        assert!(serde_json::from_str::<HexBytes>("\"abc\"").is_err());
+        assert!(serde_json::from_str::<HexBytes>("\"zz\"").is_err());
+        assert!(serde_json::from_str::<HexBytes>("\"+1\"").is_err());
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        let vector = test_vector();
+        assert_matches!(
+            vector.verify::<Field32>(),
+            Err(TestVectorError::FieldIdMismatch(2, 1))
+        );
+
+        let mut bad = vector.clone();
+        bad.cases[1].shares[0].0[0] ^= 1;
+        assert_matches!(
+            bad.verify::<Field64>(),
+            Err(TestVectorError::Mismatch("shares"))
+        );
+
+        let mut bad = vector.clone();
+        bad.aggregate.0[0] ^= 1;
+        assert_matches!(
+            bad.verify::<Field64>(),
+            Err(TestVectorError::Mismatch("aggregate"))
+        );
+
+        let mut bad = vector;
+        bad.cases[2].seed.0.pop();
+        assert_matches!(bad.verify::<Field64>(), Err(TestVectorError::SeedLen(2)));
+    }
+}
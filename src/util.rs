@@ -3,7 +3,17 @@
 
 //! Utility functions for handling Prio stuff.
 
-use crate::field::{FieldElement, FieldError};
+use crate::codec::{encode_u32_items, Decode, Encode};
+use crate::fft::mixed_radix_sizes;
+use crate::field::{merge_vector, FieldElement, FieldElementWithInteger, FieldError};
+use std::{
+    cmp::min,
+    convert::TryFrom,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    ops::Range,
+};
+use subtle::Choice;
 
 /// Serialization errors
 #[derive(Debug, thiserror::Error)]
@@ -12,23 +22,261 @@ pub enum SerializeError {
     /// element of the field.
     #[error("last chunk of bytes is incomplete")]
     IncompleteChunk,
+    /// Emitted by `deserialize()` if the length prefix is missing or does not match the number of
+    /// encoded elements.
+    #[error("length prefix is missing or does not match the input")]
+    LengthPrefixMismatch,
     /// Emitted by `unpack_proof[_mut]` if the serialized share+proof has the wrong length
     #[error("serialized input has wrong length")]
     UnpackInputSizeMismatch,
     /// Finite field operation error.
     #[error("finite field operation error")]
     Field(#[from] FieldError),
+    /// Emitted by `read_header()` if the input is too short to contain a header.
+    #[error("header is incomplete")]
+    IncompleteHeader,
+    /// Emitted by `read_header()` if the header has an unknown format version.
+    #[error("unsupported format version {0}")]
+    UnsupportedVersion(u8),
+    /// Emitted by `read_header()` if the input was encoded for a different field.
+    #[error("input encodes elements of field {0}, expected field {1}")]
+    FieldIdMismatch(u8, u8),
+    /// Emitted by `read_header()` if the input was encoded for a different dimension.
+    #[error("input has dimension {0}, expected dimension {1}")]
+    DimensionMismatch(u32, u32),
+    /// Emitted by `proof_from_base64_url()` if the input is not valid base64.
+    #[cfg(feature = "base64-encoding")]
+    #[error("base64 decoding error")]
+    DecodeBase64(#[from] base64::DecodeError),
+    /// Emitted by `Accumulator` if a share in the stream has the wrong number of elements.
+    #[error("share has {0} elements, expected {1}")]
+    ShareLengthMismatch(usize, usize),
+    /// Emitted by `read_share()` if the underlying reader fails.
+    #[error("I/O error")]
+    Io(#[from] io::Error),
 }
 
-/// Returns the number of field elements in the proof for given dimension of
-/// data elements
+/// Layout of a proof vector for a given dimension of data elements
 ///
 /// Proof is a vector, where the first `dimension` elements are the data
 /// elements, the next 3 elements are the zero terms for polynomials f, g and h
 /// and the remaining elements are non-zero points of h(x).
-pub fn proof_length(dimension: usize) -> usize {
-    // number of data items + number of zero terms + N
-    dimension + 3 + (dimension + 1).next_power_of_two()
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofLayout {
+    dimension: usize,
+    num_points: usize,
+}
+
+impl ProofLayout {
+    /// Returns the layout of proofs over field `F` for the given dimension
+    pub fn new<F: FieldElementWithInteger>(dimension: usize) -> Self {
+        Self {
+            dimension,
+            num_points: num_points::<F>(dimension)
+                .unwrap_or_else(|| (dimension + 1).next_power_of_two()),
+        }
+    }
+
+    /// Returns the number of data elements
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns N, the number of points at which f and g are interpolated. This is the smallest
+    /// number of the form `2^a * 3^b` that is larger than the dimension, since the zero terms take
+    /// up one point, and for which the field has roots of unity of order 2N. If there is no such
+    /// number, then N is the smallest power of two larger than the dimension, and the field is too
+    /// small for the dimension (see [`ProofDimensions`]).
+    pub fn num_points(&self) -> usize {
+        self.num_points
+    }
+
+    /// Returns the number of field elements in the proof
+    pub fn proof_length(&self) -> usize {
+        self.h_points_range().end
+    }
+
+    /// Returns the range of the data elements
+    pub fn data_range(&self) -> Range<usize> {
+        0..self.dimension
+    }
+
+    /// Returns the index of the zeroth coefficient of polynomial f
+    pub fn f0_index(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the index of the zeroth coefficient of polynomial g
+    pub fn g0_index(&self) -> usize {
+        self.dimension + 1
+    }
+
+    /// Returns the index of the zeroth coefficient of polynomial h
+    pub fn h0_index(&self) -> usize {
+        self.dimension + 2
+    }
+
+    /// Returns the range of the non-zero points of polynomial h
+    pub fn h_points_range(&self) -> Range<usize> {
+        let start = self.h0_index() + 1;
+        start..start + self.num_points()
+    }
+
+    /// Unpacks the proof vector into subcomponents
+    pub fn unpack<'a, F: FieldElement>(
+        &self,
+        proof: &'a [F],
+    ) -> Result<UnpackedProof<'a, F>, SerializeError> {
+        if proof.len() != self.proof_length() {
+            return Err(SerializeError::UnpackInputSizeMismatch);
+        }
+        let (data, rest) = proof.split_at(self.data_range().end);
+        let (zero_terms, points_h_packed) = rest.split_at(self.h_points_range().start - data.len());
+        match zero_terms {
+            [f0, g0, h0] => Ok(UnpackedProof {
+                data,
+                f0,
+                g0,
+                h0,
+                points_h_packed,
+            }),
+            _ => Err(SerializeError::UnpackInputSizeMismatch),
+        }
+    }
+
+    /// Unpacks a mutable proof vector into mutable subcomponents
+    pub fn unpack_mut<'a, F: FieldElement>(
+        &self,
+        proof: &'a mut [F],
+    ) -> Result<UnpackedProofMut<'a, F>, SerializeError> {
+        if proof.len() != self.proof_length() {
+            return Err(SerializeError::UnpackInputSizeMismatch);
+        }
+        let (data, rest) = proof.split_at_mut(self.data_range().end);
+        let (zero_terms, points_h_packed) =
+            rest.split_at_mut(self.h_points_range().start - data.len());
+        match zero_terms {
+            [f0, g0, h0] => Ok(UnpackedProofMut {
+                data,
+                f0,
+                g0,
+                h0,
+                points_h_packed,
+            }),
+            _ => Err(SerializeError::UnpackInputSizeMismatch),
+        }
+    }
+}
+
+/// Returns the number of field elements in the proof over field `F` for given
+/// dimension of data elements
+///
+/// See [`ProofLayout`] for the layout of the proof.
+pub fn proof_length<F: FieldElementWithInteger>(dimension: usize) -> usize {
+    ProofLayout::new::<F>(dimension).proof_length()
+}
+
+// Returns N for the given dimension (see `ProofLayout::num_points()`), or `None` if the field does
+// not have enough roots of unity.
+fn num_points<F: FieldElementWithInteger>(dimension: usize) -> Option<usize> {
+    mixed_radix_sizes::<F>()
+        .filter(|size| size % 2 == 0 && size / 2 > dimension)
+        .min()
+        .map(|size| size / 2)
+}
+
+/// Errors from validating the dimension of proofs
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DimensionError {
+    /// The proof or its evaluation domain would not fit in memory
+    #[error("dimension {0} is too large to allocate a proof")]
+    TooLarge(usize),
+    /// The field does not have enough roots of unity for the evaluation domain
+    #[error("dimension {dimension} exceeds the maximum dimension {max} supported by the field")]
+    ExceedsFieldCapacity {
+        /// The requested dimension
+        dimension: usize,
+        /// The largest dimension supported by the field
+        max: usize,
+    },
+}
+
+/// Dimensions of proofs over field `F`, validated against the capacity of the field
+///
+/// The proof polynomials are interpolated from N points and multiplied by
+/// evaluating them at 2N roots of unity, so the dimension is limited by the
+/// orders of the roots of unity of the field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofDimensions<F: FieldElementWithInteger> {
+    layout: ProofLayout,
+    phantom: PhantomData<F>,
+}
+
+impl<F: FieldElementWithInteger> ProofDimensions<F> {
+    /// Validates the dimension
+    ///
+    /// # Errors
+    ///
+    /// Fails if the dimension is larger than [`Self::max_dimension()`] or if
+    /// the proof would not fit in memory.
+    pub fn new(dimension: usize) -> Result<Self, DimensionError> {
+        // No proof could be allocated for such a dimension, whatever the field.
+        dimension
+            .checked_add(1)
+            .and_then(usize::checked_next_power_of_two)
+            .ok_or(DimensionError::TooLarge(dimension))?;
+
+        let max = Self::max_dimension();
+        if dimension > max {
+            return Err(DimensionError::ExceedsFieldCapacity { dimension, max });
+        }
+
+        let layout = ProofLayout::new::<F>(dimension);
+        layout
+            .num_points()
+            .checked_mul(2)
+            .and_then(|_| dimension.checked_add(3 + layout.num_points()))
+            .ok_or(DimensionError::TooLarge(dimension))?;
+
+        Ok(Self {
+            layout,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Returns the largest dimension supported by the field
+    pub fn max_dimension() -> usize {
+        // 2N must be the order of a root of unity, and N > dimension.
+        mixed_radix_sizes::<F>()
+            .filter(|size| size % 2 == 0)
+            .max()
+            .map_or(0, |size| size / 2 - 1)
+    }
+
+    /// Returns the number of data elements
+    pub fn dimension(&self) -> usize {
+        self.layout.dimension()
+    }
+
+    /// Returns the number of field elements in the proof
+    pub fn proof_length(&self) -> usize {
+        self.layout.proof_length()
+    }
+
+    /// Returns N, the number of points at which f and g are interpolated
+    pub fn num_points(&self) -> usize {
+        self.layout.num_points()
+    }
+
+    /// Returns 2N, the number of roots of unity at which the proof polynomials are evaluated
+    pub fn eval_domain_size(&self) -> usize {
+        2 * self.layout.num_points()
+    }
+
+    /// Returns the layout of the proof
+    pub fn layout(&self) -> ProofLayout {
+        self.layout
+    }
 }
 
 /// Unpacked proof with subcomponents
@@ -62,103 +310,425 @@ pub struct UnpackedProofMut<'a, F: FieldElement> {
 }
 
 /// Unpacks the proof vector into subcomponents
-pub(crate) fn unpack_proof<F: FieldElement>(
+#[cfg(test)]
+pub(crate) fn unpack_proof<F: FieldElementWithInteger>(
     proof: &[F],
     dimension: usize,
 ) -> Result<UnpackedProof<F>, SerializeError> {
-    // check the proof length
-    if proof.len() != proof_length(dimension) {
-        return Err(SerializeError::UnpackInputSizeMismatch);
-    }
-    // split share into components
-    let (data, rest) = proof.split_at(dimension);
-    if let ([f0, g0, h0], points_h_packed) = rest.split_at(3) {
-        Ok(UnpackedProof {
-            data,
-            f0,
-            g0,
-            h0,
-            points_h_packed,
-        })
-    } else {
-        Err(SerializeError::UnpackInputSizeMismatch)
-    }
+    ProofLayout::new::<F>(dimension).unpack(proof)
 }
 
 /// Unpacks a mutable proof vector into mutable subcomponents
 // TODO(timg): This is public because it is used by tests/tweaks.rs. We should
 // refactor that test so it doesn't require the crate to expose this function or
 // UnpackedProofMut.
-pub fn unpack_proof_mut<F: FieldElement>(
+pub fn unpack_proof_mut<F: FieldElementWithInteger>(
     proof: &mut [F],
     dimension: usize,
 ) -> Result<UnpackedProofMut<F>, SerializeError> {
-    // check the share length
-    if proof.len() != proof_length(dimension) {
-        return Err(SerializeError::UnpackInputSizeMismatch);
-    }
-    // split share into components
-    let (data, rest) = proof.split_at_mut(dimension);
-    if let ([f0, g0, h0], points_h_packed) = rest.split_at_mut(3) {
-        Ok(UnpackedProofMut {
-            data,
-            f0,
-            g0,
-            h0,
-            points_h_packed,
-        })
-    } else {
-        Err(SerializeError::UnpackInputSizeMismatch)
-    }
+    ProofLayout::new::<F>(dimension).unpack_mut(proof)
 }
 
+/// The length in bytes of the prefix written by `serialize()`.
+const LENGTH_PREFIX_LEN: usize = 4;
+
 /// Get a byte array from a slice of field elements
+///
+/// The encoding is the one produced by [`encode_u32_items`]: the length of the encoded elements in
+/// bytes as a 4-byte big-endian integer, followed by the elements in order, each as a fixed-length
+/// little-endian integer of `F::BYTES` bytes. This is the TLS
+/// encoding of a vector `F data<0..2^32-1>`, so other Prio implementations can produce and consume
+/// it byte-for-byte, and it can be embedded in messages encoded with the [`codec`](crate::codec)
+/// module.
+///
+/// # Panics
+///
+/// Panics if the encoding of `data` is longer than `u32::MAX` bytes.
 pub fn serialize<F: FieldElement>(data: &[F]) -> Vec<u8> {
-    let mut vec = Vec::<u8>::with_capacity(data.len() * F::BYTES);
-    for elem in data.iter() {
-        elem.append_to(&mut vec);
-    }
-    vec
+    let mut bytes = Vec::with_capacity(LENGTH_PREFIX_LEN + data.len() * F::BYTES);
+    encode_u32_items(&mut bytes, &(), data).expect("too many elements to serialize");
+    bytes
 }
 
-/// Get a vector of field elements from a byte slice
+/// Get a vector of field elements from a byte slice encoded by `serialize()`
 pub fn deserialize<F: FieldElement>(data: &[u8]) -> Result<Vec<F>, SerializeError> {
-    if data.len() % F::BYTES != 0 {
+    let mut out = Vec::new();
+    deserialize_into(data, &mut out)?;
+    Ok(out)
+}
+
+/// Like `deserialize()`, but writes the field elements to `out`, replacing its contents. The
+/// allocation of `out` is reused, so a server that decodes many inputs of the same length only
+/// allocates once. If an error is returned, the contents of `out` are unspecified.
+pub fn deserialize_into<F: FieldElement>(
+    data: &[u8],
+    out: &mut Vec<F>,
+) -> Result<(), SerializeError> {
+    if data.len() < LENGTH_PREFIX_LEN {
+        return Err(SerializeError::LengthPrefixMismatch);
+    }
+    let (prefix, elems) = data.split_at(LENGTH_PREFIX_LEN);
+    if elems.len() % F::BYTES != 0 {
         return Err(SerializeError::IncompleteChunk);
     }
-    let mut vec = Vec::<F>::with_capacity(data.len() / F::BYTES);
-    for chunk in data.chunks_exact(F::BYTES) {
-        vec.push(F::read_from(chunk).or_else(|err| Err(SerializeError::Field(err)))?);
+    let len = u32::get_decoded(prefix).map_err(|_| SerializeError::LengthPrefixMismatch)?;
+    if elems.len() != len as usize {
+        return Err(SerializeError::LengthPrefixMismatch);
     }
-    Ok(vec)
+    out.clear();
+    out.reserve(elems.len() / F::BYTES);
+    for chunk in elems.chunks_exact(F::BYTES) {
+        out.push(F::read_from(chunk)?);
+    }
+    Ok(())
+}
+
+/// Encodes `bytes` in URL-safe base64 without padding.
+#[cfg(feature = "base64-encoding")]
+pub(crate) fn encode_base64_url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
 }
 
-/// Add two field element arrays together elementwise.
+/// Decodes URL-safe base64, with or without padding.
+#[cfg(feature = "base64-encoding")]
+pub(crate) fn decode_base64_url(encoded: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    base64::decode_config(encoded.trim_end_matches('='), base64::URL_SAFE_NO_PAD)
+}
+
+/// Encodes a vector of field elements, e.g., a proof, as the output of `serialize()` in URL-safe
+/// base64 without padding.
+#[cfg(feature = "base64-encoding")]
+pub fn proof_to_base64_url<F: FieldElement>(proof: &[F]) -> String {
+    encode_base64_url(&serialize(proof))
+}
+
+/// Decodes a vector of field elements encoded by `proof_to_base64_url()`. Padding is accepted but
+/// not required.
+#[cfg(feature = "base64-encoding")]
+pub fn proof_from_base64_url<F: FieldElement>(encoded: &str) -> Result<Vec<F>, SerializeError> {
+    deserialize(&decode_base64_url(encoded)?)
+}
+
+/// The version of the header written by `write_header()`.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// The length in bytes of the header written by `write_header()`.
+pub const HEADER_LEN: usize = 6;
+
+/// Appends a header describing the configuration that `bytes` is encoded for: the format version,
+/// the [`ID`](FieldElement::ID) of `F` and the dimension as a 4-byte big-endian
+/// integer.
+///
+/// # Panics
 ///
-/// Returns None, when array lengths are not equal.
-pub fn reconstruct_shares<F: FieldElement>(share1: &[F], share2: &[F]) -> Option<Vec<F>> {
-    if share1.len() != share2.len() {
-        return None;
+/// Panics if `dimension` is larger than `u32::MAX`.
+pub fn write_header<F: FieldElement>(bytes: &mut Vec<u8>, dimension: usize) {
+    let dimension = u32::try_from(dimension).expect("dimension too large to serialize");
+    FORMAT_VERSION.encode(bytes);
+    (F::ID as u8).encode(bytes);
+    dimension.encode(bytes);
+}
+
+/// Checks the header written by `write_header()` against the expected field and dimension and
+/// returns the rest of the input.
+pub fn read_header<F: FieldElement>(
+    bytes: &[u8],
+    dimension: usize,
+) -> Result<&[u8], SerializeError> {
+    if bytes.len() < HEADER_LEN {
+        return Err(SerializeError::IncompleteHeader);
+    }
+    let (header, rest) = bytes.split_at(HEADER_LEN);
+    if header[0] != FORMAT_VERSION {
+        return Err(SerializeError::UnsupportedVersion(header[0]));
+    }
+    if header[1] != F::ID as u8 {
+        return Err(SerializeError::FieldIdMismatch(header[1], F::ID as u8));
+    }
+    let got = u32::get_decoded(&header[2..]).map_err(|_| SerializeError::IncompleteHeader)?;
+    if u32::try_from(dimension) != Ok(got) {
+        return Err(SerializeError::DimensionMismatch(got, dimension as u32));
+    }
+    Ok(rest)
+}
+
+/// Like `serialize()`, but prefixes the output with the header written by `write_header()`, so
+/// that a server configured with a different field or dimension rejects it.
+pub fn serialize_with_header<F: FieldElement>(dimension: usize, data: &[F]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + LENGTH_PREFIX_LEN + data.len() * F::BYTES);
+    write_header::<F>(&mut bytes, dimension);
+    bytes.extend_from_slice(&serialize(data));
+    bytes
+}
+
+/// Get a vector of field elements from a byte slice encoded by `serialize_with_header()`, after
+/// checking that it was encoded for field `F` and the given dimension.
+pub fn deserialize_with_header<F: FieldElement>(
+    dimension: usize,
+    data: &[u8],
+) -> Result<Vec<F>, SerializeError> {
+    deserialize(read_header::<F>(data, dimension)?)
+}
+
+/// Like `deserialize_with_header()`, but writes the field elements to `out` as
+/// `deserialize_into()` does.
+pub fn deserialize_with_header_into<F: FieldElement>(
+    dimension: usize,
+    data: &[u8],
+    out: &mut Vec<F>,
+) -> Result<(), SerializeError> {
+    deserialize_into(read_header::<F>(data, dimension)?, out)
+}
+
+/// Writes `data` to `writer` in the encoding produced by `serialize()`, without buffering the
+/// whole encoding in memory.
+///
+/// # Panics
+///
+/// Panics if the encoding of `data` is longer than `u32::MAX` bytes.
+pub fn write_share<F: FieldElement, W: Write>(writer: &mut W, data: &[F]) -> io::Result<()> {
+    let len = data
+        .len()
+        .checked_mul(F::BYTES)
+        .and_then(|len| u32::try_from(len).ok())
+        .expect("too many elements to serialize");
+    writer.write_all(&len.get_encoded())?;
+    for elem in data {
+        elem.encode_into(writer)?;
+    }
+    Ok(())
+}
+
+/// Reads the next vector of field elements encoded by `serialize()` or `write_share()` from
+/// `reader`, so that a stream of shares can be processed one at a time. Returns `None` if the
+/// reader is at the end of its input.
+///
+/// Elements are validated as they are read, and memory is allocated as elements arrive rather than
+/// up front according to the length prefix, so a corrupted prefix cannot cause a large allocation.
+pub fn read_share<F: FieldElement, R: Read>(
+    reader: &mut R,
+) -> Result<Option<Vec<F>>, SerializeError> {
+    let mut data = Vec::new();
+    Ok(if read_share_into(reader, &mut data)? {
+        Some(data)
+    } else {
+        None
+    })
+}
+
+/// Like `read_share()`, but writes the field elements to `out`, replacing its contents and reusing
+/// its allocation. Returns `false`, leaving `out` empty, if the reader is at the end of its input.
+/// If an error is returned, the contents of `out` are unspecified.
+pub fn read_share_into<F: FieldElement, R: Read>(
+    reader: &mut R,
+    out: &mut Vec<F>,
+) -> Result<bool, SerializeError> {
+    out.clear();
+    let len = match read_length_prefix::<F, R>(reader)? {
+        Some(len) => len,
+        None => return Ok(false),
+    };
+    read_elements_into(reader, len, out)?;
+    Ok(true)
+}
+
+// Reads the length prefix of the next share and returns the number of elements it announces, or
+// returns `None` at the end of the input.
+fn read_length_prefix<F: FieldElement, R: Read>(
+    reader: &mut R,
+) -> Result<Option<usize>, SerializeError> {
+    let mut prefix = [0; LENGTH_PREFIX_LEN];
+    let len = match read_up_to(reader, &mut prefix)? {
+        0 => return Ok(None),
+        LENGTH_PREFIX_LEN => {
+            u32::get_decoded(&prefix).map_err(|_| SerializeError::LengthPrefixMismatch)? as usize
+        }
+        _ => return Err(SerializeError::LengthPrefixMismatch),
+    };
+    if len % F::BYTES != 0 {
+        return Err(SerializeError::IncompleteChunk);
+    }
+    Ok(Some(len / F::BYTES))
+}
+
+// Reads `len` field elements and appends them to `out`.
+fn read_elements_into<F: FieldElement, R: Read>(
+    reader: &mut R,
+    len: usize,
+    out: &mut Vec<F>,
+) -> Result<(), SerializeError> {
+    // Cap the up-front allocation at 1 MiB.
+    out.reserve(min(len, (1 << 20) / F::BYTES));
+    let mut buf = vec![0; F::BYTES];
+    for _ in 0..len {
+        match read_up_to(reader, &mut buf)? {
+            0 => return Err(SerializeError::LengthPrefixMismatch),
+            n if n < buf.len() => return Err(SerializeError::IncompleteChunk),
+            _ => out.push(F::read_from(&buf)?),
+        }
+    }
+    Ok(())
+}
+
+/// Sums a stream of shares encoded by `write_share()`, e.g., the contents of a file that shares
+/// are appended to as they arrive. Only one share is held in memory at a time, so the memory used
+/// depends on the dimension but not on the number of shares.
+#[derive(Clone, Debug)]
+pub struct Accumulator<F: FieldElement> {
+    total: Vec<F>,
+    count: usize,
+    share: Vec<F>,
+}
+
+impl<F: FieldElement> Accumulator<F> {
+    /// Creates an accumulator for shares of `dimension` field elements.
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            total: vec![F::zero(); dimension],
+            count: 0,
+            share: Vec::with_capacity(dimension),
+        }
+    }
+
+    /// Reads the next share from `reader` and adds it to the total. Returns `false` if the reader
+    /// is at the end of its input.
+    ///
+    /// The length prefix is checked against the dimension before the share is read, and the total
+    /// is only updated once the whole share has been read and validated. If an error is returned,
+    /// the total is unchanged, but the position of the reader within the stream is unspecified.
+    pub fn accumulate<R: Read>(&mut self, reader: &mut R) -> Result<bool, SerializeError> {
+        let len = match read_length_prefix::<F, R>(reader)? {
+            Some(len) => len,
+            None => return Ok(false),
+        };
+        if len != self.total.len() {
+            return Err(SerializeError::ShareLengthMismatch(len, self.total.len()));
+        }
+
+        self.share.clear();
+        read_elements_into(reader, len, &mut self.share)?;
+        merge_vector(&mut self.total, &self.share)?;
+        self.count += 1;
+        Ok(true)
+    }
+
+    /// Adds every share from `reader` to the total, until the end of its input. Returns the number
+    /// of shares that were read. If an error is returned, the shares preceding the one that
+    /// failed remain in the total.
+    pub fn accumulate_all<R: Read>(&mut self, reader: &mut R) -> Result<usize, SerializeError> {
+        let mut count = 0;
+        while self.accumulate(reader)? {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns the sum of the shares accumulated so far.
+    pub fn total(&self) -> &[F] {
+        &self.total
     }
 
-    let mut reconstructed: Vec<F> = vec![F::zero(); share1.len()];
+    /// Returns the number of shares accumulated so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Consumes the accumulator, returning the sum of the shares.
+    pub fn into_total(self) -> Vec<F> {
+        self.total
+    }
+}
 
-    for (r, (s1, s2)) in reconstructed
-        .iter_mut()
-        .zip(share1.iter().zip(share2.iter()))
-    {
-        *r = *s1 + *s2;
+// Fills `buf` from `reader`, stopping early only at the end of the input. Returns the number of
+// bytes read.
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Compares two field elements in constant time.
+pub fn ct_eq<F: FieldElement>(a: &F, b: &F) -> bool {
+    bool::from(a.ct_eq(b))
+}
+
+/// Compares two vectors of field elements. The running time depends on the lengths of the inputs
+/// but not on their contents, so it does not reveal at which position they first differ.
+pub fn ct_eq_slice<F: FieldElement>(a: &[F], b: &[F]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let eq = a
+        .iter()
+        .zip(b.iter())
+        .fold(Choice::from(1), |acc, (x, y)| acc & x.ct_eq(y));
+    bool::from(eq)
+}
+
+/// Errors from reconstructing shares
+#[derive(Debug, thiserror::Error)]
+pub enum ReconstructError {
+    /// Emitted by `reconstruct_shares()` if fewer than two shares are given
+    #[error("at least two shares are required")]
+    TooFewShares,
+    /// Emitted by `reconstruct_shares()` if the shares have different lengths
+    #[error("shares have different lengths")]
+    ShareLengthMismatch,
+}
+
+/// Add any number of field element arrays together elementwise.
+///
+/// The shares are consumed one at a time, so they can be streamed from an iterator without
+/// being collected first. Returns an error if fewer than two shares are given or if their lengths
+/// are not equal.
+pub fn reconstruct_shares<F, S, I>(shares: I) -> Result<Vec<F>, ReconstructError>
+where
+    F: FieldElement,
+    S: AsRef<[F]>,
+    I: IntoIterator<Item = S>,
+{
+    let mut shares = shares.into_iter();
+    let mut reconstructed = shares
+        .next()
+        .ok_or(ReconstructError::TooFewShares)?
+        .as_ref()
+        .to_vec();
+
+    let mut count = 1;
+    for share in shares {
+        merge_vector(&mut reconstructed, share.as_ref())
+            .map_err(|_| ReconstructError::ShareLengthMismatch)?;
+        count += 1;
+    }
+    if count < 2 {
+        return Err(ReconstructError::TooFewShares);
     }
 
-    Some(reconstructed)
+    Ok(reconstructed)
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::field::{Field32, Field64};
+    use crate::codec::decode_u32_items;
+    use crate::field::{Field32, Field61, Field64, FieldElementWithInteger};
     use assert_matches::assert_matches;
 
+    /// Decodes a lowercase hex string, e.g., from a published test vector.
+    pub fn from_hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
     pub fn secret_share(share: &mut [Field32]) -> Vec<Field32> {
         use rand::Rng;
         let mut rng = rand::thread_rng();
@@ -168,7 +738,7 @@ pub mod tests {
         rng.fill(&mut random[..]);
 
         for (r, f) in random.iter().zip(share2.iter_mut()) {
-            *f = Field32::from(*r);
+            *f = Field32::from_reduced(*r);
         }
 
         for (f1, f2) in share.iter_mut().zip(share2.iter()) {
@@ -181,29 +751,97 @@ pub mod tests {
     #[test]
     fn test_unpack_share_mut() {
         let dim = 15;
-        let len = proof_length(dim);
+        let len = proof_length::<Field32>(dim);
 
-        let mut share = vec![Field32::from(0); len];
+        let mut share = vec![Field32::from_reduced(0); len];
         let unpacked = unpack_proof_mut(&mut share, dim).unwrap();
-        *unpacked.f0 = Field32::from(12);
+        *unpacked.f0 = Field32::from_reduced(12);
         assert_eq!(share[dim], 12);
 
-        let mut short_share = vec![Field32::from(0); len - 1];
+        let mut short_share = vec![Field32::from_reduced(0); len - 1];
         assert_matches!(
             unpack_proof_mut(&mut short_share, dim),
             Err(SerializeError::UnpackInputSizeMismatch)
         );
     }
 
+    #[test]
+    fn test_proof_layout() {
+        let layout = ProofLayout::new::<Field64>(15);
+        assert_eq!(layout.num_points(), 16);
+        assert_eq!(layout.data_range(), 0..15);
+        assert_eq!(layout.f0_index(), 15);
+        assert_eq!(layout.g0_index(), 16);
+        assert_eq!(layout.h0_index(), 17);
+        assert_eq!(layout.h_points_range(), 18..34);
+        assert_eq!(layout.proof_length(), 34);
+        assert_eq!(proof_length::<Field64>(15), 34);
+
+        let proof: Vec<Field64> = (0..34).map(Field64::from_reduced).collect();
+        let unpacked = layout.unpack(&proof).unwrap();
+        assert_eq!(unpacked.data, &proof[..15]);
+        assert_eq!(*unpacked.f0, proof[15]);
+        assert_eq!(*unpacked.g0, proof[16]);
+        assert_eq!(*unpacked.h0, proof[17]);
+        assert_eq!(unpacked.points_h_packed, &proof[18..]);
+    }
+
+    #[test]
+    fn test_proof_layout_mixed_radix() {
+        // N is the smallest number of the form 2^a * 3^b larger than the dimension for which the
+        // field has roots of unity of order 2N, rather than the next power of two.
+        for (dimension, n) in [(0, 1), (2, 3), (16, 18), (17, 18), (300, 384)].iter() {
+            assert_eq!(ProofLayout::new::<Field32>(*dimension).num_points(), *n);
+            assert_eq!(ProofLayout::new::<Field64>(*dimension).num_points(), *n);
+        }
+        assert_eq!(proof_length::<Field32>(17), 17 + 3 + 18);
+
+        // Field32 only has roots of unity of order 3^2, while Field64 has roots of order 3^3.
+        assert_eq!(ProofLayout::new::<Field32>(26).num_points(), 32);
+        assert_eq!(ProofLayout::new::<Field64>(26).num_points(), 27);
+        assert_eq!(ProofLayout::new::<Field32>(100).num_points(), 128);
+        assert_eq!(ProofLayout::new::<Field64>(100).num_points(), 108);
+
+        // Field61 only has roots of unity of order 2 * 3^2, so N divides 9.
+        assert_eq!(ProofLayout::new::<Field61>(5).num_points(), 9);
+        assert_eq!(ProofDimensions::<Field61>::max_dimension(), 8);
+    }
+
+    #[test]
+    fn test_proof_dimensions() {
+        let dims = ProofDimensions::<Field32>::new(15).unwrap();
+        assert_eq!(dims.dimension(), 15);
+        assert_eq!(dims.proof_length(), proof_length::<Field32>(15));
+        assert_eq!(dims.num_points(), 16);
+        assert_eq!(dims.eval_domain_size(), 32);
+        assert_eq!(dims.layout(), ProofLayout::new::<Field32>(15));
+
+        // Field32 has roots of unity of order 2^20 * 3^2.
+        let max = 9 * (1 << 19) - 1;
+        assert_eq!(ProofDimensions::<Field32>::max_dimension(), max);
+        assert!(ProofDimensions::<Field32>::new(max).is_ok());
+        assert_eq!(
+            ProofDimensions::<Field32>::new(max + 1).unwrap_err(),
+            DimensionError::ExceedsFieldCapacity {
+                dimension: max + 1,
+                max
+            }
+        );
+        assert_eq!(
+            ProofDimensions::<Field64>::new(usize::MAX).unwrap_err(),
+            DimensionError::TooLarge(usize::MAX)
+        );
+    }
+
     #[test]
     fn test_unpack_share() {
         let dim = 15;
-        let len = proof_length(dim);
+        let len = proof_length::<Field64>(dim);
 
-        let share = vec![Field64::from(0); len];
+        let share = vec![Field64::from_reduced(0); len];
         unpack_proof(&share, dim).unwrap();
 
-        let short_share = vec![Field64::from(0); len - 1];
+        let short_share = vec![Field64::from_reduced(0); len - 1];
         assert_matches!(
             unpack_proof(&short_share, dim),
             Err(SerializeError::UnpackInputSizeMismatch)
@@ -213,22 +851,230 @@ pub mod tests {
     #[test]
     fn secret_sharing() {
         let mut share1 = vec![Field32::zero(); 10];
-        share1[3] = 21.into();
-        share1[8] = 123.into();
+        share1[3] = Field32::from_reduced(21);
+        share1[8] = Field32::from_reduced(123);
 
         let original_data = share1.clone();
 
         let share2 = secret_share(&mut share1);
 
-        let reconstructed = reconstruct_shares(&share1, &share2).unwrap();
+        let reconstructed = reconstruct_shares([&share1, &share2]).unwrap();
         assert_eq!(reconstructed, original_data);
     }
 
+    #[test]
+    #[cfg(feature = "base64-encoding")]
+    fn base64_url_encoding() {
+        // 0x3e and 0x3f encode to `-` and `_`, which differ between the standard and URL-safe
+        // alphabets.
+        assert_eq!(encode_base64_url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_base64_url("-_8").unwrap(), [0xfb, 0xff]);
+        assert_eq!(decode_base64_url("-_8=").unwrap(), [0xfb, 0xff]);
+        assert!(decode_base64_url("+/8").is_err());
+
+        let proof = vec![Field64::from(1), Field64::from(2)];
+        let encoded = proof_to_base64_url(&proof);
+        assert!(!encoded.ends_with('='));
+        assert_eq!(proof_from_base64_url::<Field64>(&encoded).unwrap(), proof);
+        assert_matches!(
+            proof_from_base64_url::<Field64>("!"),
+            Err(SerializeError::DecodeBase64(_))
+        );
+    }
+
+    #[test]
+    fn deserialization_into_buffer() {
+        let share1 = vec![Field64::from(1), Field64::from(2), Field64::from(3)];
+        let share2 = vec![Field64::from(4), Field64::from(5), Field64::from(6)];
+
+        let mut out: Vec<Field64> = Vec::new();
+        deserialize_into(&serialize(&share1), &mut out).unwrap();
+        assert_eq!(out, share1);
+        let capacity = out.capacity();
+        deserialize_into(&serialize(&share2), &mut out).unwrap();
+        assert_eq!(out, share2);
+        assert_eq!(out.capacity(), capacity);
+
+        deserialize_with_header_into(3, &serialize_with_header(3, &share1), &mut out).unwrap();
+        assert_eq!(out, share1);
+
+        let mut writer = Vec::new();
+        write_share(&mut writer, &share2).unwrap();
+        let mut reader = std::io::Cursor::new(&writer);
+        assert!(read_share_into(&mut reader, &mut out).unwrap());
+        assert_eq!(out, share2);
+        assert!(!read_share_into(&mut reader, &mut out).unwrap());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn serialization_with_header() {
+        let data = vec![Field64::from(1), Field64::from(2)];
+        let bytes = serialize_with_header(7, &data);
+        assert_eq!(
+            bytes[..HEADER_LEN],
+            [FORMAT_VERSION, Field64::ID as u8, 0, 0, 0, 7]
+        );
+        assert_eq!(bytes[HEADER_LEN..], serialize(&data)[..]);
+        assert_eq!(deserialize_with_header::<Field64>(7, &bytes).unwrap(), data);
+
+        assert_matches!(
+            deserialize_with_header::<Field64>(8, &bytes),
+            Err(SerializeError::DimensionMismatch(7, 8))
+        );
+        assert_matches!(
+            deserialize_with_header::<Field61>(7, &bytes),
+            Err(SerializeError::FieldIdMismatch(id, _)) if id == Field64::ID as u8
+        );
+        assert_matches!(
+            deserialize_with_header::<Field64>(7, &bytes[..HEADER_LEN - 1]),
+            Err(SerializeError::IncompleteHeader)
+        );
+
+        let mut bad_version = bytes;
+        bad_version[0] = 2;
+        assert_matches!(
+            deserialize_with_header::<Field64>(7, &bad_version),
+            Err(SerializeError::UnsupportedVersion(2))
+        );
+    }
+
+    #[test]
+    fn streaming_serialization() {
+        let share1 = vec![Field64::from(1), Field64::from(2), Field64::from(3)];
+        let share2 = vec![Field64::from(4)];
+
+        let mut writer = Vec::new();
+        write_share(&mut writer, &share1).unwrap();
+        write_share(&mut writer, &share2).unwrap();
+        write_share::<Field64, _>(&mut writer, &[]).unwrap();
+        assert_eq!(writer[..writer.len() - 16], serialize(&share1)[..]);
+
+        let mut reader = std::io::Cursor::new(&writer);
+        assert_eq!(read_share(&mut reader).unwrap(), Some(share1));
+        assert_eq!(read_share(&mut reader).unwrap(), Some(share2));
+        assert_eq!(read_share::<Field64, _>(&mut reader).unwrap(), Some(vec![]));
+        assert_eq!(read_share::<Field64, _>(&mut reader).unwrap(), None);
+
+        // Truncated prefix, element and vector.
+        assert_matches!(
+            read_share::<Field64, _>(&mut &writer[..2]),
+            Err(SerializeError::LengthPrefixMismatch)
+        );
+        assert_matches!(
+            read_share::<Field64, _>(&mut &writer[..7]),
+            Err(SerializeError::IncompleteChunk)
+        );
+        assert_matches!(
+            read_share::<Field64, _>(&mut &writer[..12]),
+            Err(SerializeError::LengthPrefixMismatch)
+        );
+
+        // An element that exceeds the modulus.
+        let mut bad = serialize(&[Field64::from(1)]);
+        bad[4..].copy_from_slice(&[0xff; 8]);
+        assert_matches!(
+            read_share::<Field64, _>(&mut &bad[..]),
+            Err(SerializeError::Field(FieldError::FromBytesModulusOverflow))
+        );
+    }
+
+    #[test]
+    fn streaming_accumulation() {
+        let shares = [
+            vec![Field64::from(1), Field64::from(2)],
+            vec![Field64::from(3), Field64::from(4)],
+            vec![Field64::from(5), Field64::from(6)],
+        ];
+        let mut stream = Vec::new();
+        for share in shares.iter() {
+            write_share(&mut stream, share).unwrap();
+        }
+
+        let mut accumulator = Accumulator::<Field64>::new(2);
+        assert_eq!(accumulator.accumulate_all(&mut &stream[..]).unwrap(), 3);
+        assert_eq!(accumulator.count(), 3);
+        assert_eq!(accumulator.total(), [Field64::from(9), Field64::from(12)]);
+
+        // A share of the wrong length is rejected without reading it, and a truncated share is
+        // rejected without changing the total.
+        write_share(&mut stream, &[Field64::from(1)]).unwrap();
+        let mut reader = &stream[stream.len() - 12..];
+        assert_matches!(
+            accumulator.accumulate(&mut reader),
+            Err(SerializeError::ShareLengthMismatch(1, 2))
+        );
+        assert_matches!(
+            accumulator.accumulate(&mut &stream[..10]),
+            Err(SerializeError::IncompleteChunk)
+        );
+        assert_eq!(accumulator.count(), 3);
+        assert_eq!(
+            accumulator.into_total(),
+            [Field64::from(9), Field64::from(12)]
+        );
+    }
+
+    #[test]
+    fn constant_time_comparison() {
+        let a = [Field64::from(1), Field64::from(2)];
+        let b = [Field64::from(1), Field64::from(3)];
+        assert!(ct_eq(&a[0], &b[0]));
+        assert!(!ct_eq(&a[1], &b[1]));
+        assert!(ct_eq_slice(&a, &a));
+        assert!(!ct_eq_slice(&a, &b));
+        assert!(!ct_eq_slice(&a, &a[..1]));
+        assert!(ct_eq_slice::<Field64>(&[], &[]));
+    }
+
+    #[test]
+    fn reconstruct_many_shares() {
+        let data = vec![Field64::from(3), Field64::from(5)];
+        let shares = crate::field::split(&data, 4).unwrap();
+        assert_eq!(reconstruct_shares(shares.iter()).unwrap(), data);
+        assert_eq!(reconstruct_shares(shares).unwrap(), data);
+
+        assert_matches!(
+            reconstruct_shares::<Field64, &[Field64], _>(vec![]),
+            Err(ReconstructError::TooFewShares)
+        );
+        assert_matches!(
+            reconstruct_shares([&data]),
+            Err(ReconstructError::TooFewShares)
+        );
+        assert_matches!(
+            reconstruct_shares([&data[..], &data[..1]]),
+            Err(ReconstructError::ShareLengthMismatch)
+        );
+    }
+
     #[test]
     fn serialization() {
-        let field = [Field32::from(1), Field32::from(0x99997)];
+        let field = [Field32::from_reduced(1), Field32::from_reduced(0x99997)];
         let bytes = serialize(&field);
         let field_deserialized = deserialize::<Field32>(&bytes).unwrap();
         assert_eq!(field_deserialized, field);
+
+        // The encoding is fixed: a big-endian length in bytes followed by little-endian elements,
+        // as encoded by the codec module.
+        assert_eq!(bytes, [0, 0, 0, 8, 1, 0, 0, 0, 0x97, 0x99, 0x09, 0]);
+        assert_eq!(serialize::<Field32>(&[]), [0, 0, 0, 0]);
+        assert_eq!(
+            decode_u32_items::<_, Field32>(&(), &mut std::io::Cursor::new(&bytes[..])).unwrap(),
+            field
+        );
+
+        assert_matches!(
+            deserialize::<Field32>(&bytes[..3]),
+            Err(SerializeError::LengthPrefixMismatch)
+        );
+        assert_matches!(
+            deserialize::<Field32>(&bytes[..bytes.len() - 1]),
+            Err(SerializeError::IncompleteChunk)
+        );
+        assert_matches!(
+            deserialize::<Field32>(&bytes[..bytes.len() - 4]),
+            Err(SerializeError::LengthPrefixMismatch)
+        );
     }
 }
@@ -3,7 +3,7 @@
 
 use prio::client::*;
 use prio::encrypt::*;
-use prio::field::Field32;
+use prio::field::{decode_aggregate, Field32, FieldElementWithInteger};
 use prio::server::*;
 
 #[test]
@@ -36,7 +36,7 @@ fn accumulation() {
     for _ in 0..number_of_clients {
         // some random data
         let data = (0..dim)
-            .map(|_| Field32::from(rng.gen_range(0, 2)))
+            .map(|_| Field32::from_reduced(rng.gen_range(0, 2)))
             .collect::<Vec<Field32>>();
 
         // update reference count
@@ -62,6 +62,132 @@ fn accumulation() {
     let total1 = server1.total_shares();
     let total2 = server2.total_shares();
 
-    let reconstructed = prio::util::reconstruct_shares(total1, total2).unwrap();
+    let reconstructed = prio::util::reconstruct_shares([total1, total2]).unwrap();
     assert_eq!(reconstructed, reference_count);
+
+    // Each count is at most the number of clients.
+    let counts = decode_aggregate(&reconstructed, number_of_clients).unwrap();
+    assert_eq!(counts, reference_count);
+}
+
+#[test]
+fn accumulation_three_servers() {
+    let dim = 17;
+    let priv_keys = [
+        "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9Rq\
+         Zx3XMkdEHBHfNuCSMpOwofVSq3TfyKwn0NrftKisKKVSaTOt5seJ67P5QL4hxgPWvxw==",
+        "BNNOqoU54GPo+1gTPv+hCgA9U2ZCKd76yOMrWa1xTWgeb4LhFLMQIQoRwDVaW64g\
+         /WTdcxT4rDULoycUNFB60LER6hPEHg/ObBnRPV1rwS3nj9Bj0tbjVPPyL9p8QW8B+w==",
+        "BBBJF6ZQ7zgTR5rQ/AlmRBNc7DgdjCEWSsxhj1RWZ1fpRGm9so4ze/yXworbVTsS\
+         n5Mby2KFWuURrswzPYGEALKYcTvU05UmHZv2EBKeuJrjxpTPiSYihUpu0ocCAUHEgQ==",
+    ]
+    .iter()
+    .map(|key| PrivateKey::from_base64(key).unwrap())
+    .collect::<Vec<_>>();
+
+    let mut client_mem =
+        Client::with_public_keys(dim, priv_keys.iter().map(PublicKey::from).collect()).unwrap();
+    let mut servers = priv_keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| Server::<Field32>::new(dim, i == 0, key).unwrap())
+        .collect::<Vec<_>>();
+
+    let mut reference_count = vec![0u32; dim];
+    for k in 0..10u32 {
+        let data = (0..dim as u32)
+            .map(|i| Field32::from_reduced((i + k) % 2))
+            .collect::<Vec<Field32>>();
+        for (r, d) in reference_count.iter_mut().zip(data.iter()) {
+            *r += u32::from(*d);
+        }
+
+        let shares = client_mem.encode_shares(&data).unwrap();
+        let eval_at = servers[0].choose_eval_at();
+        let messages = servers
+            .iter_mut()
+            .zip(shares.iter())
+            .map(|(server, share)| {
+                server
+                    .generate_verification_message(eval_at, share)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Each server holds one share and contributes one verification message.
+        assert_eq!(shares.len(), 3);
+        assert!(is_valid_shares(&messages));
+        for (server, share) in servers.iter_mut().zip(shares.iter()) {
+            assert!(server.aggregate_with_messages(share, &messages).unwrap());
+        }
+    }
+
+    // An invalid input is rejected by all servers.
+    let shares = client_mem
+        .encode_shares(&vec![Field32::from_reduced(2); dim])
+        .unwrap();
+    let eval_at = servers[0].choose_eval_at();
+    let messages = servers
+        .iter_mut()
+        .zip(shares.iter())
+        .map(|(server, share)| {
+            server
+                .generate_verification_message(eval_at, share)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for (server, share) in servers.iter_mut().zip(shares.iter()) {
+        assert!(!server.aggregate_with_messages(share, &messages).unwrap());
+    }
+
+    let reconstructed =
+        prio::util::reconstruct_shares(servers.iter().map(|server| server.total_shares())).unwrap();
+    assert_eq!(reconstructed, reference_count);
+}
+
+#[test]
+fn mismatched_configuration() {
+    let priv_key1 = PrivateKey::from_base64(
+        "BIl6j+J6dYttxALdjISDv6ZI4/VWVEhUzaS05LgrsfswmbLOgNt9HUC2E0w+9Rq\
+         Zx3XMkdEHBHfNuCSMpOwofVSq3TfyKwn0NrftKisKKVSaTOt5seJ67P5QL4hxgPWvxw==",
+    )
+    .unwrap();
+    let priv_key2 = PrivateKey::from_base64(
+        "BNNOqoU54GPo+1gTPv+hCgA9U2ZCKd76yOMrWa1xTWgeb4LhFLMQIQoRwDVaW64g\
+         /WTdcxT4rDULoycUNFB60LER6hPEHg/ObBnRPV1rwS3nj9Bj0tbjVPPyL9p8QW8B+w==",
+    )
+    .unwrap();
+
+    let mut client_mem: Client<Field32> =
+        Client::new(8, PublicKey::from(&priv_key1), PublicKey::from(&priv_key2)).unwrap();
+    let (share1, share2) = client_mem
+        .encode_simple(&[Field32::from_reduced(0); 8])
+        .unwrap();
+
+    // Both servers reject shares encoded for a different dimension.
+    let mut server1: Server<Field32> = Server::new(9, true, priv_key1.clone()).unwrap();
+    let mut server2: Server<Field32> = Server::new(9, false, priv_key2).unwrap();
+    let eval_at = server1.choose_eval_at();
+    assert!(matches!(
+        server1.generate_verification_message(eval_at, &share1),
+        Err(ServerError::Serialize(
+            prio::util::SerializeError::DimensionMismatch(8, 9)
+        ))
+    ));
+    assert!(matches!(
+        server2.generate_verification_message(eval_at, &share2),
+        Err(ServerError::Serialize(
+            prio::util::SerializeError::DimensionMismatch(8, 9)
+        ))
+    ));
+
+    // The first server rejects shares encoded for a different field.
+    let mut server1: Server<prio::field::Field64> = Server::new(8, true, priv_key1).unwrap();
+    let eval_at = server1.choose_eval_at();
+    assert!(matches!(
+        server1.generate_verification_message(eval_at, &share1),
+        Err(ServerError::Serialize(
+            prio::util::SerializeError::FieldIdMismatch(..)
+        ))
+    ));
 }
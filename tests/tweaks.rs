@@ -4,9 +4,9 @@
 use prio::{
     client::Client,
     encrypt::{decrypt_share, encrypt_share, PrivateKey, PublicKey},
-    field::{Field32, FieldElement},
+    field::{Field32, FieldElement, FieldElementWithInteger},
     server::Server,
-    util::{deserialize, serialize, unpack_proof_mut},
+    util::{deserialize_with_header, serialize_with_header, unpack_proof_mut},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -53,16 +53,16 @@ fn tweaks(tweak: Tweak) {
     let mut data = vec![Field32::zero(); dim];
 
     if let Tweak::WrongInput = tweak {
-        data[0] = Field32::from(2);
+        data[0] = Field32::from_reduced(2);
     }
 
     let (share1_original, share2) = client_mem.encode_simple(&data).unwrap();
 
     let decrypted_share1 = decrypt_share(&share1_original, &priv_key1_clone).unwrap();
-    let mut share1_field: Vec<Field32> = deserialize(&decrypted_share1).unwrap();
+    let mut share1_field: Vec<Field32> = deserialize_with_header(dim, &decrypted_share1).unwrap();
     let unpacked_share1 = unpack_proof_mut(&mut share1_field, dim).unwrap();
 
-    let one = Field32::from(1);
+    let one = Field32::from_reduced(1);
 
     match tweak {
         Tweak::DataPartOfShare => unpacked_share1.data[0] += one,
@@ -74,7 +74,8 @@ fn tweaks(tweak: Tweak) {
     };
 
     // reserialize altered share1
-    let share1_modified = encrypt_share(&serialize(&share1_field), &pub_key1_clone).unwrap();
+    let share1_modified =
+        encrypt_share(&serialize_with_header(dim, &share1_field), &pub_key1_clone).unwrap();
 
     let eval_at = server1.choose_eval_at();
 